@@ -2,7 +2,6 @@
 #![no_main]
 #![feature(default_alloc_error_handler)]
 
-use cortex_m::singleton;
 use executor::tasks;
 use general::AsyncSerial;
 use hal::delay::Delay;
@@ -21,10 +20,11 @@ use crate::rt::entry;
 use crate::rt::ExceptionFrame;
 use hal::stm32::interrupt;
 
-static SerialTxNotifier: utils::serial::SerialNotifier<utils::serial::Tx2Key> =
-    utils::serial::SerialNotifier::<utils::serial::Tx2Key>::new();
-static SerialRxNotifier: utils::serial::SerialNotifier<utils::serial::Rx2Key> =
-    utils::serial::SerialNotifier::<utils::serial::Rx2Key>::new();
+utils::serial_interrupts!(USART2 => { tx: DMA1_CH7, rx: DMA1_CH6 });
+
+static WAKERS: executor::Wakers<2> = executor::Wakers::new();
+
+static FRAMES: utils::serial::FramePool<256, 2> = utils::serial::FramePool::new();
 
 static TIMER: utils::timer::fixed_size::TimerWheel<
     utils::timer::fixed_size::LevelOneWheel,
@@ -97,19 +97,15 @@ fn main() -> ! {
         hal::serial::Serial::usart2(dp.USART2, (tx, rx), serial_conf, clocks, &mut rcc.apb1r1);
 
     let aserial = {
-        let rx1 =
-            singleton!(: stm32l4xx_hal::dma::DMAFrame<256> = stm32l4xx_hal::dma::DMAFrame::new())
-                .unwrap();
-        let tx1 =
-            singleton!(: stm32l4xx_hal::dma::DMAFrame<256> = stm32l4xx_hal::dma::DMAFrame::new())
-                .unwrap();
+        let rx1 = FRAMES.take().unwrap();
+        let tx1 = FRAMES.take().unwrap();
         let (tx, rx) = serial.split();
         utils::serial::Serial::<utils::serial::USART2>::new(
             tx,
             rx,
             (channels.7, channels.6),
             (tx1, rx1),
-            (&SerialTxNotifier, &SerialRxNotifier),
+            (&USART2::TX, &USART2::RX),
         )
     };
 
@@ -137,7 +133,7 @@ fn main() -> ! {
 
     let extension_task = extension.run(
         || [],
-        |_| {},
+        |option| option.value,
         &[],
         |serial| {
             let rx1 =
@@ -149,14 +145,15 @@ fn main() -> ! {
             let tx =
             singleton!(: stm32l4xx_hal::dma::DMAFrame<256> = stm32l4xx_hal::dma::DMAFrame::new())
                 .unwrap();
-            utils::Serial::new(serial, (channels.7, channels.6), (tx, rx1, rx2), (&SerialTxNotifier, &SerialRxNotifier))
+            utils::Serial::new(serial, (channels.7, channels.6), (tx, rx1, rx2), (&USART2::TX, &USART2::RX))
         },
+        |_, _| {},
     );
     */
 
     tasks!(task_list, (send(aserial), ext_task), (other(led), test));
 
-    let runtime = executor::Runtime::new(task_list);
+    let runtime = executor::Runtime::new(task_list, &WAKERS);
     runtime.run();
 }
 
@@ -197,15 +194,6 @@ unsafe fn HardFault(ef: &ExceptionFrame) -> ! {
     panic!("{:#?}", ef);
 }
 
-#[interrupt]
-fn DMA1_CH7() {
-    SerialTxNotifier.transfer_complete();
-}
-#[interrupt]
-fn DMA1_CH6() {
-    SerialRxNotifier.transfer_complete();
-}
-
 #[interrupt]
 fn TIM3() {
     TIMER.tick();
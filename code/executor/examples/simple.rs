@@ -1,4 +1,4 @@
-use executor::{tasks, Runtime, StaticList};
+use executor::{tasks, Runtime, StaticList, Wakers};
 
 async fn first() {}
 
@@ -6,6 +6,8 @@ async fn second() {}
 
 async fn third() {}
 
+static WAKERS: Wakers<3> = Wakers::new();
+
 fn main() {
     tasks!(list, (first(), f), (second(), s), (third(), t));
 
@@ -16,6 +18,6 @@ fn main() {
     assert!(list.get(2).is_some());
     assert!(list.get(3).is_none());
 
-    let runtime = Runtime::new(list);
+    let runtime = Runtime::new(list, &WAKERS);
     runtime.run();
 }
@@ -0,0 +1,165 @@
+use core::{
+    future::Future,
+    pin::pin,
+    sync::atomic::{self, AtomicBool},
+    task::{Context, Poll},
+};
+
+use crate::waking::{self, InternalWaker};
+
+/// The Waker-State used by [`block_on`].
+///
+/// This is a `static` instead of a local, because the Future can clone its Waker and store it
+/// anywhere, so the Waker could otherwise still be woken after `block_on` returned.
+static WAKER: InternalWaker = InternalWaker::new();
+/// Whether the [`WAKER`] is currently used by a call to [`block_on`]
+static CLAIMED: AtomicBool = AtomicBool::new(false);
+
+/// Releases the [`WAKER`] again, even if the Future panicked
+struct Claim;
+
+impl Claim {
+    fn acquire() -> Option<Self> {
+        if CLAIMED.swap(true, atomic::Ordering::SeqCst) {
+            return None;
+        }
+        Some(Self)
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        CLAIMED.store(false, atomic::Ordering::SeqCst);
+    }
+}
+
+/// Drives the Future to completion on the current thread and returns its Output, which is useful
+/// for running async code, like the initialization, before the [`Runtime`](crate::Runtime)
+/// is started.
+///
+/// The Future is pinned on the Stack and only polled again, once it was woken, spinning in the
+/// mean time.
+///
+/// # Nesting
+/// There is only a single Waker-State shared by all calls, so if `block_on` is called while
+/// another call is still running, like from an interrupt-handler or another thread, the inner
+/// call simply polls its Future continuously instead of waiting for it to be woken.
+///
+/// # Example
+/// ```rust
+/// # use executor::block_on;
+/// async fn init() -> u8 {
+///     13
+/// }
+///
+/// assert_eq!(13, block_on(init()));
+/// ```
+pub fn block_on<F>(fut: F) -> F::Output
+where
+    F: Future,
+{
+    let mut fut = pin!(fut);
+
+    let claim = Claim::acquire();
+
+    let waker = waking::create_waker(&WAKER);
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        if claim.is_some() {
+            WAKER.set_ready(false);
+        }
+
+        if let Poll::Ready(output) = fut.as_mut().poll(&mut context) {
+            return output;
+        }
+
+        while claim.is_some() && !WAKER.is_ready() {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::pin::Pin;
+
+    use super::*;
+
+    /// A Future, that is pending on the first poll, but wakes itself right away
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = u8;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.0 {
+                return Poll::Ready(2);
+            }
+
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn ready_future() {
+        assert_eq!(1, block_on(async { 1 }));
+    }
+
+    #[test]
+    fn yield_once() {
+        assert_eq!(2, block_on(YieldOnce(false)));
+    }
+
+    #[test]
+    fn woken_from_thread() {
+        use std::sync::{Arc, Mutex};
+
+        /// A Future, that completes once the Flag was set and hands its Waker to the other
+        /// thread
+        struct Flag {
+            state: Arc<Mutex<FlagState>>,
+        }
+        type FlagState = (bool, Option<core::task::Waker>);
+
+        impl Future for Flag {
+            type Output = ();
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                let mut state = self.state.lock().unwrap();
+                if state.0 {
+                    return Poll::Ready(());
+                }
+
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+
+        let state: Arc<Mutex<FlagState>> = Arc::new(Mutex::new((false, None)));
+
+        let setter_state = state.clone();
+        let setter = std::thread::spawn(move || loop {
+            let mut state = setter_state.lock().unwrap();
+            if let Some(waker) = state.1.take() {
+                state.0 = true;
+                waker.wake();
+                return;
+            }
+
+            drop(state);
+            std::thread::yield_now();
+        });
+
+        block_on(Flag { state });
+
+        setter.join().unwrap();
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(3, block_on(async { block_on(YieldOnce(false)) + 1 }));
+    }
+}
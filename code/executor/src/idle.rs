@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+/// What the [`Runtime`](crate::Runtime) does after a pass, in which no Task was polled.
+///
+/// On the embedded Targets the Runtime simply spins, but on a host this pegs a CPU core at 100%
+/// for no reason, which makes running the Simulator unpleasant. This is only available with the
+/// `host-sim` feature enabled and has no effect on the embedded builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdleBackoff {
+    /// Immediately start the next pass, like on the embedded Targets
+    Spin,
+    /// Yield the current thread to the OS, before starting the next pass
+    #[default]
+    Yield,
+    /// Sleep for the given Duration, before starting the next pass
+    Sleep(Duration),
+}
+
+impl IdleBackoff {
+    /// Performs the Backoff for a single idle pass
+    pub(crate) fn wait(&self) {
+        match self {
+            Self::Spin => core::hint::spin_loop(),
+            Self::Yield => std::thread::yield_now(),
+            Self::Sleep(duration) => std::thread::sleep(*duration),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn sleep_waits() {
+        let start = Instant::now();
+        IdleBackoff::Sleep(Duration::from_millis(5)).wait();
+        assert!(start.elapsed() >= Duration::from_millis(5));
+    }
+
+    #[test]
+    fn default_yields() {
+        assert_eq!(IdleBackoff::Yield, IdleBackoff::default());
+
+        // Neither of these block
+        IdleBackoff::Spin.wait();
+        IdleBackoff::Yield.wait();
+    }
+}
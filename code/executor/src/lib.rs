@@ -7,20 +7,29 @@
 //!
 //! # Example
 //! ```rust,no_run
-//! # use executor::{tasks, Runtime};
+//! # use executor::{tasks, Runtime, Wakers};
 //! async fn first() {}
 //! async fn second() {}
 //!
+//! static WAKERS: Wakers<2> = Wakers::new();
+//!
 //! tasks!(list, (first(), first_task), (second(), second_task));
 //!
-//! let runtime = Runtime::new(list);
+//! let runtime = Runtime::new(list, &WAKERS);
 //! runtime.run();
 //! ```
-#![cfg_attr(not(test), no_std)]
+//!
+//! # Host Simulation
+//! With the `host-sim` feature enabled, the crate links `std` and the Runtime backs off in
+//! passes where no Task was ready, see [`IdleBackoff`]. This is only intended for host builds,
+//! like tests and the Simulator, and must never be enabled for the embedded Targets.
+#![cfg_attr(not(any(test, feature = "host-sim")), no_std)]
 #![feature(generic_const_exprs)]
 
 use core::{
     array,
+    future::Future,
+    pin::Pin,
     task::{Context, Poll},
 };
 
@@ -31,68 +40,1130 @@ mod tasklist;
 pub use tasklist::*;
 
 mod waking;
+pub use waking::{InternalWaker, Wakers};
+
+mod blocking;
+pub use blocking::block_on;
+
+mod restart;
+pub use restart::Restart;
+
+#[cfg(feature = "host-sim")]
+mod idle;
+#[cfg(feature = "host-sim")]
+pub use idle::IdleBackoff;
+
+/// The maximum number of Tasks a single [`Runtime`] can hold.
+///
+/// Every appended Task nests the Type of the Task-List one level deeper and the Runtime keeps a
+/// few arrays with an entry for every Task on the stack, so very large Task-Lists blow up the
+/// compile times and can overflow the stack at runtime. Exceeding this limit is rejected, when
+/// creating the Runtime, at compile time instead.
+///
+/// # Recursion Limit
+/// With the default `recursion_limit` of the compiler, a List of more than 64 Tasks already fails
+/// to compile because the nested Type gets too deep. Raising the `recursion_limit` does not get
+/// around this limit.
+///
+/// # Example
+/// ```rust,compile_fail
+/// #![recursion_limit = "256"]
+/// # use executor::{Runtime, Task, Wakers};
+/// async fn task() {}
+///
+/// macro_rules! append {
+///     ($list:expr, $futs:ident;) => { $list };
+///     ($list:expr, $futs:ident; $_t:tt $($rest:tt)*) => {
+///         append!($list.append(Task::new($futs.next().unwrap())), $futs; $($rest)*)
+///     };
+/// }
+///
+/// static WAKERS: Wakers<65> = Wakers::new();
+///
+/// let mut futures: [_; 65] = core::array::from_fn(|_| task());
+/// let mut futs = futures.iter_mut();
+///
+/// // Appends another 64 Tasks to the first one
+/// let list = append!(
+///     Task::new(futs.next().unwrap()), futs;
+///     x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+///     x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x x
+/// );
+///
+/// let runtime = Runtime::new(list, &WAKERS);
+/// ```
+pub const MAX_TASKS: usize = 64;
 
 /// An async Runtime for a no_std environment, which does not perform any dynamic memory allocation.
 ///
 /// This runtime only handles a fixed number of async Tasks, that are known at compile-time and
 /// does not support dynamically starting/spawning new Tasks.
+///
+/// # Wakers
+/// The Wakers for the Tasks are stored in a `'static` [`Wakers`] Storage, which means that a Task
+/// can clone its Waker and hand it to anything else, like an interrupt-handler, without having to
+/// worry about the Runtime going away.
+///
+/// # Priorities
+/// Every Task has a priority, which can be set using [`Runtime::set_priority`]. In every pass,
+/// the ready Tasks with a higher priority are polled before the ready Tasks with a lower
+/// priority. Lower priority Tasks are still polled in every pass, just later, so they can't be
+/// starved by higher priority Tasks.
+///
+/// # Task Lookup
+/// The Tasks are stored in a recursive [`StaticList`], where looking up a Task walks the List one
+/// Node at a time. To avoid doing this for every Task on every pass, the Runtime walks the List
+/// once when it is created and caches a pointer to the Future of every Task.
+///
+/// ```rust,compile_fail
+/// # use executor::{tasks, Runtime, Wakers};
+/// async fn first() {}
+/// async fn second() {}
+///
+/// let wakers = Wakers::<2>::new();
+///
+/// tasks!(list, (first(), first_task), (second(), second_task));
+///
+/// // The Wakers need to be 'static
+/// let runtime = Runtime::new(list, &wakers);
+/// ```
 pub struct Runtime<'f, T, const L: usize> {
     metadata: [TaskMetadata; L],
-    wakers: [waking::InternalWaker; L],
+    /// The ids of the Tasks in the order they should be polled in
+    order: [usize; L],
+    wakers: &'static Wakers<L>,
+    /// Pointers to the Futures of the Tasks, indexed by their id.
+    ///
+    /// The Futures are borrowed by the Task-List for `'f` and are not moved with the Runtime, so
+    /// the pointers stay valid for as long as the Runtime exists.
+    futures: [*mut (dyn Future<Output = ()> + 'f); L],
+    /// Keeps the Task-List, and with that the borrows of the Futures, alive
+    #[allow(dead_code)]
     tasks: Task<'f, T, L>,
+    /// Measures the Duration of every pass, if a Clock was installed
+    timing: Option<PassTiming<'f>>,
+    /// Detects passes, in which the Tasks make no progress, if a Guard was installed
+    livelock: Option<LivelockGuard<'f>>,
+    /// The Factories of the Tasks installed using [`with_restart`](Runtime::with_restart),
+    /// indexed by their id
+    factories: [Option<TaskFactory<'f>>; L],
+    /// What to do after a pass, in which no Task was polled
+    #[cfg(feature = "host-sim")]
+    idle: IdleBackoff,
+}
+
+/// Creates a new Future for a Task, once its previous Future completed, see
+/// [`Runtime::with_restart`]
+type TaskFactory<'f> = &'f mut dyn FnMut() -> Pin<&'f mut dyn Future<Output = ()>>;
+
+/// The Clock used to measure the Duration of the passes and the Durations measured so far
+struct PassTiming<'f> {
+    now: &'f dyn Fn() -> u32,
+    last: u32,
+    max: u32,
+}
+
+/// Counts the consecutive passes without any progress, see [`Runtime::with_livelock_guard`]
+struct LivelockGuard<'f> {
+    limit: usize,
+    stuck: usize,
+    on_stuck: &'f dyn Fn(),
 }
 
-struct TaskMetadata {
+/// The Scheduling information of a single Task, which can be prepared in a const context and
+/// passed to [`Runtime::from_parts`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskMetadata {
     done: bool,
     id: usize,
+    priority: u8,
+    /// How often the Task was polled so far
+    polls: u32,
+}
+
+impl TaskMetadata {
+    /// The Metadata for the Task with the given id, which is not done and has a priority of 0
+    pub const fn new(id: usize) -> Self {
+        Self {
+            done: false,
+            id,
+            priority: 0,
+            polls: 0,
+        }
+    }
+
+    /// Sets the priority of the Task, see [`Runtime::set_priority`]
+    pub const fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// The default Metadata for `L` Tasks, where the Metadata for every Task is stored at the
+    /// index of its id
+    pub const fn defaults<const L: usize>() -> [Self; L] {
+        let mut metadata = [Self::new(0); L];
+
+        let mut id = 0;
+        while id < L {
+            metadata[id] = Self::new(id);
+            id += 1;
+        }
+
+        metadata
+    }
+}
+
+/// The current State of a single Task, as returned by [`Runtime::snapshot`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TaskSnapshot {
+    /// The id of the Task
+    pub id: usize,
+    /// Whether the Future of the Task has completed
+    pub done: bool,
+    /// How often the Task was polled so far, including any Futures it was running before being
+    /// replaced using [`Runtime::replace_task`]. This wraps around on overflow.
+    pub polls: u32,
+    /// Whether the Task is currently woken and will be polled in the next pass, unless it is
+    /// already done
+    pub ready: bool,
 }
 
 impl<'f, T, const L: usize> Runtime<'f, T, L>
 where
     T: TaskList<'f>,
 {
-    /// Creates a new Runtime for the List of Tasks
-    pub fn new(tasks: Task<'f, T, L>) -> Self {
-        let wakers = array::from_fn(|_| waking::InternalWaker::new());
-        let meta = array::from_fn(|idx| TaskMetadata {
-            done: false,
-            id: idx,
+    const TASK_LIMIT: () = assert!(
+        L <= MAX_TASKS,
+        "A Runtime can hold at most MAX_TASKS Tasks, split the Tasks into fewer, larger ones"
+    );
+
+    /// Creates a new Runtime for the List of Tasks, using the given Storage for the Wakers of the
+    /// Tasks
+    pub fn new(tasks: Task<'f, T, L>, wakers: &'static Wakers<L>) -> Self {
+        Self::from_parts(tasks, wakers, TaskMetadata::defaults())
+    }
+
+    /// Creates a new Runtime for the List of Tasks from already prepared parts, which can be
+    /// built in a const context. This avoids building the Metadata at startup and allows for
+    /// setting the priorities up front.
+    ///
+    /// # Panics
+    /// If the Metadata is not stored at the index of the id of its Task, like it is done by
+    /// [`TaskMetadata::defaults`]
+    pub fn from_parts(
+        mut tasks: Task<'f, T, L>,
+        wakers: &'static Wakers<L>,
+        metadata: [TaskMetadata; L],
+    ) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::TASK_LIMIT;
+
+        assert!(
+            metadata
+                .iter()
+                .enumerate()
+                .all(|(idx, meta)| meta.id == idx),
+            "The Metadata needs to be stored at the index of its Task"
+        );
+
+        wakers.reset();
+
+        let futures = array::from_fn(|idx| {
+            let task_fut = tasks
+                .get_mut(idx)
+                .and_then(|t| t.content())
+                .expect("The List contains exactly L Tasks");
+
+            // Safety: We never move the Future out of the Pin, the pointer is only turned back
+            // into a pinned reference for polling it
+            let fut: &mut (dyn Future<Output = ()> + 'f) =
+                unsafe { task_fut.as_mut().get_unchecked_mut() };
+            fut as *mut (dyn Future<Output = ()> + 'f)
         });
 
+        let mut order = array::from_fn(|idx| idx);
+        Self::sort_order(&mut order, &metadata);
+
         Self {
+            futures,
             tasks,
             wakers,
-            metadata: meta,
+            metadata,
+            order,
+            timing: None,
+            livelock: None,
+            factories: array::from_fn(|_| None),
+            #[cfg(feature = "host-sim")]
+            idle: IdleBackoff::default(),
         }
     }
 
+    /// Installs a Clock, which is called before and after every pass to measure how long the
+    /// pass took, see [`last_pass_ticks`](Self::last_pass_ticks) and
+    /// [`max_pass_ticks`](Self::max_pass_ticks).
+    ///
+    /// The Clock can return any free-running counter, like the counter of a hardware Timer, that
+    /// is allowed to wrap around. Without a Clock, the passes are not measured at all.
+    pub fn with_clock(mut self, now: &'f dyn Fn() -> u32) -> Self {
+        self.timing = Some(PassTiming {
+            now,
+            last: 0,
+            max: 0,
+        });
+        self
+    }
+
+    /// The Duration of the last pass in ticks of the Clock, or None if no Clock was installed
+    /// using [`with_clock`](Self::with_clock)
+    pub fn last_pass_ticks(&self) -> Option<u32> {
+        self.timing.as_ref().map(|t| t.last)
+    }
+
+    /// The Duration of the longest pass so far in ticks of the Clock, or None if no Clock was
+    /// installed using [`with_clock`](Self::with_clock)
+    pub fn max_pass_ticks(&self) -> Option<u32> {
+        self.timing.as_ref().map(|t| t.max)
+    }
+
+    /// Installs a Guard, that calls `on_stuck` once `passes` consecutive passes polled at least
+    /// one Task, but none of the polled Tasks completed or parked, meaning that every polled
+    /// Task was still ready after being polled. This usually indicates a Task spinning, without
+    /// ever waiting on anything, which starves the rest of the System.
+    ///
+    /// After calling `on_stuck`, the Guard starts counting again from zero, so it is called every
+    /// `passes` passes for as long as the Tasks are stuck. This can be used to stop feeding a
+    /// hardware Watchdog or to log the Livelock. Passes in which no Task was polled count as
+    /// progress, as all the Tasks are parked.
+    ///
+    /// # Panics
+    /// If `passes` is 0
+    pub fn with_livelock_guard(mut self, passes: usize, on_stuck: &'f dyn Fn()) -> Self {
+        assert!(
+            passes > 0,
+            "The Livelock-Guard needs at least a single pass"
+        );
+
+        self.livelock = Some(LivelockGuard {
+            limit: passes,
+            stuck: 0,
+            on_stuck,
+        });
+        self
+    }
+
+    /// Installs a Factory for the Task with the given id, which is called whenever the Future of
+    /// the Task completes and whose Future is then installed in its place, like using
+    /// [`replace_task`](Self::replace_task). This way the Task is never done and is reborn every
+    /// time it completes.
+    ///
+    /// Unlike [`Restart`], which stores the new Futures inline in the Task-List, the Futures
+    /// created by the Factory are borrowed for `'f`, for the same reasons as in
+    /// [`replace_task`](Self::replace_task), so the Factory needs to hand out Storage that lives
+    /// as long as the Runtime, like a Pool of Futures.
+    ///
+    /// # Panics
+    /// If there is no Task with the given id
+    pub fn with_restart(mut self, id: usize, factory: TaskFactory<'f>) -> Self {
+        let slot = self
+            .factories
+            .get_mut(id)
+            .expect("There is no Task with the given id");
+        *slot = Some(factory);
+        self
+    }
+
+    /// Sets what the Runtime does in [`run`](Self::run) after a pass, in which no Task was
+    /// polled, which defaults to [`IdleBackoff::Yield`].
+    ///
+    /// This is only available for host builds, with the `host-sim` feature enabled.
+    #[cfg(feature = "host-sim")]
+    pub fn with_idle_backoff(mut self, idle: IdleBackoff) -> Self {
+        self.idle = idle;
+        self
+    }
+
+    /// Fills `out` with the current State of every Task, where the State of every Task is stored
+    /// at the index of its id.
+    ///
+    /// This does not change anything about the Tasks, so it can be used to expose the Health of
+    /// the Scheduler, like over the Protocol, without affecting it.
+    pub fn snapshot(&self, out: &mut [TaskSnapshot; L]) {
+        for (entry, meta) in out.iter_mut().zip(self.metadata.iter()) {
+            *entry = TaskSnapshot {
+                id: meta.id,
+                done: meta.done,
+                polls: meta.polls,
+                ready: self
+                    .wakers
+                    .get(meta.id)
+                    .expect("There is a Waker for every Task")
+                    .is_ready(),
+            };
+        }
+    }
+
+    /// Sets the priority of the Task with the given id, where Tasks with a higher priority are
+    /// polled first. All Tasks start with a priority of 0.
+    ///
+    /// Returns false if there is no Task with the given id.
+    pub fn set_priority(&mut self, id: usize, priority: u8) -> bool {
+        let entry = match self.metadata.get_mut(id) {
+            Some(e) => e,
+            None => return false,
+        };
+        entry.priority = priority;
+
+        Self::sort_order(&mut self.order, &self.metadata);
+
+        true
+    }
+
+    /// Sorts the ids of the Tasks by their priority, keeping Tasks with the same priority in the
+    /// order of their ids
+    fn sort_order(order: &mut [usize; L], metadata: &[TaskMetadata; L]) {
+        order.sort_unstable_by_key(|id| (core::cmp::Reverse(metadata[*id].priority), *id));
+    }
+
+    /// Get the Waker-State for the Task with the given id.
+    ///
+    /// This allows something like an interrupt-handler to directly mark a Task as ready, using
+    /// [`InternalWaker::set_ready`], without needing a stored clone of the Tasks Waker.
+    pub fn waker_handle(&self, id: usize) -> Option<&'static InternalWaker> {
+        self.wakers.get(id)
+    }
+
+    /// Replaces the Future of the Task with the given id, which allows reusing the slot of a
+    /// one-shot Task once it has finished.
+    ///
+    /// The new Future is only installed if the Task is currently done, in which case it is also
+    /// marked as ready, so it gets polled in the next pass.
+    ///
+    /// Returns false if there is no Task with the given id or if it is not done yet.
+    ///
+    /// # Lifetimes
+    /// The Task-List still holds the borrow of the old Future, which is simply never polled again.
+    /// The new Future is borrowed for the same `'f`, so it outlives the Runtime just like the
+    /// Futures in the Task-List and its pointer stays valid for as long as the Runtime exists.
+    pub fn replace_task(&mut self, id: usize, fut: Pin<&'f mut dyn Future<Output = ()>>) -> bool {
+        let entry = match self.metadata.get_mut(id) {
+            Some(e) if e.done => e,
+            _ => return false,
+        };
+
+        self.futures[id] = Self::future_ptr(fut);
+        entry.done = false;
+
+        self.wakers
+            .get(id)
+            .expect("There is a Waker for every Task")
+            .set_ready(true);
+
+        true
+    }
+
+    /// Turns the pinned Future into the pointer stored for polling it
+    fn future_ptr(
+        fut: Pin<&'f mut dyn Future<Output = ()>>,
+    ) -> *mut (dyn Future<Output = ()> + 'f) {
+        // Safety: Same as in `new`, the Future is never moved out of the Pin
+        let fut: &mut (dyn Future<Output = ()> + 'f) = unsafe { fut.get_unchecked_mut() };
+        fut as *mut (dyn Future<Output = ()> + 'f)
+    }
+
+    /// Performs a single pass over all the Tasks, polling every Task that is ready, and returns
+    /// the number of Tasks that were polled
+    pub fn poll_once(&mut self) -> usize {
+        self.poll_pass(|_| {})
+    }
+
+    /// Performs `passes` passes over all the Tasks, like [`poll_once`](Self::poll_once), and
+    /// records the ids of the polled Tasks, in the order they were polled in, into `record`.
+    ///
+    /// This is mostly useful for testing and debugging the scheduling, as it is fully
+    /// deterministic. Once `record` is full, any further polls are not recorded anymore.
+    ///
+    /// Returns the number of ids written to `record`.
+    pub fn run_recorded(&mut self, passes: usize, record: &mut [usize]) -> usize {
+        let mut recorded = 0;
+
+        for _ in 0..passes {
+            self.poll_pass(|id| {
+                if let Some(slot) = record.get_mut(recorded) {
+                    *slot = id;
+                    recorded += 1;
+                }
+            });
+        }
+
+        recorded
+    }
+
+    /// Performs a single pass over all the Tasks and calls `observe` with the id of every Task,
+    /// right before it is polled
+    fn poll_pass<O>(&mut self, mut observe: O) -> usize
+    where
+        O: FnMut(usize),
+    {
+        let start = self.timing.as_ref().map(|t| (t.now)());
+        let mut polled = 0;
+        let mut progressed = false;
+
+        for id in self.order {
+            let entry = &mut self.metadata[id];
+            let iwaker = self.wakers.get(id).unwrap();
+            if !iwaker.is_ready() || entry.done {
+                continue;
+            }
+            iwaker.set_ready(false);
+
+            // Safety: The pointer is valid, see the documentation of the field, and the Future
+            // was pinned by the Task-List
+            let mut task_fut = unsafe { Pin::new_unchecked(&mut *self.futures[id]) };
+
+            let waker = waking::create_waker(iwaker);
+            let mut context = Context::from_waker(&waker);
+
+            observe(id);
+            polled += 1;
+            entry.polls = entry.polls.wrapping_add(1);
+            match task_fut.as_mut().poll(&mut context) {
+                Poll::Pending => {
+                    progressed |= !iwaker.is_ready();
+                }
+                Poll::Ready(_) => {
+                    progressed = true;
+
+                    match self.factories[id].as_mut() {
+                        Some(factory) => {
+                            self.futures[id] = Self::future_ptr(factory());
+                            iwaker.set_ready(true);
+                        }
+                        None => {
+                            entry.done = true;
+                        }
+                    }
+                }
+            };
+        }
+
+        if let (Some(timing), Some(start)) = (self.timing.as_mut(), start) {
+            timing.last = (timing.now)().wrapping_sub(start);
+            timing.max = timing.max.max(timing.last);
+        }
+
+        if let Some(guard) = self.livelock.as_mut() {
+            if polled == 0 || progressed {
+                guard.stuck = 0;
+            } else {
+                guard.stuck += 1;
+                if guard.stuck >= guard.limit {
+                    guard.stuck = 0;
+                    (guard.on_stuck)();
+                }
+            }
+        }
+
+        polled
+    }
+
     /// Actually starts/runs the Runtime, this will never return as we expect the Tasks to run
     /// forever.
+    ///
+    /// On the embedded Targets, this simply busy-loops over the Tasks. With the `host-sim`
+    /// feature enabled, an idle pass instead backs off as configured using
+    /// [`with_idle_backoff`](Self::with_idle_backoff).
     pub fn run(mut self) -> ! {
         loop {
-            for (id, (entry, iwaker)) in
-                self.metadata.iter_mut().zip(self.wakers.iter()).enumerate()
-            {
-                if !iwaker.is_ready() || entry.done {
-                    continue;
-                }
-                iwaker.set_ready(false);
+            #[allow(unused_variables)]
+            let polled = self.poll_once();
 
-                let task = self.tasks.get_mut(id).unwrap();
-                let task_fut = task.content().unwrap();
+            #[cfg(feature = "host-sim")]
+            if polled == 0 {
+                self.idle.wait();
+            }
 
-                let waker = unsafe { waking::create_waker(iwaker) };
-                let mut context = Context::from_waker(&waker);
+            assert!(self.metadata.iter().any(|m| !m.done), "Should run forever");
+        }
+    }
+}
 
-                match task_fut.as_mut().poll(&mut context) {
-                    Poll::Pending => {}
-                    Poll::Ready(_) => {
-                        entry.done = true;
-                    }
-                };
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+
+    /// A Future that counts how often it was polled, but never completes or wakes itself
+    struct CountPolls<'c>(&'c Cell<usize>);
+
+    impl<'c> Future for CountPolls<'c> {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+            self.0.set(self.0.get() + 1);
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn poll_once_all_ready() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        let first = Cell::new(0);
+        let second = Cell::new(0);
+        tasks!(list, (CountPolls(&first), f), (CountPolls(&second), s));
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+
+        assert_eq!(2, runtime.poll_once());
+        assert_eq!((1, 1), (first.get(), second.get()));
+
+        assert_eq!(0, runtime.poll_once());
+        assert_eq!((1, 1), (first.get(), second.get()));
+    }
+
+    #[test]
+    fn waker_handle_set_ready() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        let first = Cell::new(0);
+        let second = Cell::new(0);
+        tasks!(list, (CountPolls(&first), f), (CountPolls(&second), s));
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+        runtime.poll_once();
+        assert_eq!((1, 1), (first.get(), second.get()));
+
+        // Appending a Task puts it at the front of the List, so the first Task has the last id
+        let handle = runtime.waker_handle(1).expect("Task 1 exists");
+        handle.set_ready(true);
+
+        assert_eq!(1, runtime.poll_once());
+        assert_eq!((2, 1), (first.get(), second.get()));
+    }
+
+    #[test]
+    fn waker_handle_out_of_range() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        let first = Cell::new(0);
+        let second = Cell::new(0);
+        tasks!(list, (CountPolls(&first), f), (CountPolls(&second), s));
+
+        let runtime = Runtime::new(list, &WAKERS);
+
+        assert!(runtime.waker_handle(2).is_none());
+    }
+
+    #[test]
+    fn cached_futures_match_list() {
+        static WAKERS: Wakers<3> = Wakers::new();
+
+        let counts = [Cell::new(0), Cell::new(0), Cell::new(0)];
+        tasks!(
+            list,
+            (CountPolls(&counts[0]), a),
+            (CountPolls(&counts[1]), b),
+            (CountPolls(&counts[2]), c)
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+
+        for id in 0..3 {
+            let from_list = runtime
+                .tasks
+                .get_mut(id)
+                .and_then(|t| t.content())
+                .map(|fut| unsafe { fut.as_mut().get_unchecked_mut() } as *mut _ as *mut u8)
+                .unwrap();
+
+            assert_eq!(from_list, runtime.futures[id] as *mut u8);
+        }
+    }
+
+    #[test]
+    fn cached_futures_poll_counts() {
+        static WAKERS: Wakers<8> = Wakers::new();
+
+        let counts: [Cell<usize>; 8] = Default::default();
+        tasks!(
+            list,
+            (CountPolls(&counts[0]), t0),
+            (CountPolls(&counts[1]), t1),
+            (CountPolls(&counts[2]), t2),
+            (CountPolls(&counts[3]), t3),
+            (CountPolls(&counts[4]), t4),
+            (CountPolls(&counts[5]), t5),
+            (CountPolls(&counts[6]), t6),
+            (CountPolls(&counts[7]), t7)
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+        assert_eq!(8, runtime.poll_once());
+
+        // Every pass only polls the woken Tasks, each exactly once
+        for _ in 0..10 {
+            for id in 0..3 {
+                runtime.waker_handle(id).unwrap().set_ready(true);
             }
+            assert_eq!(3, runtime.poll_once());
+        }
 
-            assert!(self.metadata.iter().any(|m| !m.done), "Should run forever");
+        // The first Task has the last id
+        for (idx, count) in counts.iter().enumerate() {
+            let id = 7 - idx;
+            let expected = if id < 3 { 11 } else { 1 };
+            assert_eq!(expected, count.get(), "Task {}", id);
+        }
+    }
+
+    /// A Future that records the order in which the Tasks were polled
+    struct RecordPoll<'c> {
+        name: char,
+        order: &'c core::cell::RefCell<Vec<char>>,
+    }
+
+    impl<'c> Future for RecordPoll<'c> {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+            self.order.borrow_mut().push(self.name);
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn priority_poll_order() {
+        static WAKERS: Wakers<3> = Wakers::new();
+
+        let order = core::cell::RefCell::new(Vec::new());
+        tasks!(
+            list,
+            (
+                RecordPoll {
+                    name: 'a',
+                    order: &order
+                },
+                a
+            ),
+            (
+                RecordPoll {
+                    name: 'b',
+                    order: &order
+                },
+                b
+            ),
+            (
+                RecordPoll {
+                    name: 'c',
+                    order: &order
+                },
+                c
+            )
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+
+        // Task 'a' has the id 2 and task 'c' the id 0
+        assert!(runtime.set_priority(2, 5));
+        assert!(runtime.set_priority(1, 1));
+        assert!(!runtime.set_priority(3, 1));
+
+        assert_eq!(3, runtime.poll_once());
+        assert_eq!(vec!['a', 'b', 'c'], *order.borrow());
+
+        // The low priority Task is still polled, after the higher priority one
+        order.borrow_mut().clear();
+        runtime.waker_handle(0).unwrap().set_ready(true);
+        runtime.waker_handle(2).unwrap().set_ready(true);
+
+        assert_eq!(2, runtime.poll_once());
+        assert_eq!(vec!['a', 'c'], *order.borrow());
+    }
+
+    /// A Future that wakes itself on every poll, until it was polled the given number of times,
+    /// and completes on the poll after that
+    struct SelfWaking(usize);
+
+    impl Future for SelfWaking {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.0 == 0 {
+                return Poll::Ready(());
+            }
+
+            self.0 -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn run_recorded_poll_order() {
+        static WAKERS: Wakers<3> = Wakers::new();
+
+        let idle = Cell::new(0);
+        tasks!(
+            list,
+            (SelfWaking(usize::MAX), forever),
+            (SelfWaking(1), twice),
+            (CountPolls(&idle), idle_task)
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+
+        // 'forever' has the id 2, 'twice' the id 1 and 'idle_task' the id 0
+        let mut record = [usize::MAX; 16];
+        let recorded = runtime.run_recorded(5, &mut record);
+
+        assert_eq!(&[0, 1, 2, 1, 2, 2, 2, 2], &record[..recorded]);
+        assert_eq!(1, idle.get());
+    }
+
+    #[test]
+    fn run_recorded_full_record() {
+        static WAKERS: Wakers<3> = Wakers::new();
+
+        tasks!(
+            list,
+            (SelfWaking(usize::MAX), a),
+            (SelfWaking(usize::MAX), b),
+            (SelfWaking(usize::MAX), c)
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+
+        let mut record = [usize::MAX; 4];
+        assert_eq!(4, runtime.run_recorded(5, &mut record));
+        assert_eq!([0, 1, 2, 0], record);
+    }
+
+    #[test]
+    fn replace_finished_task() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        let replaced = Cell::new(0);
+        let mut too_early = SelfWaking(0);
+        let mut replacement = CountPolls(&replaced);
+        let mut too_late = SelfWaking(0);
+
+        let idle = Cell::new(0);
+        tasks!(list, (SelfWaking(0), once), (CountPolls(&idle), idle_task));
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+
+        // 'once' has the id 1 and did not run yet, so it can't be replaced
+        assert!(!runtime.replace_task(1, Pin::<&mut SelfWaking>::new(&mut too_early)));
+
+        assert_eq!(2, runtime.poll_once());
+        assert_eq!(0, runtime.poll_once());
+
+        assert!(runtime.replace_task(1, Pin::<&mut CountPolls>::new(&mut replacement)));
+
+        assert_eq!(1, runtime.poll_once());
+        assert_eq!((1, 1), (replaced.get(), idle.get()));
+
+        // The new Future never completes, so it stays in place
+        assert!(!runtime.replace_task(1, Pin::<&mut SelfWaking>::new(&mut too_late)));
+        runtime.waker_handle(1).unwrap().set_ready(true);
+        assert_eq!(1, runtime.poll_once());
+        assert_eq!((2, 1), (replaced.get(), idle.get()));
+    }
+
+    #[test]
+    fn restart_completed_task() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        let restarts = Cell::new(0);
+        let factory = || {
+            restarts.set(restarts.get() + 1);
+            SelfWaking(1)
+        };
+
+        let idle = Cell::new(0);
+        tasks!(
+            list,
+            (Restart::new(factory), once),
+            (CountPolls(&idle), idle_task)
+        );
+
+        // 'once' has the id 1
+        let mut runtime = Runtime::new(list, &WAKERS);
+
+        let mut record = [usize::MAX; 8];
+        let recorded = runtime.run_recorded(5, &mut record);
+
+        // Every Future wakes itself once and completes on its second poll, after which the next
+        // one is polled in the following pass
+        assert_eq!(&[0, 1, 1, 1, 1, 1], &record[..recorded]);
+        assert_eq!(3, restarts.get());
+        assert!(runtime.metadata.iter().all(|m| !m.done));
+    }
+
+    #[test]
+    fn with_restart_reborn() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        let mut pool = [SelfWaking(1), SelfWaking(0), SelfWaking(usize::MAX)];
+        let mut next = pool.iter_mut();
+        let restarts = Cell::new(0);
+        let mut factory = || {
+            restarts.set(restarts.get() + 1);
+            Pin::new(next.next().expect("The Pool is large enough"))
+                as Pin<&mut dyn Future<Output = ()>>
+        };
+
+        let idle = Cell::new(0);
+        tasks!(list, (SelfWaking(0), once), (CountPolls(&idle), idle_task));
+
+        // 'once' has the id 1
+        let mut runtime = Runtime::new(list, &WAKERS).with_restart(1, &mut factory);
+
+        let mut record = [usize::MAX; 8];
+        let recorded = runtime.run_recorded(5, &mut record);
+
+        // The first Future completes right away, the first reborn one after waking itself once,
+        // the second one right away again and the last one never completes
+        assert_eq!(&[0, 1, 1, 1, 1, 1], &record[..recorded]);
+        assert_eq!(3, restarts.get());
+        assert!(runtime.metadata.iter().all(|m| !m.done));
+    }
+
+    #[test]
+    fn from_parts_const() {
+        const METADATA: [TaskMetadata; 3] = {
+            let mut metadata = TaskMetadata::defaults();
+            metadata[0] = metadata[0].with_priority(2);
+            metadata[2] = metadata[2].with_priority(1);
+            metadata
+        };
+        static WAKERS: Wakers<3> = Wakers::from_wakers([
+            InternalWaker::new(),
+            InternalWaker::new(),
+            InternalWaker::new(),
+        ]);
+
+        tasks!(
+            list,
+            (SelfWaking(1), a),
+            (SelfWaking(1), b),
+            (SelfWaking(1), c)
+        );
+
+        let mut runtime = Runtime::from_parts(list, &WAKERS, METADATA);
+
+        let mut record = [usize::MAX; 6];
+        assert_eq!(6, runtime.run_recorded(3, &mut record));
+        assert_eq!([0, 2, 1, 0, 2, 1], record);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_parts_misplaced_metadata() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        tasks!(list, (SelfWaking(1), a), (SelfWaking(1), b));
+
+        let metadata = [TaskMetadata::new(1), TaskMetadata::new(0)];
+        Runtime::from_parts(list, &WAKERS, metadata);
+    }
+
+    #[test]
+    fn snapshot_after_passes() {
+        static WAKERS: Wakers<3> = Wakers::new();
+
+        let idle = Cell::new(0);
+        tasks!(
+            list,
+            (SelfWaking(usize::MAX), forever),
+            (SelfWaking(1), twice),
+            (CountPolls(&idle), idle_task)
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+
+        let mut snapshot = [TaskSnapshot::default(); 3];
+        runtime.snapshot(&mut snapshot);
+        for (id, entry) in snapshot.iter().enumerate() {
+            assert_eq!(
+                TaskSnapshot {
+                    id,
+                    done: false,
+                    polls: 0,
+                    ready: true
+                },
+                *entry
+            );
+        }
+
+        for _ in 0..3 {
+            runtime.poll_once();
+        }
+
+        // 'forever' has the id 2, 'twice' the id 1 and 'idle_task' the id 0
+        runtime.snapshot(&mut snapshot);
+        assert_eq!(
+            [
+                TaskSnapshot {
+                    id: 0,
+                    done: false,
+                    polls: 1,
+                    ready: false
+                },
+                TaskSnapshot {
+                    id: 1,
+                    done: true,
+                    polls: 2,
+                    ready: false
+                },
+                TaskSnapshot {
+                    id: 2,
+                    done: false,
+                    polls: 3,
+                    ready: true
+                },
+            ],
+            snapshot
+        );
+    }
+
+    /// A Future that advances a fake Clock by `step` every time it is polled and wakes itself
+    /// again, until it completes after `remaining` more polls
+    struct AdvanceClock {
+        clock: &'static core::sync::atomic::AtomicU32,
+        step: u32,
+        remaining: usize,
+    }
+
+    impl Future for AdvanceClock {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            self.clock
+                .fetch_add(self.step, core::sync::atomic::Ordering::SeqCst);
+            if self.remaining == 0 {
+                return Poll::Ready(());
+            }
+
+            self.remaining -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
         }
     }
+
+    #[test]
+    fn pass_duration_clock() {
+        static WAKERS: Wakers<2> = Wakers::new();
+        static CLOCK: core::sync::atomic::AtomicU32 = core::sync::atomic::AtomicU32::new(0);
+
+        let now = || CLOCK.load(core::sync::atomic::Ordering::SeqCst);
+
+        tasks!(
+            list,
+            (
+                AdvanceClock {
+                    clock: &CLOCK,
+                    step: 3,
+                    remaining: 2
+                },
+                short
+            ),
+            (
+                AdvanceClock {
+                    clock: &CLOCK,
+                    step: 5,
+                    remaining: 0
+                },
+                once
+            )
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS).with_clock(&now);
+        assert_eq!(Some(0), runtime.last_pass_ticks());
+
+        runtime.poll_once();
+        assert_eq!(Some(8), runtime.last_pass_ticks());
+
+        runtime.poll_once();
+        assert_eq!(Some(3), runtime.last_pass_ticks());
+        assert_eq!(Some(8), runtime.max_pass_ticks());
+
+        runtime.poll_once();
+        runtime.poll_once();
+        assert_eq!(Some(0), runtime.last_pass_ticks());
+        assert_eq!(Some(8), runtime.max_pass_ticks());
+    }
+
+    #[test]
+    fn pass_duration_without_clock() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        let polls = Cell::new(0);
+        tasks!(
+            list,
+            (CountPolls(&polls), task),
+            (CountPolls(&polls), other)
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS);
+        runtime.poll_once();
+        assert_eq!(2, polls.get());
+
+        assert_eq!(None, runtime.last_pass_ticks());
+        assert_eq!(None, runtime.max_pass_ticks());
+    }
+
+    #[test]
+    fn livelock_guard_fires() {
+        static WAKERS: Wakers<2> = Wakers::new();
+
+        let fired = Cell::new(0);
+        let on_stuck = || fired.set(fired.get() + 1);
+
+        let polls = Cell::new(0);
+        tasks!(
+            list,
+            (SelfWaking(usize::MAX), spin),
+            (CountPolls(&polls), parked)
+        );
+
+        let mut runtime = Runtime::new(list, &WAKERS).with_livelock_guard(3, &on_stuck);
+
+        // The first pass also polls the parked Task for the first time, which parks it
+        runtime.poll_once();
+        assert_eq!(0, fired.get());
+
+        runtime.poll_once();
+        runtime.poll_once();
+        assert_eq!(0, fired.get());
+
+        runtime.poll_once();
+        assert_eq!(1, fired.get());
+
+        for _ in 0..3 {
+            runtime.poll_once();
+        }
+        assert_eq!(2, fired.get());
+        assert_eq!(1, polls.get());
+    }
+
+    #[test]
+    fn livelock_guard_progress() {
+        static WAKERS: Wakers<1> = Wakers::new();
+
+        let fired = Cell::new(0);
+        let on_stuck = || fired.set(fired.get() + 1);
+
+        tasks!(list, (SelfWaking(2), task),);
+
+        let mut runtime = Runtime::new(list, &WAKERS).with_livelock_guard(3, &on_stuck);
+        for _ in 0..5 {
+            runtime.poll_once();
+        }
+
+        // The Task spins for 2 passes and completes in the third one, after which nothing is
+        // polled anymore
+        assert_eq!(0, fired.get());
+    }
 }
@@ -0,0 +1,65 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A Task, that restarts itself whenever its Future completes, by creating a new Future using
+/// its Factory, which turns a one-shot Task into one that runs forever.
+///
+/// The Future is stored inline and every new Future is created in the same place, replacing the
+/// completed one, so restarting a Task never allocates or needs any extra Storage. The new Future
+/// is polled in the next pass of the [`Runtime`](crate::Runtime), like any other woken Task.
+/// To create the Futures outside of the Task-List instead, see
+/// [`Runtime::with_restart`](crate::Runtime::with_restart).
+///
+/// # Example
+/// ```rust
+/// # use executor::{tasks, Restart, Runtime, Wakers};
+/// async fn handle_request() {}
+///
+/// static WAKERS: Wakers<1> = Wakers::new();
+///
+/// tasks!(list, (Restart::new(handle_request), requests),);
+///
+/// let mut runtime = Runtime::new(list, &WAKERS);
+/// runtime.poll_once();
+/// ```
+pub struct Restart<F, Fut> {
+    factory: F,
+    fut: Fut,
+}
+
+impl<F, Fut> Restart<F, Fut>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    /// Creates the Task, starting with the first Future created by `factory`
+    pub fn new(mut factory: F) -> Self {
+        let fut = factory();
+        Self { factory, fut }
+    }
+}
+
+impl<F, Fut> Future for Restart<F, Fut>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: The Future is never moved out of the Pin, a completed Future is only replaced
+        // in place using `Pin::set`, which drops it first. The Factory is not pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut fut = unsafe { Pin::new_unchecked(&mut this.fut) };
+
+        if fut.as_mut().poll(cx).is_ready() {
+            fut.set((this.factory)());
+            cx.waker().wake_by_ref();
+        }
+
+        Poll::Pending
+    }
+}
@@ -19,20 +19,23 @@ unsafe fn w_wake_by_ref(ptr: *const ()) {
 }
 unsafe fn w_drop(_: *const ()) {}
 
-/// # Safety
-/// The provided `InternalWaker` needs to have a lifetime that is valid for at least the entire
-/// Duration of the Runtime
-pub unsafe fn create_waker(iwaker: &InternalWaker) -> Waker {
+/// Creates a Waker for the given `InternalWaker`.
+///
+/// Because the `InternalWaker` lives for `'static`, the returned Waker (and all its clones) can
+/// be freely stored anywhere, like in an interrupt-handler, without ever dangling.
+pub fn create_waker(iwaker: &'static InternalWaker) -> Waker {
     let raw_waker = RawWaker::new(iwaker as *const InternalWaker as *const (), &VTABLE);
     unsafe { Waker::from_raw(raw_waker) }
 }
 
+/// The State backing the Waker of a single Task, which stores whether or not the Task is ready to
+/// be polled again
 pub struct InternalWaker {
     ready: AtomicBool,
 }
 
 impl InternalWaker {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
             ready: AtomicBool::new(true),
         }
@@ -45,3 +48,55 @@ impl InternalWaker {
         self.ready.load(atomic::Ordering::SeqCst)
     }
 }
+
+impl Default for InternalWaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The Storage for the Wakers of all `L` Tasks of a [`Runtime`](crate::Runtime).
+///
+/// This needs to be placed in a `static`, because the Wakers handed out to the Tasks point into
+/// this Storage and may be stored by them for an arbitrary amount of time.
+///
+/// # Example
+/// ```rust
+/// # use executor::Wakers;
+/// static WAKERS: Wakers<2> = Wakers::new();
+/// ```
+pub struct Wakers<const L: usize> {
+    wakers: [InternalWaker; L],
+}
+
+impl<const L: usize> Wakers<L> {
+    pub const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const WAKER: InternalWaker = InternalWaker::new();
+
+        Self { wakers: [WAKER; L] }
+    }
+
+    /// Creates the Storage from already built Wakers
+    pub const fn from_wakers(wakers: [InternalWaker; L]) -> Self {
+        Self { wakers }
+    }
+
+    /// Marks all the Wakers as ready
+    pub(crate) fn reset(&self) {
+        for waker in self.wakers.iter() {
+            waker.set_ready(true);
+        }
+    }
+
+    /// Get the Waker for the Task with the given index
+    pub fn get(&self, index: usize) -> Option<&InternalWaker> {
+        self.wakers.get(index)
+    }
+}
+
+impl<const L: usize> Default for Wakers<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
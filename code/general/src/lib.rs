@@ -4,3 +4,5 @@ mod serial;
 pub use serial::*;
 
 pub mod bfmt;
+
+pub mod string;
@@ -17,12 +17,23 @@ pub trait AsyncSerial<const N: usize> {
         's: 'f;
 }
 
-#[cfg(feature = "mocks")]
+/// Writes all the given Frames to the serial in order, by waiting for each Frame to be written
+/// before starting the next one
+pub async fn write_all<S, const N: usize>(serial: &mut S, frames: &[[u8; N]])
+where
+    S: AsyncSerial<N>,
+{
+    for frame in frames {
+        serial.write(*frame).await;
+    }
+}
+
+#[cfg(any(test, feature = "mocks"))]
 pub mod mocks {
     extern crate alloc;
 
-    use alloc::vec::Vec;
-    use core::{future::Future, marker::PhantomData};
+    use alloc::{collections::VecDeque, rc::Rc, vec::Vec};
+    use core::{cell::RefCell, future::Future, task::Waker};
 
     use crate::AsyncSerial;
 
@@ -112,4 +123,153 @@ pub mod mocks {
             core::task::Poll::Ready(())
         }
     }
+
+    /// The Frames send in one Direction of a Loopback
+    struct LoopbackChannel<const N: usize> {
+        frames: VecDeque<[u8; N]>,
+        /// The Waker of the Reader waiting for the next Frame
+        waker: Option<Waker>,
+    }
+
+    /// Creates a connected pair of Serials, where every Frame written to one half can be read
+    /// from the other half.
+    ///
+    /// Unlike the [`MockSerial`], which only checks a fixed Script of Reads and Writes, this
+    /// allows for two Tasks, like a Controller and an Extension, to actually talk to each other.
+    pub fn loopback<const N: usize>() -> (LoopbackSerial<N>, LoopbackSerial<N>) {
+        let first = Rc::new(RefCell::new(LoopbackChannel {
+            frames: VecDeque::new(),
+            waker: None,
+        }));
+        let second = Rc::new(RefCell::new(LoopbackChannel {
+            frames: VecDeque::new(),
+            waker: None,
+        }));
+
+        (
+            LoopbackSerial {
+                incoming: first.clone(),
+                outgoing: second.clone(),
+            },
+            LoopbackSerial {
+                incoming: second,
+                outgoing: first,
+            },
+        )
+    }
+
+    /// A single half of a Loopback, see [`loopback`]
+    pub struct LoopbackSerial<const N: usize> {
+        incoming: Rc<RefCell<LoopbackChannel<N>>>,
+        outgoing: Rc<RefCell<LoopbackChannel<N>>>,
+    }
+
+    impl<const N: usize> AsyncSerial<N> for LoopbackSerial<N> {
+        type ReceiveFuture<'f> = LoopbackReceiveFuture<'f, N> where Self: 'f;
+        type WriteFuture<'f> = MockWriteFuture where Self: 'f;
+
+        fn read<'s, 'f>(&'s mut self) -> Self::ReceiveFuture<'f>
+        where
+            's: 'f,
+        {
+            LoopbackReceiveFuture {
+                channel: &self.incoming,
+            }
+        }
+
+        fn write<'s, 'f>(&'s mut self, buffer: [u8; N]) -> Self::WriteFuture<'f>
+        where
+            's: 'f,
+        {
+            let mut channel = self.outgoing.borrow_mut();
+            channel.frames.push_back(buffer);
+            if let Some(waker) = channel.waker.take() {
+                waker.wake();
+            }
+
+            MockWriteFuture {}
+        }
+    }
+
+    pub struct LoopbackReceiveFuture<'f, const N: usize> {
+        channel: &'f RefCell<LoopbackChannel<N>>,
+    }
+    impl<'f, const N: usize> Future for LoopbackReceiveFuture<'f, N> {
+        type Output = [u8; N];
+
+        fn poll(
+            self: core::pin::Pin<&mut Self>,
+            cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<Self::Output> {
+            let mut channel = self.channel.borrow_mut();
+            match channel.frames.pop_front() {
+                Some(frame) => core::task::Poll::Ready(frame),
+                None => {
+                    channel.waker = Some(cx.waker().clone());
+                    core::task::Poll::Pending
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_all_in_order() {
+        let mut serial = mocks::MockSerial::<4>::new();
+        serial.write([1; 4]);
+        serial.write([2; 4]);
+        serial.write([3; 4]);
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let frames = [[1; 4], [2; 4], [3; 4]];
+        rt.block_on(async {
+            write_all(&mut &mut serial, &frames).await;
+        });
+
+        serial.assert_outstanding();
+    }
+
+    #[test]
+    fn loopback_both_directions() {
+        let (mut first, mut second) = mocks::loopback::<4>();
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            first.write([1; 4]).await;
+            first.write([2; 4]).await;
+            second.write([3; 4]).await;
+
+            assert_eq!([1; 4], second.read().await);
+            assert_eq!([2; 4], second.read().await);
+            assert_eq!([3; 4], first.read().await);
+        });
+    }
+
+    #[test]
+    fn write_all_empty() {
+        let mut serial = mocks::MockSerial::<4>::new();
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            write_all(&mut &mut serial, &[]).await;
+        });
+
+        serial.assert_outstanding();
+    }
 }
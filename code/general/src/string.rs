@@ -0,0 +1,159 @@
+//! Strings with a fixed Capacity, which can be owned without needing `alloc`.
+//!
+//! These are the owned counterpart to the borrowed `&str`s used throughout the Protocol, for
+//! anything that needs to keep a short Name around after the Buffer it was received in has been
+//! reused.
+
+use core::str::FromStr;
+
+/// The Error returned when a String does not fit into a [`FixedStr`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum FixedStrError {
+    /// The String is longer than the Capacity, contains the Length of the String
+    TooLong(usize),
+}
+
+/// A String, that stores up to `N` Bytes inline.
+///
+/// # Example
+/// ```rust
+/// # use general::string::{FixedStr, FixedStrError};
+/// let name: FixedStr<8> = "fan".parse().unwrap();
+/// assert_eq!("fan", name.as_str());
+///
+/// assert_eq!(
+///     Err(FixedStrError::TooLong(11)),
+///     "temperature".parse::<FixedStr<8>>()
+/// );
+/// ```
+#[derive(Clone, Copy)]
+pub struct FixedStr<const N: usize> {
+    buffer: [u8; N],
+    /// The number of Bytes used in the Buffer, which always hold valid UTF-8
+    len: usize,
+}
+
+impl<const N: usize> FixedStr<N> {
+    /// Creates a new empty String
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; N],
+            len: 0,
+        }
+    }
+
+    /// The String as a regular `&str`
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buffer[..self.len]).expect("The Buffer holds valid UTF-8")
+    }
+
+    /// The Length of the String in Bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the String is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The maximum Length of the String in Bytes
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> FromStr for FixedStr<N> {
+    type Err = FixedStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > N {
+            return Err(FixedStrError::TooLong(s.len()));
+        }
+
+        let mut result = Self::new();
+        result.buffer[..s.len()].copy_from_slice(s.as_bytes());
+        result.len = s.len();
+
+        Ok(result)
+    }
+}
+
+impl<const N: usize> Default for FixedStr<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> PartialEq for FixedStr<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for FixedStr<N> {}
+
+impl<const N: usize> PartialEq<str> for FixedStr<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for FixedStr<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> core::fmt::Display for FixedStr<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        let name: FixedStr<4> = "fan".parse().unwrap();
+
+        assert_eq!("fan", name.as_str());
+        assert_eq!(3, name.len());
+        assert_eq!(4, name.capacity());
+        assert!(!name.is_empty());
+    }
+
+    #[test]
+    fn from_str_exact_capacity() {
+        let name: FixedStr<3> = "fan".parse().unwrap();
+        assert_eq!("fan", name.as_str());
+    }
+
+    #[test]
+    fn from_str_too_long() {
+        assert_eq!(
+            Err(FixedStrError::TooLong(4)),
+            "fans".parse::<FixedStr<3>>()
+        );
+        assert_eq!(Err(FixedStrError::TooLong(1)), "a".parse::<FixedStr<0>>());
+    }
+
+    #[test]
+    fn empty() {
+        let name = FixedStr::<4>::new();
+
+        assert_eq!("", name.as_str());
+        assert!(name.is_empty());
+        assert_eq!(name, FixedStr::default());
+    }
+
+    #[test]
+    fn multi_byte_chars() {
+        let name: FixedStr<8> = "größe".parse().unwrap();
+
+        assert_eq!("größe", name.as_str());
+        assert_eq!(7, name.len());
+    }
+}
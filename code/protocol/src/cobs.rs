@@ -0,0 +1,157 @@
+//! Consistent Overhead Byte Stuffing (COBS)
+//!
+//! COBS encodes arbitrary Data into a Frame that contains no zero bytes, so a single zero byte
+//! can be used as an unambiguous delimiter between Frames. The overhead is at most one byte for
+//! every 254 bytes of Data.
+
+/// The Error returned when decoding an invalid COBS Frame
+#[derive(Debug, PartialEq, Eq)]
+pub enum CobsDecodeError {
+    /// The Frame ended in the middle of a Block
+    Truncated,
+    /// The decoded Data does not fit into the output Buffer
+    OutputTooSmall,
+}
+
+/// The maximum size of the encoded Frame for `len` bytes of Data, excluding the delimiter
+pub const fn max_encoded_len(len: usize) -> usize {
+    len + len / 254 + 1
+}
+
+/// Encodes the Data into the output Buffer and returns the number of bytes written, not
+/// including a delimiter.
+///
+/// # Panics
+/// If the output Buffer is smaller than [`max_encoded_len`] of the Data
+pub fn encode(data: &[u8], output: &mut [u8]) -> usize {
+    let mut code_index = 0;
+    let mut out_index = 1;
+    let mut code = 1u8;
+
+    for byte in data.iter().copied() {
+        if byte == 0 {
+            output[code_index] = code;
+            code_index = out_index;
+            out_index += 1;
+            code = 1;
+            continue;
+        }
+
+        output[out_index] = byte;
+        out_index += 1;
+        code += 1;
+
+        if code == 0xff {
+            output[code_index] = code;
+            code_index = out_index;
+            out_index += 1;
+            code = 1;
+        }
+    }
+
+    output[code_index] = code;
+
+    out_index
+}
+
+/// Decodes the Frame into the output Buffer and returns the number of decoded bytes. The Frame
+/// ends either at the first zero byte or at the end of the given Buffer.
+pub fn decode(frame: &[u8], output: &mut [u8]) -> Result<usize, CobsDecodeError> {
+    let frame = match frame.iter().position(|b| *b == 0) {
+        Some(end) => &frame[..end],
+        None => frame,
+    };
+
+    let mut in_index = 0;
+    let mut out_index = 0;
+    while in_index < frame.len() {
+        let code = frame[in_index] as usize;
+        in_index += 1;
+
+        let block = frame
+            .get(in_index..in_index + code - 1)
+            .ok_or(CobsDecodeError::Truncated)?;
+        output
+            .get_mut(out_index..out_index + block.len())
+            .ok_or(CobsDecodeError::OutputTooSmall)?
+            .copy_from_slice(block);
+        in_index += block.len();
+        out_index += block.len();
+
+        // A full Block is not followed by an implicit zero and neither is the last Block
+        if code != 0xff && in_index < frame.len() {
+            *output
+                .get_mut(out_index)
+                .ok_or(CobsDecodeError::OutputTooSmall)? = 0;
+            out_index += 1;
+        }
+    }
+
+    Ok(out_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(data: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![0xaa; max_encoded_len(data.len())];
+        let length = encode(data, &mut encoded);
+        encoded.truncate(length);
+
+        assert!(encoded.iter().all(|b| *b != 0));
+
+        let mut decoded = vec![0; data.len()];
+        let decoded_length = decode(&encoded, &mut decoded).expect("Should work");
+        assert_eq!(data.len(), decoded_length);
+        assert_eq!(data, &decoded[..]);
+
+        encoded
+    }
+
+    #[test]
+    fn known_values() {
+        assert_eq!(vec![0x01, 0x01], roundtrip(&[0x00]));
+        assert_eq!(vec![0x01, 0x01, 0x01], roundtrip(&[0x00, 0x00]));
+        assert_eq!(
+            vec![0x03, 0x11, 0x22, 0x02, 0x33],
+            roundtrip(&[0x11, 0x22, 0x00, 0x33])
+        );
+        assert_eq!(vec![0x01], roundtrip(&[]));
+    }
+
+    #[test]
+    fn zeros() {
+        let encoded = roundtrip(&[0; 300]);
+        assert_eq!(301, encoded.len());
+    }
+
+    #[test]
+    fn long_block() {
+        let data: Vec<u8> = (0..600).map(|i| (i % 255) as u8 + 1).collect();
+        let encoded = roundtrip(&data);
+        assert_eq!(max_encoded_len(data.len()), encoded.len());
+
+        let data: Vec<u8> = (0..254).map(|_| 1).collect();
+        let encoded = roundtrip(&data);
+        assert_eq!(0xff, encoded[0]);
+    }
+
+    #[test]
+    fn decode_truncated() {
+        let mut output = [0; 8];
+        assert_eq!(
+            Err(CobsDecodeError::Truncated),
+            decode(&[0x05, 0x11, 0x22], &mut output)
+        );
+    }
+
+    #[test]
+    fn decode_output_too_small() {
+        let mut output = [0; 1];
+        assert_eq!(
+            Err(CobsDecodeError::OutputTooSmall),
+            decode(&[0x03, 0x11, 0x22], &mut output)
+        );
+    }
+}
@@ -1,24 +1,75 @@
-use core::array;
+use core::{array, task::Poll};
 
-use crate::packet;
+use crate::{
+    packet::{self, ExtensionId, PacketDirection, PacketKind},
+    DataPoint, OptionsIter, VERSION,
+};
 
 /// This should only be used by the Controller in the Rack
-pub struct Controller<const N: usize, Sel, Rc, Ser>
-where
+///
+/// # Frame Size
+/// Every Packet is send and received as a single Frame of `FN` bytes over the serial connection.
+/// The Frame needs to be able to hold an entire Packet, so `FN` needs to be at least
+/// [`Packet::FRAME_LEN`](packet::Packet::FRAME_LEN), which is checked at compile time, any
+/// remaining bytes in the Frame after the Packet are padded with zeros.
+///
+/// # Tracing
+/// The Controller calls its `Tr` Hook with the Kind of every Packet it sends or receives, see
+/// [`Controller::init_traced`]. This is a no-op for Controllers created using
+/// [`Controller::init`].
+pub struct Controller<
+    const N: usize,
+    Sel,
+    Rc,
+    Ser,
+    const FN: usize = { packet::Packet::FRAME_LEN },
+    Tr = fn(PacketDirection, PacketKind),
+> where
     Sel: Select<N>,
     Rc: ReadyCheck<N>,
 {
     selector: Sel,
     ready: Rc,
     serial: Ser,
+    on_packet: Tr,
+    /// The Sequence-Number of the next Request, that changes the State of an Extension
+    seq: u8,
 
     extensions: [CtrlExtension; N],
 }
 
+/// The default Hook of a Controller, that ignores all the Packets
+fn ignore_packet(_: PacketDirection, _: PacketKind) {}
+
+/// The physical Slot of an Extension on the Controller, which corresponds to the select and
+/// ready lines it is connected to.
+///
+/// This is intentionally a distinct type from the logical [`ExtensionId`] of an Extension, so the
+/// two can't be accidentally mixed up.
+///
+/// # Example
+/// ```rust,compile_fail
+/// # use protocol::{packet::ExtensionId, SlotIndex};
+/// let slot: SlotIndex = ExtensionId(3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlotIndex(pub usize);
+
+impl From<usize> for SlotIndex {
+    fn from(raw: usize) -> Self {
+        Self(raw)
+    }
+}
+impl From<SlotIndex> for usize {
+    fn from(slot: SlotIndex) -> Self {
+        slot.0
+    }
+}
+
 /// Defines an interface to check if a specific Extension is ready
 pub trait ReadyCheck<const N: usize> {
-    /// Check the ready state of the Extension with the given index
-    fn check(&self, idx: usize) -> bool;
+    /// Check the ready state of the Extension in the given Slot
+    fn check(&self, slot: SlotIndex) -> bool;
 
     /// Check the ready state of all the Extensions
     fn check_all(&self) -> [bool; N];
@@ -26,81 +77,1677 @@ pub trait ReadyCheck<const N: usize> {
 
 /// Defines an interface to select a specific Extension
 pub trait Select<const N: usize> {
-    /// Select the Extension corresponding to the index
-    fn select(&mut self, index: usize);
+    /// Select the Extension in the given Slot
+    fn select(&mut self, slot: SlotIndex);
 }
 
 struct CtrlExtension {
-    id: u8,
-    initialized: bool,
+    slot: SlotIndex,
+    /// The ID of the Extension, if it has been initialized
+    id: Option<ExtensionId>,
+    /// The Protocol-Version reported by the Extension, if it responded to the last probe
+    version: Option<u8>,
+}
+
+impl CtrlExtension {
+    fn new(slot: SlotIndex) -> Self {
+        Self {
+            slot,
+            id: None,
+            version: None,
+        }
+    }
+
+    /// Records the Response to a probe, an Extension speaking a different Protocol-Version is
+    /// treated as not initialized, because we can't communicate with it
+    fn record_probe(&mut self, response: Option<(bool, Option<ExtensionId>, u8)>) {
+        self.version = response.map(|(_, _, version)| version);
+        self.id = match response {
+            Some((true, id, VERSION)) => id,
+            _ => None,
+        };
+    }
 }
 
+/// The number of times reading a single Byte is attempted, while the serial would block, before
+/// giving up on the Response of an Extension
+const READ_ATTEMPTS: usize = 1_000_000;
+
+/// The number of Frames, that are discarded while waiting for the Response to a single attempt of
+/// a Request, like late Responses to earlier Requests, before the Request is send again
+const MAX_STALE_FRAMES: usize = 4;
+
+/// The Errors that can occur while communicating with the Extensions
 #[derive(Debug, PartialEq, Eq)]
-pub enum InitError<SE> {
-    NBSerialError(nb::Error<SE>),
-    SerialError(SE),
+pub enum CtrlError<SE> {
+    /// The underlying serial returned an Error
+    Serial(SE),
+    /// The received Packet could not be deserialized
+    Deserialize(packet::PacketDeserializeError),
+    /// The Request could not be serialized, like a Configure with a very long Name
+    Serialize(packet::PacketSerializeError),
+    /// The Extension responded with a Packet, that is not valid as a Response to our Request
+    UnexpectedPacket { got: PacketKind },
+    /// The Extension did not respond within [`READ_ATTEMPTS`] attempts to read a Byte
+    ReadTimeout,
+    /// There is no initialized Extension in the given Slot
+    NotInitialized,
+    /// The Extension did not acknowledge the Request, after it was send the given number of
+    /// times
+    NoAck { attempts: usize },
+}
+
+enum MetricsPollState {
+    Sending {
+        pos: usize,
+    },
+    Flushing,
+    Receiving {
+        pos: usize,
+    },
+    /// The whole Response has been received, which is only deserialized again to hand it out
+    Received,
+}
+
+/// A manually driven request for the Metrics of a single Extension.
+///
+/// Every call to [`MetricsPoll::step`] makes as much progress as possible without blocking, first
+/// sending the Request and then receiving the Response. This allows the Controller to issue a
+/// request and come back to it later, instead of blocking until the Response has arrived.
+///
+/// # Shared Serial
+/// As the Extensions share the same serial connection, only one MetricsPoll should be waiting for
+/// its Response at any time, otherwise the Responses can't be told apart.
+///
+/// # Frame Size
+/// The Request and Response are send as Frames of `FN` bytes, like the Packets of the
+/// [`Controller`], so `FN` needs to match the Frame size of the Controller.
+pub struct MetricsPoll<const FN: usize = { packet::Packet::FRAME_LEN }> {
+    request: [u8; FN],
+    buffer: [u8; FN],
+    state: MetricsPollState,
+}
+
+impl<const FN: usize> MetricsPoll<FN> {
+    const FRAME_CHECK: () = assert!(
+        FN >= packet::Packet::FRAME_LEN,
+        "A Frame needs to be able to hold a Packet"
+    );
+
+    /// Creates a new Request for the Metrics of the Extension with the given ID
+    pub fn new(id: ExtensionId) -> Result<Self, packet::PacketSerializeError> {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::FRAME_CHECK;
+
+        let mut request = [0; FN];
+        request[..packet::Packet::FRAME_LEN]
+            .copy_from_slice(&packet::Packet::metrics_request(id).serialize()?);
+
+        Ok(Self {
+            request,
+            buffer: [0; FN],
+            state: MetricsPollState::Sending { pos: 0 },
+        })
+    }
+
+    /// Advances the Request as far as possible, returns Pending if the serial would block.
+    ///
+    /// Once the Response has been received, every further call returns the same Metrics again.
+    pub fn step<'s, S>(
+        &'s mut self,
+        serial: &mut S,
+    ) -> Poll<Result<OptionsIter<'s, DataPoint<'s>>, CtrlError<S::Error>>>
+    where
+        S: embedded_hal::serial::nb::Read + embedded_hal::serial::nb::Write,
+    {
+        self.step_traced(serial, &mut ignore_packet)
+    }
+
+    /// Advances the Request, like [`MetricsPoll::step`], but calls `on_packet` once the Request
+    /// has been send and once the Response has been received. Stepping a completed Poll again
+    /// returns the same Response without reporting it again
+    pub fn step_traced<'s, S>(
+        &'s mut self,
+        serial: &mut S,
+        on_packet: &mut impl FnMut(PacketDirection, PacketKind),
+    ) -> Poll<Result<OptionsIter<'s, DataPoint<'s>>, CtrlError<S::Error>>>
+    where
+        S: embedded_hal::serial::nb::Read + embedded_hal::serial::nb::Write,
+    {
+        // Whether the Response was completed by this step, so it has not been reported yet
+        let mut received = false;
+        loop {
+            match &mut self.state {
+                MetricsPollState::Sending { pos } if *pos < self.request.len() => {
+                    match serial.write(self.request[*pos]) {
+                        Ok(_) => *pos += 1,
+                        Err(nb::Error::WouldBlock) => return Poll::Pending,
+                        Err(nb::Error::Other(e)) => return Poll::Ready(Err(CtrlError::Serial(e))),
+                    };
+                }
+                MetricsPollState::Sending { .. } => {
+                    on_packet(PacketDirection::Send, PacketKind::Metrics);
+                    self.state = MetricsPollState::Flushing;
+                }
+                MetricsPollState::Flushing => {
+                    match serial.flush() {
+                        Ok(_) => self.state = MetricsPollState::Receiving { pos: 0 },
+                        Err(nb::Error::WouldBlock) => return Poll::Pending,
+                        Err(nb::Error::Other(e)) => return Poll::Ready(Err(CtrlError::Serial(e))),
+                    };
+                }
+                MetricsPollState::Receiving { pos } if *pos < self.buffer.len() => {
+                    match serial.read() {
+                        Ok(byte) => {
+                            self.buffer[*pos] = byte;
+                            *pos += 1;
+                        }
+                        Err(nb::Error::WouldBlock) => return Poll::Pending,
+                        Err(nb::Error::Other(e)) => return Poll::Ready(Err(CtrlError::Serial(e))),
+                    };
+                }
+                MetricsPollState::Receiving { .. } => {
+                    self.state = MetricsPollState::Received;
+                    received = true;
+                    break;
+                }
+                MetricsPollState::Received => break,
+            };
+        }
+
+        let raw = self.buffer[..packet::Packet::FRAME_LEN]
+            .try_into()
+            .expect("FRAME_CHECK makes sure the Frame can hold a Packet");
+        let response = match packet::Packet::deserialize(raw) {
+            Ok(r) => r,
+            Err(e) => return Poll::Ready(Err(CtrlError::Deserialize(e))),
+        };
+        if received {
+            on_packet(PacketDirection::Receive, response.data.kind());
+        }
+
+        match response.data {
+            packet::PacketData::MetricsResponse { metrics } => Poll::Ready(Ok(metrics)),
+            other => Poll::Ready(Err(CtrlError::UnexpectedPacket { got: other.kind() })),
+        }
+    }
 }
 
-impl<const N: usize, Sel, Rc, Ser> Controller<N, Sel, Rc, Ser>
+impl<const N: usize, Sel, Rc, Ser, const FN: usize> Controller<N, Sel, Rc, Ser, FN>
 where
     Sel: Select<N>,
     Rc: ReadyCheck<N>,
     Ser: embedded_hal::serial::nb::Read + embedded_hal::serial::nb::Write,
 {
-    pub fn init(
+    /// Probes every Slot, whose ready line is asserted, and records the ID the Extension responded
+    /// with.
+    ///
+    /// # Errors
+    /// Unlike [`Controller::discover`], which skips Slots that could not be probed, this fails if
+    /// any of the ready Extensions can't be probed, because it either does not respond or
+    /// responds with anything other than a Probe-Response.
+    pub fn init(select: Sel, ready: Rc, serial: Ser) -> Result<Self, CtrlError<Ser::Error>> {
+        Self::init_traced(select, ready, serial, ignore_packet)
+    }
+}
+
+impl<const N: usize, Sel, Rc, Ser, const FN: usize, Tr> Controller<N, Sel, Rc, Ser, FN, Tr>
+where
+    Sel: Select<N>,
+    Rc: ReadyCheck<N>,
+    Ser: embedded_hal::serial::nb::Read + embedded_hal::serial::nb::Write,
+    Tr: FnMut(PacketDirection, PacketKind),
+{
+    const FRAME_CHECK: () = assert!(
+        FN >= packet::Packet::FRAME_LEN,
+        "A Frame needs to be able to hold a Packet"
+    );
+
+    /// Initializes the Controller, like [`Controller::init`], but calls `on_packet` for every
+    /// Packet send or received by the Controller, starting with the initial Probes
+    pub fn init_traced(
         mut select: Sel,
         ready: Rc,
         mut serial: Ser,
-    ) -> Result<Self, InitError<Ser::Error>> {
-        let extension = array::from_fn(|idx| {
-            if !ready.check(idx) {
-                return CtrlExtension {
-                    id: idx as u8,
-                    initialized: false,
-                };
+        mut on_packet: Tr,
+    ) -> Result<Self, CtrlError<Ser::Error>> {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::FRAME_CHECK;
+
+        let mut extensions: [CtrlExtension; N] =
+            array::from_fn(|idx| CtrlExtension::new(SlotIndex(idx)));
+
+        let mut frame = [0; FN];
+        for extension in extensions.iter_mut() {
+            if !ready.check(extension.slot) {
+                continue;
             }
 
             // Select the correct line
-            select.select(idx);
+            select.select(extension.slot);
 
             let probe_packet = packet::Packet::init_probe();
-            for byte in probe_packet.serialize() {
-                loop {
-                    if let Err(e) = serial.write(byte) {
-                        match e {
-                            nb::Error::WouldBlock => continue,
-                            _ => panic!(""),
-                        };
-                    }
-                }
-            }
-            serial.flush().unwrap();
+            Self::write_packet(&mut serial, &mut on_packet, &probe_packet)?;
 
-            let mut buffer = [0; 256];
-            let response = packet::Packet::read_blocking(&mut serial, &mut buffer).expect("");
+            let response = Self::read_packet(&mut serial, &mut on_packet, &mut frame)?;
 
-            let (status, id) = match response.data {
-                packet::PacketData::InitProbeResponse { status, id } => (status, id),
-                _ => panic!(""),
+            let probe = match response.data {
+                packet::PacketData::InitProbeResponse {
+                    status,
+                    id,
+                    version,
+                } => (status, id, version),
+                other => return Err(CtrlError::UnexpectedPacket { got: other.kind() }),
             };
 
-            match id {
-                Some(id) => CtrlExtension {
-                    id,
-                    initialized: status,
-                },
-                None => CtrlExtension {
-                    id: idx as u8,
-                    initialized: false,
-                },
-            }
-        });
+            extension.record_probe(Some(probe));
+        }
 
         Ok(Self {
             selector: select,
             ready,
             serial,
-            extensions: extension,
+            on_packet,
+            seq: 0,
+            extensions,
         })
     }
+
+    /// Probes every Slot, whose ready line is asserted, and records the ID the Extension
+    /// responded with. This can be used to rescan the Slots after an Extension was plugged in or
+    /// removed.
+    ///
+    /// A Slot is None if it is not ready, the Extension is not initialized, speaks a different
+    /// Protocol-Version or anything went wrong while probing it.
+    pub fn discover(&mut self) -> [Option<ExtensionId>; N] {
+        self.discover_with_wait(1, || {})
+    }
+
+    /// Probes every Slot, like [`Controller::discover`], but checks the ready line of every Slot
+    /// up to `attempts` times, calling `delay` between the checks, before giving up on it. This
+    /// allows Extensions that are still booting to be discovered as well.
+    ///
+    /// The ready line is always checked at least once, but note that every Slot without an
+    /// Extension waits for `attempts - 1` delays.
+    pub fn discover_with_wait(
+        &mut self,
+        attempts: usize,
+        mut delay: impl FnMut(),
+    ) -> [Option<ExtensionId>; N] {
+        let mut frame = [0; FN];
+        for idx in 0..N {
+            let slot = SlotIndex(idx);
+
+            let probe = if Self::wait_ready(&self.ready, slot, attempts, &mut delay) {
+                self.selector.select(slot);
+                Self::probe(&mut self.serial, &mut self.on_packet, &mut frame)
+            } else {
+                None
+            };
+
+            self.extensions[idx].record_probe(probe);
+        }
+
+        array::from_fn(|idx| self.extensions[idx].id)
+    }
+
+    /// Checks the ready line of the Slot up to `attempts` times, at least once, and calls `delay`
+    /// between the checks
+    fn wait_ready(ready: &Rc, slot: SlotIndex, attempts: usize, delay: &mut impl FnMut()) -> bool {
+        for attempt in 0..attempts.max(1) {
+            if attempt > 0 {
+                delay();
+            }
+
+            if ready.check(slot) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the Protocol-Version reported by the Extension in the given Slot, when it was last
+    /// probed. This can be used to find out why an Extension was not initialized.
+    pub fn version(&self, slot: SlotIndex) -> Option<u8> {
+        self.extensions.get(slot.0).and_then(|ext| ext.version)
+    }
+
+    /// Probes the currently selected Extension, without panicking on any unexpected Response
+    fn probe(
+        serial: &mut Ser,
+        on_packet: &mut Tr,
+        frame: &mut [u8; FN],
+    ) -> Option<(bool, Option<ExtensionId>, u8)> {
+        Self::write_packet(serial, on_packet, &packet::Packet::init_probe()).ok()?;
+
+        match Self::read_packet(serial, on_packet, frame).ok()?.data {
+            packet::PacketData::InitProbeResponse {
+                status,
+                id,
+                version,
+            } => Some((status, id, version)),
+            _ => None,
+        }
+    }
+
+    /// Creates a new [`MetricsPoll`] for the Extension in the given Slot, fails with
+    /// [`CtrlError::NotInitialized`] if there is no initialized Extension in that Slot
+    pub fn metrics_poll(&self, slot: SlotIndex) -> Result<MetricsPoll<FN>, CtrlError<Ser::Error>> {
+        let id = self
+            .extensions
+            .get(slot.0)
+            .and_then(|ext| ext.id)
+            .ok_or(CtrlError::NotInitialized)?;
+
+        MetricsPoll::new(id).map_err(CtrlError::Serialize)
+    }
+
+    /// Configures the Extension in the given Slot and returns the Configuration it actually
+    /// applied, which might differ from the requested one, like when the Value was clamped by
+    /// the Extension. The returned DataPoint borrows from the given Frame.
+    pub fn configure<'f>(
+        &mut self,
+        slot: SlotIndex,
+        option: DataPoint<'_>,
+        frame: &'f mut [u8; FN],
+    ) -> Result<DataPoint<'f>, CtrlError<Ser::Error>> {
+        let id = self
+            .extensions
+            .get(slot.0)
+            .and_then(|ext| ext.id)
+            .ok_or(CtrlError::NotInitialized)?;
+
+        let request = packet::Packet::configure(id, option).with_seq(self.next_seq());
+        Self::write_packet(&mut self.serial, &mut self.on_packet, &request)?;
+
+        let response = Self::read_packet(&mut self.serial, &mut self.on_packet, frame)?;
+
+        match response.data {
+            packet::PacketData::AckConfigured { applied } => Ok(applied),
+            other => Err(CtrlError::UnexpectedPacket { got: other.kind() }),
+        }
+    }
+
+    /// Configures the Extension in the given Slot, like [`Controller::configure`], but sends the
+    /// Request up to `attempts` times, at least once, until the Extension acknowledges it, see
+    /// [`Controller::send_with_retry`].
+    ///
+    /// # Retransmission
+    /// Every attempt is send with the same Sequence-Number, so an Extension that receives the
+    /// Request more than once, because only its Acknowledgement was lost, does not apply the
+    /// Configuration again, but only acknowledges it again.
+    ///
+    /// # Errors
+    /// Returns [`CtrlError::NoAck`] if none of the attempts was acknowledged.
+    pub fn configure_with_retry<'f>(
+        &mut self,
+        slot: SlotIndex,
+        option: DataPoint<'_>,
+        frame: &'f mut [u8; FN],
+        attempts: usize,
+    ) -> Result<DataPoint<'f>, CtrlError<Ser::Error>> {
+        let id = self
+            .extensions
+            .get(slot.0)
+            .and_then(|ext| ext.id)
+            .ok_or(CtrlError::NotInitialized)?;
+
+        let request = packet::Packet::configure(id, option).with_seq(self.next_seq());
+        let response =
+            self.request_with_retry(&request, PacketKind::AckConfigured, frame, attempts)?;
+
+        match response.data {
+            packet::PacketData::AckConfigured { applied } => Ok(applied),
+            other => Err(CtrlError::UnexpectedPacket { got: other.kind() }),
+        }
+    }
+
+    /// Requests the Firmware-Version and Build-Identifier of the Extension in the given Slot, see
+    /// [`PacketData::InfoResponse`](packet::PacketData::InfoResponse)
+    pub fn info(&mut self, slot: SlotIndex) -> Result<(u16, [u8; 8]), CtrlError<Ser::Error>> {
+        self.info_with_retry(slot, 1)
+    }
+
+    /// Requests the Info of the Extension in the given Slot, like [`Controller::info`], but sends
+    /// the Request up to `attempts` times, at least once, until the Extension responds, see
+    /// [`Controller::send_with_retry`].
+    ///
+    /// # Errors
+    /// Returns [`CtrlError::NoAck`] if none of the attempts was answered.
+    pub fn info_with_retry(
+        &mut self,
+        slot: SlotIndex,
+        attempts: usize,
+    ) -> Result<(u16, [u8; 8]), CtrlError<Ser::Error>> {
+        let id = self
+            .extensions
+            .get(slot.0)
+            .and_then(|ext| ext.id)
+            .ok_or(CtrlError::NotInitialized)?;
+
+        let request = packet::Packet::info_request(id).with_seq(self.next_seq());
+        let mut frame = [0; FN];
+        let response =
+            self.request_with_retry(&request, PacketKind::InfoResponse, &mut frame, attempts)?;
+
+        match response.data {
+            packet::PacketData::InfoResponse { fw_version, build } => Ok((fw_version, build)),
+            other => Err(CtrlError::UnexpectedPacket { got: other.kind() }),
+        }
+    }
+
+    /// Restarts the Extension in the given Slot, which does not respond, but lowers its ready
+    /// line until it was initialized again. The Slot is therefore no longer initialized, until it
+    /// is discovered again.
+    pub fn restart(&mut self, slot: SlotIndex) -> Result<(), CtrlError<Ser::Error>> {
+        self.restart_with_retry(slot, 1)
+    }
+
+    /// Restarts the Extension in the given Slot, like [`Controller::restart`], but sends the
+    /// Request up to `attempts` times, at least once, until the Extension lowers its ready line,
+    /// which acknowledges the Restart, see [`Controller::send_with_retry`].
+    ///
+    /// # Retransmission
+    /// An Extension only restarts once, as it no longer handles any Requests after the first
+    /// Restart, until it was initialized again.
+    ///
+    /// # Errors
+    /// Returns [`CtrlError::NoAck`] if the ready line was not lowered after any of the attempts.
+    pub fn restart_with_retry(
+        &mut self,
+        slot: SlotIndex,
+        attempts: usize,
+    ) -> Result<(), CtrlError<Ser::Error>> {
+        let id = self
+            .extensions
+            .get(slot.0)
+            .and_then(|ext| ext.id)
+            .ok_or(CtrlError::NotInitialized)?;
+
+        let request = packet::Packet::restart(id).with_seq(self.next_seq());
+        self.send_with_retry(&request, attempts, |ctrl| {
+            let lowered = (0..READ_ATTEMPTS).any(|_| !ctrl.ready.check(slot));
+            Ok(lowered)
+        })?;
+
+        self.extensions[slot.0].id = None;
+
+        Ok(())
+    }
+
+    /// Sends the Request up to `attempts` times, at least once, until `acknowledged` reports,
+    /// that the Extension acknowledged it, which is checked after every attempt.
+    ///
+    /// # Errors
+    /// Returns [`CtrlError::NoAck`] if none of the attempts was acknowledged, any other Error is
+    /// returned right away.
+    fn send_with_retry<A>(
+        &mut self,
+        request: &packet::Packet<'_>,
+        attempts: usize,
+        mut acknowledged: A,
+    ) -> Result<(), CtrlError<Ser::Error>>
+    where
+        A: FnMut(&mut Self) -> Result<bool, CtrlError<Ser::Error>>,
+    {
+        let attempts = attempts.max(1);
+        for _ in 0..attempts {
+            Self::write_packet(&mut self.serial, &mut self.on_packet, request)?;
+
+            if acknowledged(self)? {
+                return Ok(());
+            }
+        }
+
+        Err(CtrlError::NoAck { attempts })
+    }
+
+    /// Sends the Request, like [`Controller::send_with_retry`], until the Response of the
+    /// `expected` Kind with the Sequence-Number of the Request arrives and returns that Response.
+    ///
+    /// # Stale Frames
+    /// Any other valid Frame, like the late Response to an earlier Request, is discarded. A
+    /// corrupted Frame might mean that we are no longer aligned with the Frames on the serial, so
+    /// everything is discarded until the serial is idle, before the Request is send again.
+    fn request_with_retry<'f>(
+        &mut self,
+        request: &packet::Packet<'_>,
+        expected: PacketKind,
+        frame: &'f mut [u8; FN],
+        attempts: usize,
+    ) -> Result<packet::Packet<'f>, CtrlError<Ser::Error>> {
+        let seq = request.seq();
+        self.send_with_retry(request, attempts, |ctrl| {
+            // Only validate the Frames here, as the Packet can't borrow the Frame across attempts
+            for _ in 0..MAX_STALE_FRAMES {
+                let raw = match Self::read_frame(&mut ctrl.serial, frame) {
+                    Ok(raw) => raw,
+                    // A partially received Frame is dropped and the next one starts aligned
+                    Err(CtrlError::ReadTimeout) => return Ok(false),
+                    Err(e) => return Err(e),
+                };
+
+                match packet::Packet::validate(raw) {
+                    Ok(kind) if kind == expected && packet::Packet::frame_seq(raw) == seq => {
+                        return Ok(true);
+                    }
+                    Ok(_) => continue,
+                    Err(_) => {
+                        Self::resync(&mut ctrl.serial)?;
+                        return Ok(false);
+                    }
+                };
+            }
+
+            Ok(false)
+        })?;
+
+        let raw = Self::packet_part(frame);
+        let response = packet::Packet::deserialize(raw).map_err(CtrlError::Deserialize)?;
+        (self.on_packet)(PacketDirection::Receive, response.data.kind());
+
+        Ok(response)
+    }
+
+    /// Discards everything on the serial, until no Byte arrives for [`READ_ATTEMPTS`] attempts,
+    /// which only happens between Frames. Gives up after the Bytes of [`MAX_STALE_FRAMES`]
+    /// Frames, if the serial never becomes idle.
+    fn resync(serial: &mut Ser) -> Result<(), CtrlError<Ser::Error>> {
+        for _ in 0..FN * MAX_STALE_FRAMES {
+            match Self::read_byte(serial) {
+                Ok(_) => continue,
+                Err(CtrlError::ReadTimeout) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Returns the Sequence-Number for a new Request and advances it, wrapping around after 255
+    fn next_seq(&mut self) -> u8 {
+        let seq = self.seq;
+        self.seq = self.seq.wrapping_add(1);
+        seq
+    }
+
+    /// Advances the given [`MetricsPoll`] using the serial of the Controller
+    pub fn step_metrics<'p>(
+        &mut self,
+        poll: &'p mut MetricsPoll<FN>,
+    ) -> Poll<Result<OptionsIter<'p, DataPoint<'p>>, CtrlError<Ser::Error>>> {
+        poll.step_traced(&mut self.serial, &mut self.on_packet)
+    }
+
+    /// Writes the Packet as a single Frame to the serial
+    fn write_packet(
+        serial: &mut Ser,
+        on_packet: &mut Tr,
+        packet: &packet::Packet<'_>,
+    ) -> Result<(), CtrlError<Ser::Error>> {
+        let mut frame = [0; FN];
+        frame[..packet::Packet::FRAME_LEN]
+            .copy_from_slice(&packet.serialize().map_err(CtrlError::Serialize)?);
+
+        on_packet(PacketDirection::Send, packet.data.kind());
+
+        for byte in frame {
+            nb::block!(serial.write(byte)).map_err(CtrlError::Serial)?;
+        }
+        nb::block!(serial.flush()).map_err(CtrlError::Serial)?;
+
+        Ok(())
+    }
+
+    /// Reads a single Frame from the serial and deserializes the Packet contained in it
+    fn read_packet<'f>(
+        serial: &mut Ser,
+        on_packet: &mut Tr,
+        frame: &'f mut [u8; FN],
+    ) -> Result<packet::Packet<'f>, CtrlError<Ser::Error>> {
+        let raw = Self::read_frame(serial, frame)?;
+        let packet = packet::Packet::deserialize(raw).map_err(CtrlError::Deserialize)?;
+        on_packet(PacketDirection::Receive, packet.data.kind());
+
+        Ok(packet)
+    }
+
+    /// Reads a single Frame from the serial and returns the part of it containing the Packet
+    fn read_frame<'f>(
+        serial: &mut Ser,
+        frame: &'f mut [u8; FN],
+    ) -> Result<&'f [u8; packet::Packet::FRAME_LEN], CtrlError<Ser::Error>> {
+        for entry in frame.iter_mut() {
+            *entry = Self::read_byte(serial)?;
+        }
+
+        Ok(Self::packet_part(frame))
+    }
+
+    /// The part of the Frame, that contains the Packet
+    fn packet_part(frame: &[u8; FN]) -> &[u8; packet::Packet::FRAME_LEN] {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::FRAME_CHECK;
+
+        frame[..packet::Packet::FRAME_LEN]
+            .try_into()
+            .expect("FRAME_CHECK makes sure the Frame can hold a Packet")
+    }
+
+    /// Reads a single Byte from the serial, but gives up after [`READ_ATTEMPTS`] attempts
+    fn read_byte(serial: &mut Ser) -> Result<u8, CtrlError<Ser::Error>> {
+        for _ in 0..READ_ATTEMPTS {
+            match serial.read() {
+                Ok(byte) => return Ok(byte),
+                Err(nb::Error::WouldBlock) => continue,
+                Err(nb::Error::Other(e)) => return Err(CtrlError::Serial(e)),
+            };
+        }
+
+        Err(CtrlError::ReadTimeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::packet::ReceiverID;
+
+    use embedded_hal_mock::serial::{Mock as SerialMock, Transaction as SerialTransaction};
+
+    struct SingleReady;
+    impl ReadyCheck<1> for SingleReady {
+        fn check(&self, _: SlotIndex) -> bool {
+            true
+        }
+
+        fn check_all(&self) -> [bool; 1] {
+            [true]
+        }
+    }
+
+    struct NoopSelect;
+    impl Select<1> for NoopSelect {
+        fn select(&mut self, _: SlotIndex) {}
+    }
+
+    #[test]
+    fn init_larger_frame() {
+        let mut expectations = vec![];
+
+        let mut probe_frame = [0; 300];
+        probe_frame[..256].copy_from_slice(&packet::Packet::init_probe().serialize().unwrap());
+        expectations.extend(probe_frame.into_iter().map(SerialTransaction::write));
+        expectations.push(SerialTransaction::flush());
+
+        let response = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: packet::PacketData::InitProbeResponse {
+                status: true,
+                id: Some(ExtensionId(13)),
+                version: VERSION,
+            },
+        };
+        let mut response_frame = [0; 300];
+        response_frame[..256].copy_from_slice(&response.serialize().unwrap());
+        expectations.extend(response_frame.into_iter().map(SerialTransaction::read));
+
+        let serial = SerialMock::new(&expectations);
+
+        let controller: Controller<1, _, _, _, 300> =
+            Controller::init(NoopSelect, SingleReady, serial).expect("Should work");
+
+        assert_eq!(Some(ExtensionId(13)), controller.extensions[0].id);
+        assert_eq!(Some(VERSION), controller.version(SlotIndex(0)));
+
+        let mut serial = controller.serial;
+        serial.done();
+    }
+
+    #[test]
+    fn init_version_mismatch() {
+        let mut expectations = vec![];
+
+        expectations.extend(
+            packet::Packet::init_probe()
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::write),
+        );
+        expectations.push(SerialTransaction::flush());
+
+        let response = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: packet::PacketData::InitProbeResponse {
+                status: true,
+                id: Some(ExtensionId(13)),
+                version: VERSION + 1,
+            },
+        };
+        expectations.extend(
+            response
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::read),
+        );
+
+        let serial = SerialMock::new(&expectations);
+
+        let controller: Controller<1, _, _, _> =
+            Controller::init(NoopSelect, SingleReady, serial).expect("Should work");
+
+        assert_eq!(None, controller.extensions[0].id);
+        assert_eq!(Some(VERSION + 1), controller.version(SlotIndex(0)));
+
+        let mut serial = controller.serial;
+        serial.done();
+    }
+
+    /// A serial, that accepts every byte written to it, but fails every read
+    struct FaultySerial;
+
+    impl embedded_hal::serial::ErrorType for FaultySerial {
+        type Error = embedded_hal::serial::ErrorKind;
+    }
+    impl embedded_hal::serial::nb::Read for FaultySerial {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            Err(nb::Error::Other(embedded_hal::serial::ErrorKind::Overrun))
+        }
+    }
+    impl embedded_hal::serial::nb::Write for FaultySerial {
+        fn write(&mut self, _: u8) -> nb::Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn flush(&mut self) -> nb::Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn init_serial_error() {
+        let result: Result<Controller<1, _, _, _>, _> =
+            Controller::init(NoopSelect, SingleReady, FaultySerial);
+
+        assert!(matches!(
+            result,
+            Err(CtrlError::Serial(embedded_hal::serial::ErrorKind::Overrun))
+        ));
+    }
+
+    #[test]
+    fn init_unexpected_packet() {
+        let mut expectations = vec![];
+
+        expectations.extend(
+            packet::Packet::init_probe()
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::write),
+        );
+        expectations.push(SerialTransaction::flush());
+        expectations.extend(
+            packet::Packet::ack(ReceiverID::Controller)
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::read),
+        );
+
+        let serial = SerialMock::new(&expectations);
+
+        let result: Result<Controller<1, _, _, _>, _> =
+            Controller::init(NoopSelect, SingleReady, serial);
+
+        assert!(matches!(
+            result,
+            Err(CtrlError::UnexpectedPacket {
+                got: PacketKind::Acknowledge
+            })
+        ));
+    }
+
+    #[test]
+    fn init_read_timeout() {
+        let serial = ScriptedSerial {
+            write_capacity: 256,
+            ..Default::default()
+        };
+
+        let result: Result<Controller<1, _, _, _>, _> =
+            Controller::init(NoopSelect, SingleReady, serial);
+
+        assert!(matches!(result, Err(CtrlError::ReadTimeout)));
+    }
+
+    /// A serial, that only accepts a limited number of bytes and only returns the bytes it has
+    /// been given, otherwise it blocks
+    #[derive(Default)]
+    struct ScriptedSerial {
+        reads: std::collections::VecDeque<u8>,
+        written: Vec<u8>,
+        write_capacity: usize,
+    }
+
+    impl embedded_hal::serial::ErrorType for ScriptedSerial {
+        type Error = core::convert::Infallible;
+    }
+    impl embedded_hal::serial::nb::Read for ScriptedSerial {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            self.reads.pop_front().ok_or(nb::Error::WouldBlock)
+        }
+    }
+    impl embedded_hal::serial::nb::Write for ScriptedSerial {
+        fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+            if self.write_capacity == 0 {
+                return Err(nb::Error::WouldBlock);
+            }
+            self.write_capacity -= 1;
+            self.written.push(word);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> nb::Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    fn metrics_response() -> [u8; 256] {
+        packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: packet::PacketData::MetricsResponse {
+                metrics: (&[DataPoint {
+                    name: "testing",
+                    value: crate::Value::Pwm { percent: 10 },
+                }])
+                    .into(),
+            },
+        }
+        .serialize()
+        .unwrap()
+    }
+
+    #[test]
+    fn metrics_poll_phases() {
+        let mut serial = ScriptedSerial {
+            write_capacity: 100,
+            ..Default::default()
+        };
+
+        let mut poll: MetricsPoll = MetricsPoll::new(ExtensionId(13)).unwrap();
+
+        // Sending is blocked part way through
+        assert!(poll.step(&mut serial).is_pending());
+        assert_eq!(100, serial.written.len());
+
+        // Sending completes, but there is no Response yet
+        serial.write_capacity = 256;
+        assert!(poll.step(&mut serial).is_pending());
+
+        let request = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::ID(ExtensionId(13)),
+            seq: 0,
+            data: packet::PacketData::Metrics,
+        };
+        assert_eq!(&request.serialize().unwrap()[..], &serial.written[..]);
+
+        // Part of the Response arrives
+        let response = metrics_response();
+        serial.reads.extend(&response[..128]);
+        assert!(poll.step(&mut serial).is_pending());
+
+        serial.reads.extend(&response[128..]);
+        let metrics = match poll.step(&mut serial) {
+            Poll::Ready(Ok(metrics)) => metrics,
+            other => panic!("Expected Metrics, got {:?}", other),
+        };
+
+        assert_eq!(
+            vec![DataPoint {
+                name: "testing",
+                value: crate::Value::Pwm { percent: 10 },
+            }],
+            metrics.collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn metrics_poll_traced_once() {
+        let mut serial = ScriptedSerial {
+            write_capacity: 256,
+            ..Default::default()
+        };
+        serial.reads.extend(metrics_response());
+
+        let mut trace = Vec::new();
+        let mut on_packet = |direction, kind| trace.push((direction, kind));
+
+        let mut poll: MetricsPoll = MetricsPoll::new(ExtensionId(13)).unwrap();
+        assert!(matches!(
+            poll.step_traced(&mut serial, &mut on_packet),
+            Poll::Ready(Ok(_))
+        ));
+        // The completed Poll still hands out the Response, but does not report it again
+        assert!(matches!(
+            poll.step_traced(&mut serial, &mut on_packet),
+            Poll::Ready(Ok(_))
+        ));
+
+        assert_eq!(
+            vec![
+                (PacketDirection::Send, PacketKind::Metrics),
+                (PacketDirection::Receive, PacketKind::MetricsResponse),
+            ],
+            trace
+        );
+    }
+
+    #[test]
+    fn metrics_poll_larger_frame() {
+        let mut serial = ScriptedSerial {
+            write_capacity: 300,
+            ..Default::default()
+        };
+
+        let mut response = [0; 300];
+        response[..256].copy_from_slice(&metrics_response());
+        serial.reads.extend(response);
+        // The start of the next Frame must not be consumed by the Poll
+        serial.reads.push_back(0xaa);
+
+        let mut poll = MetricsPoll::<300>::new(ExtensionId(13)).unwrap();
+        assert!(matches!(poll.step(&mut serial), Poll::Ready(Ok(_))));
+
+        let mut request = [0; 300];
+        request[..256].copy_from_slice(
+            &packet::Packet::metrics_request(ExtensionId(13))
+                .serialize()
+                .unwrap(),
+        );
+        assert_eq!(&request[..], &serial.written[..]);
+        assert_eq!(vec![0xaa], serial.reads.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn metrics_poll_unexpected_response() {
+        let mut serial = ScriptedSerial {
+            write_capacity: 256,
+            ..Default::default()
+        };
+        serial.reads.extend(
+            packet::Packet::ack(ReceiverID::Controller)
+                .serialize()
+                .unwrap(),
+        );
+
+        let mut poll: MetricsPoll = MetricsPoll::new(ExtensionId(13)).unwrap();
+
+        assert!(matches!(
+            poll.step(&mut serial),
+            Poll::Ready(Err(CtrlError::UnexpectedPacket {
+                got: PacketKind::Acknowledge
+            }))
+        ));
+    }
+
+    struct FixedReady([bool; 3]);
+    impl ReadyCheck<3> for FixedReady {
+        fn check(&self, slot: SlotIndex) -> bool {
+            self.0[slot.0]
+        }
+
+        fn check_all(&self) -> [bool; 3] {
+            self.0
+        }
+    }
+
+    struct RecordSelect(Vec<SlotIndex>);
+    impl Select<3> for RecordSelect {
+        fn select(&mut self, slot: SlotIndex) {
+            self.0.push(slot);
+        }
+    }
+
+    #[test]
+    fn discover_slots() {
+        let mut expectations = vec![];
+        for id in [3, 7] {
+            expectations.extend(
+                packet::Packet::init_probe()
+                    .serialize()
+                    .unwrap()
+                    .into_iter()
+                    .map(SerialTransaction::write),
+            );
+            expectations.push(SerialTransaction::flush());
+
+            let response = packet::Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: packet::PacketData::InitProbeResponse {
+                    status: true,
+                    id: Some(ExtensionId(id)),
+                    version: VERSION,
+                },
+            };
+            expectations.extend(
+                response
+                    .serialize()
+                    .unwrap()
+                    .into_iter()
+                    .map(SerialTransaction::read),
+            );
+        }
+
+        let mut controller: Controller<3, _, _, _> = Controller {
+            selector: RecordSelect(Vec::new()),
+            ready: FixedReady([true, false, true]),
+            serial: SerialMock::new(&expectations),
+            on_packet: ignore_packet,
+            seq: 0,
+            extensions: array::from_fn(|idx| CtrlExtension::new(SlotIndex(idx))),
+        };
+
+        assert_eq!(
+            [Some(ExtensionId(3)), None, Some(ExtensionId(7))],
+            controller.discover()
+        );
+        assert_eq!(vec![SlotIndex(0), SlotIndex(2)], controller.selector.0);
+        assert_eq!(Some(ExtensionId(7)), controller.extensions[2].id);
+
+        controller.serial.done();
+    }
+
+    /// Only reports the Slot as ready, once it has been checked a given number of times, like an
+    /// Extension that is still booting
+    struct BootingReady {
+        checks: std::cell::Cell<usize>,
+        ready_after: usize,
+    }
+    impl ReadyCheck<1> for BootingReady {
+        fn check(&self, _: SlotIndex) -> bool {
+            self.checks.set(self.checks.get() + 1);
+            self.checks.get() > self.ready_after
+        }
+
+        fn check_all(&self) -> [bool; 1] {
+            [self.check(SlotIndex(0))]
+        }
+    }
+
+    #[test]
+    fn discover_waits_for_booting_slot() {
+        let mut expectations = vec![];
+        expectations.extend(
+            packet::Packet::init_probe()
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::write),
+        );
+        expectations.push(SerialTransaction::flush());
+
+        let response = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: packet::PacketData::InitProbeResponse {
+                status: true,
+                id: Some(ExtensionId(5)),
+                version: VERSION,
+            },
+        };
+        expectations.extend(
+            response
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::read),
+        );
+
+        let mut controller: Controller<1, _, _, _> = Controller {
+            selector: NoopSelect,
+            ready: BootingReady {
+                checks: std::cell::Cell::new(0),
+                ready_after: 2,
+            },
+            serial: SerialMock::new(&expectations),
+            on_packet: ignore_packet,
+            seq: 0,
+            extensions: [CtrlExtension::new(SlotIndex(0))],
+        };
+
+        let mut delays = 0;
+        assert_eq!(
+            [Some(ExtensionId(5))],
+            controller.discover_with_wait(5, || delays += 1)
+        );
+        assert_eq!(2, delays);
+        assert_eq!(3, controller.ready.checks.get());
+
+        controller.serial.done();
+    }
+
+    #[test]
+    fn discover_gives_up_on_slot() {
+        let mut controller: Controller<1, _, _, _> = Controller {
+            selector: NoopSelect,
+            ready: BootingReady {
+                checks: std::cell::Cell::new(0),
+                ready_after: 2,
+            },
+            serial: SerialMock::new(&[]),
+            on_packet: ignore_packet,
+            seq: 0,
+            extensions: [CtrlExtension::new(SlotIndex(0))],
+        };
+
+        let mut delays = 0;
+        assert_eq!([None], controller.discover_with_wait(2, || delays += 1));
+        assert_eq!(1, delays);
+
+        controller.serial.done();
+    }
+
+    #[test]
+    fn configure_returns_applied() {
+        let requested = DataPoint {
+            name: "fan",
+            value: crate::Value::Pwm { percent: 200 },
+        };
+        let applied = DataPoint {
+            name: "fan",
+            value: crate::Value::Pwm { percent: 100 },
+        };
+
+        let mut expectations = vec![];
+        let request = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::ID(ExtensionId(13)),
+            seq: 0,
+            data: packet::PacketData::Configure {
+                option: requested.clone(),
+            },
+        };
+        expectations.extend(
+            request
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::write),
+        );
+        expectations.push(SerialTransaction::flush());
+
+        let response = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: packet::PacketData::AckConfigured {
+                applied: applied.clone(),
+            },
+        };
+        expectations.extend(
+            response
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::read),
+        );
+
+        let mut controller: Controller<1, _, _, _> = Controller {
+            selector: NoopSelect,
+            ready: SingleReady,
+            serial: SerialMock::new(&expectations),
+            on_packet: ignore_packet,
+            seq: 0,
+            extensions: [CtrlExtension {
+                slot: SlotIndex(0),
+                id: Some(ExtensionId(13)),
+                version: Some(VERSION),
+            }],
+        };
+
+        let mut frame = [0; 256];
+        assert_eq!(
+            Ok(applied),
+            controller.configure(SlotIndex(0), requested, &mut frame)
+        );
+
+        controller.serial.done();
+    }
+
+    #[test]
+    fn info_returns_response() {
+        let mut expectations = vec![];
+        expectations.extend(
+            packet::Packet::info_request(ExtensionId(13))
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::write),
+        );
+        expectations.push(SerialTransaction::flush());
+
+        let response =
+            packet::Packet::to(ReceiverID::Controller).with(packet::PacketData::InfoResponse {
+                fw_version: 7,
+                build: *b"deadbeef",
+            });
+        expectations.extend(
+            response
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::read),
+        );
+
+        let mut controller: Controller<1, _, _, _> = Controller {
+            selector: NoopSelect,
+            ready: SingleReady,
+            serial: SerialMock::new(&expectations),
+            on_packet: ignore_packet,
+            seq: 0,
+            extensions: [CtrlExtension {
+                slot: SlotIndex(0),
+                id: Some(ExtensionId(13)),
+                version: Some(VERSION),
+            }],
+        };
+
+        assert_eq!(Ok((7, *b"deadbeef")), controller.info(SlotIndex(0)));
+        assert_eq!(
+            Err(CtrlError::NotInitialized),
+            controller.info(SlotIndex(1))
+        );
+
+        controller.serial.done();
+    }
+
+    /// The ready line of an Extension, that restarted
+    struct LoweredReady;
+    impl ReadyCheck<1> for LoweredReady {
+        fn check(&self, _: SlotIndex) -> bool {
+            false
+        }
+
+        fn check_all(&self) -> [bool; 1] {
+            [false]
+        }
+    }
+
+    #[test]
+    fn restart_retry_no_ack() {
+        let mut expectations = vec![];
+        for _ in 0..2 {
+            expectations.extend(
+                packet::Packet::restart(ExtensionId(13))
+                    .serialize()
+                    .unwrap()
+                    .into_iter()
+                    .map(SerialTransaction::write),
+            );
+            expectations.push(SerialTransaction::flush());
+        }
+
+        // The ready line is never lowered
+        let mut controller: Controller<1, _, _, _> = Controller {
+            selector: NoopSelect,
+            ready: SingleReady,
+            serial: SerialMock::new(&expectations),
+            on_packet: ignore_packet,
+            seq: 0,
+            extensions: [CtrlExtension {
+                slot: SlotIndex(0),
+                id: Some(ExtensionId(13)),
+                version: Some(VERSION),
+            }],
+        };
+
+        assert_eq!(
+            Err(CtrlError::NoAck { attempts: 2 }),
+            controller.restart_with_retry(SlotIndex(0), 2)
+        );
+        assert_eq!(Some(ExtensionId(13)), controller.extensions[0].id);
+
+        controller.serial.done();
+    }
+
+    #[test]
+    fn restart_clears_id() {
+        let mut expectations = vec![];
+        expectations.extend(
+            packet::Packet::restart(ExtensionId(13))
+                .with_seq(3)
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::write),
+        );
+        expectations.push(SerialTransaction::flush());
+
+        let mut controller: Controller<1, _, _, _> = Controller {
+            selector: NoopSelect,
+            ready: LoweredReady,
+            serial: SerialMock::new(&expectations),
+            on_packet: ignore_packet,
+            seq: 3,
+            extensions: [CtrlExtension {
+                slot: SlotIndex(0),
+                id: Some(ExtensionId(13)),
+                version: Some(VERSION),
+            }],
+        };
+
+        assert_eq!(Ok(()), controller.restart(SlotIndex(0)));
+        assert_eq!(None, controller.extensions[0].id);
+        assert_eq!(4, controller.seq);
+
+        assert_eq!(
+            Err(CtrlError::NotInitialized),
+            controller.restart(SlotIndex(0))
+        );
+
+        controller.serial.done();
+    }
+
+    #[test]
+    fn configure_traced() {
+        let option = DataPoint {
+            name: "fan",
+            value: crate::Value::Pwm { percent: 50 },
+        };
+
+        let mut expectations = vec![];
+        let request = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::ID(ExtensionId(13)),
+            seq: 0,
+            data: packet::PacketData::Configure {
+                option: option.clone(),
+            },
+        };
+        expectations.extend(
+            request
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::write),
+        );
+        expectations.push(SerialTransaction::flush());
+
+        let response = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: packet::PacketData::AckConfigured {
+                applied: option.clone(),
+            },
+        };
+        expectations.extend(
+            response
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::read),
+        );
+
+        let trace = std::cell::RefCell::new(Vec::new());
+        let mut controller: Controller<1, _, _, _, 256, _> = Controller {
+            selector: NoopSelect,
+            ready: SingleReady,
+            serial: SerialMock::new(&expectations),
+            on_packet: |direction, kind| trace.borrow_mut().push((direction, kind)),
+            seq: 0,
+            extensions: [CtrlExtension {
+                slot: SlotIndex(0),
+                id: Some(ExtensionId(13)),
+                version: Some(VERSION),
+            }],
+        };
+
+        let mut frame = [0; 256];
+        assert_eq!(
+            Ok(option.clone()),
+            controller.configure(SlotIndex(0), option, &mut frame)
+        );
+
+        controller.serial.done();
+        assert_eq!(
+            vec![
+                (PacketDirection::Send, PacketKind::Configure),
+                (PacketDirection::Receive, PacketKind::AckConfigured),
+            ],
+            trace.into_inner()
+        );
+    }
+
+    /// A serial, that drops the first Responses like a noisy link and only delivers the
+    /// Response once the Request has been written `deliver_on` times. The `garbage` is delivered
+    /// after the first Request instead
+    struct LossySerial {
+        response: [u8; 256],
+        deliver_on: usize,
+        garbage: Vec<u8>,
+        written: usize,
+        reads: std::collections::VecDeque<u8>,
+    }
+
+    impl embedded_hal::serial::ErrorType for LossySerial {
+        type Error = core::convert::Infallible;
+    }
+    impl embedded_hal::serial::nb::Read for LossySerial {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            self.reads.pop_front().ok_or(nb::Error::WouldBlock)
+        }
+    }
+    impl embedded_hal::serial::nb::Write for LossySerial {
+        fn write(&mut self, _: u8) -> nb::Result<(), Self::Error> {
+            self.written += 1;
+            if self.written == 256 {
+                self.reads.extend(self.garbage.drain(..));
+            }
+            if self.written == self.deliver_on * 256 {
+                self.reads.extend(self.response);
+            }
+            Ok(())
+        }
+
+        fn flush(&mut self) -> nb::Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    fn lossy_controller(
+        deliver_on: usize,
+        option: &DataPoint<'_>,
+    ) -> Controller<1, NoopSelect, SingleReady, LossySerial> {
+        let response = packet::Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: packet::PacketData::AckConfigured {
+                applied: option.clone(),
+            },
+        };
+
+        Controller {
+            selector: NoopSelect,
+            ready: SingleReady,
+            serial: LossySerial {
+                response: response.serialize().unwrap(),
+                deliver_on,
+                garbage: Vec::new(),
+                written: 0,
+                reads: std::collections::VecDeque::new(),
+            },
+            on_packet: ignore_packet,
+            seq: 0,
+            extensions: [CtrlExtension {
+                slot: SlotIndex(0),
+                id: Some(ExtensionId(13)),
+                version: Some(VERSION),
+            }],
+        }
+    }
+
+    #[test]
+    fn configure_retry_lost_ack() {
+        let option = DataPoint {
+            name: "fan",
+            value: crate::Value::Pwm { percent: 50 },
+        };
+        let mut controller = lossy_controller(2, &option);
+
+        let mut frame = [0; 256];
+        assert_eq!(
+            Ok(option.clone()),
+            controller.configure_with_retry(SlotIndex(0), option, &mut frame, 3)
+        );
+        assert_eq!(2 * 256, controller.serial.written);
+    }
+
+    #[test]
+    fn configure_retry_resync() {
+        let option = DataPoint {
+            name: "fan",
+            value: crate::Value::Pwm { percent: 50 },
+        };
+        let mut controller = lossy_controller(2, &option);
+
+        // A single garbage Byte before the Response misaligns the first Frame
+        let response = controller.serial.response;
+        controller.serial.garbage.push(0xaa);
+        controller.serial.garbage.extend(response);
+
+        let mut frame = [0; 256];
+        assert_eq!(
+            Ok(option.clone()),
+            controller.configure_with_retry(SlotIndex(0), option, &mut frame, 2)
+        );
+        assert_eq!(2 * 256, controller.serial.written);
+        assert!(controller.serial.reads.is_empty());
+    }
+
+    #[test]
+    fn configure_retry_discards_stale() {
+        let option = DataPoint {
+            name: "fan",
+            value: crate::Value::Pwm { percent: 50 },
+        };
+        let mut serial = ScriptedSerial {
+            write_capacity: 256,
+            ..Default::default()
+        };
+        // The late Acknowledgement of an earlier Request and a Response of the wrong Kind
+        serial.reads.extend(
+            packet::Packet::to(ReceiverID::Controller)
+                .with(packet::PacketData::AckConfigured {
+                    applied: DataPoint {
+                        name: "fan",
+                        value: crate::Value::Pwm { percent: 10 },
+                    },
+                })
+                .with_seq(6)
+                .serialize()
+                .unwrap(),
+        );
+        serial.reads.extend(
+            packet::Packet::to(ReceiverID::Controller)
+                .with(packet::PacketData::InfoResponse {
+                    fw_version: 1,
+                    build: [0; 8],
+                })
+                .with_seq(7)
+                .serialize()
+                .unwrap(),
+        );
+        serial.reads.extend(
+            packet::Packet::to(ReceiverID::Controller)
+                .with(packet::PacketData::AckConfigured {
+                    applied: option.clone(),
+                })
+                .with_seq(7)
+                .serialize()
+                .unwrap(),
+        );
+
+        let mut controller: Controller<1, _, _, _> = Controller {
+            selector: NoopSelect,
+            ready: SingleReady,
+            serial,
+            on_packet: ignore_packet,
+            seq: 7,
+            extensions: [CtrlExtension {
+                slot: SlotIndex(0),
+                id: Some(ExtensionId(13)),
+                version: Some(VERSION),
+            }],
+        };
+
+        let mut frame = [0; 256];
+        assert_eq!(
+            Ok(option.clone()),
+            controller.configure_with_retry(SlotIndex(0), option, &mut frame, 1)
+        );
+        assert!(controller.serial.reads.is_empty());
+    }
+
+    #[test]
+    fn configure_retry_no_ack() {
+        let option = DataPoint {
+            name: "fan",
+            value: crate::Value::Pwm { percent: 50 },
+        };
+        let mut controller = lossy_controller(3, &option);
+
+        let mut frame = [0; 256];
+        assert_eq!(
+            Err(CtrlError::NoAck { attempts: 2 }),
+            controller.configure_with_retry(SlotIndex(0), option, &mut frame, 2)
+        );
+        assert_eq!(2 * 256, controller.serial.written);
+    }
+
+    #[test]
+    fn configure_not_initialized() {
+        let mut controller: Controller<1, _, _, _> = Controller {
+            selector: NoopSelect,
+            ready: SingleReady,
+            serial: SerialMock::new(&[]),
+            on_packet: ignore_packet,
+            seq: 0,
+            extensions: [CtrlExtension::new(SlotIndex(0))],
+        };
+
+        let option = DataPoint {
+            name: "fan",
+            value: crate::Value::Pwm { percent: 20 },
+        };
+        let mut frame = [0; 256];
+        assert_eq!(
+            Err(CtrlError::NotInitialized),
+            controller.configure(SlotIndex(0), option, &mut frame)
+        );
+
+        controller.serial.done();
+    }
+
+    #[test]
+    fn slot_index_conversions() {
+        assert_eq!(SlotIndex(3), SlotIndex::from(3));
+        assert_eq!(3usize, usize::from(SlotIndex(3)));
+    }
 }
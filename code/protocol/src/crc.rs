@@ -0,0 +1,143 @@
+//! Cyclic Redundancy Checks (CRC)
+//!
+//! These are used to detect corrupted Frames, like the CRC at the end of every
+//! [`Packet`](crate::packet::Packet), but work on any Data. Both CRCs use a lookup Table, which
+//! is generated at compile time, so they only need a single lookup per byte.
+
+/// The Polynomial used by [`crc8`]
+const CRC8_POLY: u8 = 0x07;
+/// The Polynomial used by [`crc16_ccitt`]
+const CRC16_POLY: u16 = 0x1021;
+
+const CRC8_TABLE: [u8; 256] = crc8_table();
+const CRC16_TABLE: [u16; 256] = crc16_table();
+
+const fn crc8_table() -> [u8; 256] {
+    let mut table = [0; 256];
+
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ CRC8_POLY
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+
+        table[byte] = crc;
+        byte += 1;
+    }
+
+    table
+}
+
+const fn crc16_table() -> [u16; 256] {
+    let mut table = [0; 256];
+
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = (byte as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ CRC16_POLY
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+
+        table[byte] = crc;
+        byte += 1;
+    }
+
+    table
+}
+
+/// Calculates the CRC-8 (Polynomial 0x07, initial Value 0x00), also known as CRC-8/SMBUS, over
+/// the given Data
+///
+/// # Example
+/// ```rust
+/// # use protocol::crc::crc8;
+/// assert_eq!(0xf4, crc8(b"123456789"));
+/// ```
+pub fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, byte| crc8_update(crc, *byte))
+}
+
+/// Feeds a single Byte into a running CRC-8, which allows calculating the same CRC as [`crc8`]
+/// for Data that is only produced one Byte at a time, starting with a CRC of 0
+///
+/// # Example
+/// ```rust
+/// # use protocol::crc::{crc8, crc8_update};
+/// let crc = b"123456789".iter().fold(0, |crc, byte| crc8_update(crc, *byte));
+/// assert_eq!(crc8(b"123456789"), crc);
+/// ```
+pub const fn crc8_update(crc: u8, byte: u8) -> u8 {
+    CRC8_TABLE[(crc ^ byte) as usize]
+}
+
+/// Calculates the CRC-16 (Polynomial 0x1021, initial Value 0xFFFF), also known as
+/// CRC-16/CCITT-FALSE, over the given Data
+///
+/// # Example
+/// ```rust
+/// # use protocol::crc::crc16_ccitt;
+/// assert_eq!(0x29b1, crc16_ccitt(b"123456789"));
+/// ```
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    data.iter().fold(0xffff, |crc, byte| {
+        (crc << 8) ^ CRC16_TABLE[((crc >> 8) as u8 ^ byte) as usize]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Calculates the CRC-8 one bit at a time, without the lookup Table
+    fn crc8_bitwise(data: &[u8]) -> u8 {
+        data.iter().fold(0, |mut crc, byte| {
+            crc ^= byte;
+            for _ in 0..8 {
+                crc = if crc & 0x80 != 0 {
+                    (crc << 1) ^ CRC8_POLY
+                } else {
+                    crc << 1
+                };
+            }
+            crc
+        })
+    }
+
+    #[test]
+    fn crc8_known_values() {
+        assert_eq!(0x00, crc8(&[]));
+        assert_eq!(0xf4, crc8(b"123456789"));
+        assert_eq!(0x00, crc8(&[0x00]));
+        assert_eq!(0x07, crc8(&[0x01]));
+    }
+
+    #[test]
+    fn crc8_matches_bitwise() {
+        let data: [u8; 256] = core::array::from_fn(|idx| idx as u8);
+
+        for len in 0..data.len() {
+            assert_eq!(crc8_bitwise(&data[..len]), crc8(&data[..len]), "{}", len);
+        }
+        assert_eq!(crc8_bitwise(b"123456789"), crc8(b"123456789"));
+    }
+
+    #[test]
+    fn crc16_ccitt_known_values() {
+        assert_eq!(0xffff, crc16_ccitt(&[]));
+        assert_eq!(0x29b1, crc16_ccitt(b"123456789"));
+        assert_eq!(0xe1f0, crc16_ccitt(&[0x00]));
+    }
+}
@@ -1,6 +1,33 @@
 use general::AsyncSerial;
 
-use crate::{packet, ConfigOption, DataPoint, VERSION};
+use crate::{
+    packet::{self, PacketDirection, PacketKind},
+    ConfigOption, DataPoint, OptionsIter, Value, VERSION,
+};
+
+/// The smallest possible serialized DataPoint, with an empty Name and a single Byte Value
+const MIN_DATAPOINT_LEN: usize = 3;
+
+/// Turns the result of serializing a Response of the given Kind into the Frame that should
+/// actually be send, which is an Error Packet if the Response does not fit into a single Packet
+fn frame_or_error(
+    kind: PacketKind,
+    frame: Result<[u8; 256], packet::PacketSerializeError>,
+    seq: u8,
+) -> (PacketKind, [u8; 256]) {
+    match frame {
+        Ok(frame) => (kind, frame),
+        Err(_) => (
+            PacketKind::Error,
+            packet::Packet::error_frame(packet::ReceiverID::Controller, seq),
+        ),
+    }
+}
+
+/// Serializes the Response, see [`frame_or_error`]
+fn response_frame(response: &packet::Packet<'_>) -> (PacketKind, [u8; 256]) {
+    frame_or_error(response.data.kind(), response.serialize(), response.seq)
+}
 
 /// This should be used by every Extension Board
 pub struct Extension<R, Sel, Ser> {
@@ -8,7 +35,13 @@ pub struct Extension<R, Sel, Ser> {
     selection_pin: Sel,
     serial: Ser,
     /// The ID of the Extension
-    id: u8,
+    id: packet::ExtensionId,
+    /// The number of consecutive invalid Frames, after which the Extension stops running
+    error_threshold: usize,
+    /// The Firmware-Version reported in response to an Info Request
+    fw_version: u16,
+    /// The Build-Identifier reported in response to an Info Request
+    build: [u8; 8],
 }
 
 pub enum ExtensionInitError<RE, Ser>
@@ -18,6 +51,7 @@ where
     ReadyError(RE),
     ReadingSerial(packet::PacketReadError<Ser::Error>),
     WritingSerial(nb::Error<<Ser as embedded_hal::serial::ErrorType>::Error>),
+    Serialize(packet::PacketSerializeError),
 }
 
 impl<RE, Ser> core::fmt::Debug for ExtensionInitError<RE, Ser>
@@ -29,8 +63,208 @@ where
             Self::ReadyError(_) => f.debug_tuple("ExtensionInitError::ReadyError").finish(),
             Self::ReadingSerial(_) => f.debug_tuple("ExtensionInitError::ReadingSerial").finish(),
             Self::WritingSerial(_) => f.debug_tuple("ExtensionInitError::WritingSerial").finish(),
+            Self::Serialize(e) => f
+                .debug_tuple("ExtensionInitError::Serialize")
+                .field(e)
+                .finish(),
+        }
+    }
+}
+
+/// The Response to a ConfigureOptions request, serialized once up front.
+///
+/// The ConfigOptions of an Extension never change, so there is no need to serialize them again
+/// for every request.
+pub struct PreparedOptions {
+    frame: [u8; 256],
+}
+
+impl PreparedOptions {
+    /// Serializes the given ConfigOptions into a ConfigureOptionsResponse Packet, fails if they
+    /// don't fit into a single Packet
+    pub fn new(
+        options: &'static [ConfigOption<'static>],
+    ) -> Result<Self, packet::PacketSerializeError> {
+        let opts_packet = packet::Packet {
+            protocol_version: VERSION,
+            receiver: packet::ReceiverID::Controller,
+            seq: 0,
+            data: packet::PacketData::ConfigureOptionsResponse {
+                options: options.into(),
+            },
+        };
+
+        Ok(Self {
+            frame: opts_packet.serialize()?,
+        })
+    }
+
+    /// The serialized Packet, ready to be send
+    pub fn frame(&self) -> &[u8; 256] {
+        &self.frame
+    }
+
+    /// The serialized Packet with the given Sequence-Number, to echo the one of the Request
+    pub fn frame_with_seq(&self, seq: u8) -> [u8; 256] {
+        let mut frame = self.frame;
+        packet::Packet::set_seq(&mut frame, seq);
+        frame
+    }
+}
+
+/// Handles the Requests from the Controller, that need some Input from the Extension itself.
+///
+/// This is implemented for a pair of closures `(metrics, configure)`, which is what
+/// [`Extension::run`] uses, but more complex Extensions can implement it for their own State.
+pub trait PacketHandler<const MC: usize> {
+    /// Collects the current Metrics of the Extension
+    fn on_metrics(&mut self) -> [DataPoint<'static>; MC];
+
+    /// Collects the Metrics for a Request of only the Metrics with the given Names.
+    ///
+    /// The Extension only sends the returned DataPoints, whose Name was actually requested, so by
+    /// default this simply collects all the Metrics. Handlers can override this to avoid
+    /// collecting Metrics that were not requested, like reading a slow Sensor.
+    fn on_metrics_subset(&mut self, names: OptionsIter<'_, &str>) -> [DataPoint<'static>; MC] {
+        let _ = names;
+        self.on_metrics()
+    }
+
+    /// Produces the current Metrics of the Extension one after the other, each of which is
+    /// serialized directly into the Response when it is visited.
+    ///
+    /// By default this visits the Metrics collected by [`on_metrics`](Self::on_metrics), but
+    /// Handlers with expensive Sensors can override this to avoid collecting all the Metrics up
+    /// front.
+    fn visit_metrics(&mut self, visit: &mut dyn FnMut(DataPoint<'_>)) {
+        for point in self.on_metrics() {
+            visit(point);
+        }
+    }
+
+    /// Produces the Metrics for a Request of only the Metrics with the given Names, like
+    /// [`visit_metrics`](Self::visit_metrics).
+    ///
+    /// The Extension only sends the visited DataPoints, whose Name was actually requested, by
+    /// default this visits the Metrics collected by
+    /// [`on_metrics_subset`](Self::on_metrics_subset).
+    fn visit_metrics_subset(
+        &mut self,
+        names: OptionsIter<'_, &str>,
+        visit: &mut dyn FnMut(DataPoint<'_>),
+    ) {
+        for point in self.on_metrics_subset(names) {
+            visit(point);
         }
     }
+
+    /// Applies the Configuration and returns the Value that was actually applied, which is
+    /// echoed back to the Controller
+    fn on_configure(&mut self, option: DataPoint<'_>) -> Value;
+
+    /// Called right before the Extension stops running, because the Controller requested a
+    /// Restart
+    fn on_restart(&mut self) {}
+
+    /// Called whenever the Controller probes the Extension, after it was initialized, to check
+    /// that it is still alive
+    fn on_heartbeat(&mut self) {}
+
+    /// Handles the opaque Data of a Raw Packet and writes the Data of the Raw Response into
+    /// `response`, returning its Length.
+    ///
+    /// By default Extensions don't understand any Raw Packets and return `None`, which responds
+    /// with an Error Packet instead.
+    fn on_raw(&mut self, data: &[u8], response: &mut [u8; packet::RAW_MAX_LEN]) -> Option<usize> {
+        let _ = (data, response);
+        None
+    }
+}
+
+impl<const MC: usize, M, C> PacketHandler<MC> for (M, C)
+where
+    M: FnMut() -> [DataPoint<'static>; MC],
+    C: FnMut(DataPoint<'_>) -> Value,
+{
+    fn on_metrics(&mut self) -> [DataPoint<'static>; MC] {
+        (self.0)()
+    }
+
+    fn on_configure(&mut self, option: DataPoint<'_>) -> Value {
+        (self.1)(option)
+    }
+}
+
+/// The Handler used by [`Extension::run_streaming`], which visits the Metrics produced by the
+/// first closure, instead of collecting them into an Array
+struct StreamedMetrics<M, C>(M, C);
+
+impl<M, C> PacketHandler<0> for StreamedMetrics<M, C>
+where
+    M: FnMut(&mut dyn FnMut(DataPoint<'_>)),
+    C: FnMut(DataPoint<'_>) -> Value,
+{
+    fn on_metrics(&mut self) -> [DataPoint<'static>; 0] {
+        []
+    }
+
+    fn visit_metrics(&mut self, visit: &mut dyn FnMut(DataPoint<'_>)) {
+        (self.0)(visit)
+    }
+
+    fn visit_metrics_subset(
+        &mut self,
+        _: OptionsIter<'_, &str>,
+        visit: &mut dyn FnMut(DataPoint<'_>),
+    ) {
+        // The Extension already omits all the Metrics, that were not requested
+        (self.0)(visit)
+    }
+
+    fn on_configure(&mut self, option: DataPoint<'_>) -> Value {
+        (self.1)(option)
+    }
+}
+
+impl<const MC: usize, H> PacketHandler<MC> for &mut H
+where
+    H: PacketHandler<MC>,
+{
+    fn on_metrics(&mut self) -> [DataPoint<'static>; MC] {
+        (**self).on_metrics()
+    }
+
+    fn on_metrics_subset(&mut self, names: OptionsIter<'_, &str>) -> [DataPoint<'static>; MC] {
+        (**self).on_metrics_subset(names)
+    }
+
+    fn visit_metrics(&mut self, visit: &mut dyn FnMut(DataPoint<'_>)) {
+        (**self).visit_metrics(visit)
+    }
+
+    fn visit_metrics_subset(
+        &mut self,
+        names: OptionsIter<'_, &str>,
+        visit: &mut dyn FnMut(DataPoint<'_>),
+    ) {
+        (**self).visit_metrics_subset(names, visit)
+    }
+
+    fn on_configure(&mut self, option: DataPoint<'_>) -> Value {
+        (**self).on_configure(option)
+    }
+
+    fn on_restart(&mut self) {
+        (**self).on_restart()
+    }
+
+    fn on_heartbeat(&mut self) {
+        (**self).on_heartbeat()
+    }
+
+    fn on_raw(&mut self, data: &[u8], response: &mut [u8; packet::RAW_MAX_LEN]) -> Option<usize> {
+        (**self).on_raw(data, response)
+    }
 }
 
 impl<R, Sel, Ser> Extension<R, Sel, Ser>
@@ -52,9 +286,11 @@ where
                 .map_err(ExtensionInitError::ReadingSerial)?;
 
             // If we are not selected, we will not react to the packet
-            if !selection.is_high().unwrap_or(false)
-                || packet.receiver != packet::ReceiverID::Everyone
-            {
+            if !packet.receiver.matches(
+                None,
+                selection.is_high().unwrap_or(false),
+                packet.data.kind(),
+            ) {
                 continue;
             }
 
@@ -63,15 +299,16 @@ where
                     // We are still initialising, so in case the Controller asks about our init status
                     // we respond that we are not initialized and have no ID
 
-                    let response = packet::Packet {
-                        protocol_version: VERSION,
-                        receiver: packet::ReceiverID::Controller,
-                        data: packet::PacketData::InitProbeResponse {
+                    let response = packet::Packet::to(packet::ReceiverID::Controller)
+                        .with(packet::PacketData::InitProbeResponse {
                             status: false,
                             id: None,
-                        },
-                    };
-                    let response_data = response.serialize();
+                            version: VERSION,
+                        })
+                        .with_seq(packet.seq);
+                    let response_data = response
+                        .serialize()
+                        .map_err(ExtensionInitError::Serialize)?;
 
                     for byte in response_data {
                         loop {
@@ -91,9 +328,13 @@ where
                     // We just got initialised, so we will accept the provided ID and send an
                     // acknowledgement
 
-                    let response = packet::Packet::ack(packet::ReceiverID::Controller);
+                    let response =
+                        packet::Packet::ack(packet::ReceiverID::Controller).with_seq(packet.seq);
 
-                    for byte in response.serialize() {
+                    let response_data = response
+                        .serialize()
+                        .map_err(ExtensionInitError::Serialize)?;
+                    for byte in response_data {
                         serial
                             .write(byte)
                             .map_err(ExtensionInitError::WritingSerial)?;
@@ -113,88 +354,320 @@ where
             selection_pin: selection,
             serial,
             id,
+            error_threshold: Self::DEFAULT_ERROR_THRESHOLD,
+            fw_version: 0,
+            build: [0; 8],
         })
     }
 
+    /// The default number of consecutive invalid Frames, after which the Extension stops running,
+    /// see [`with_error_threshold`](Self::with_error_threshold)
+    pub const DEFAULT_ERROR_THRESHOLD: usize = 8;
+
+    /// Sets the number of consecutive Frames, that can't be deserialized, after which
+    /// [`run`](Self::run) gives up and returns.
+    ///
+    /// If the received Data stays garbage, like because of persistent Framing-Errors, the
+    /// Extension can't recover on its own. So instead it sets its Ready-Pin low and stops
+    /// running, just like for a Restart, which makes the Controller initialize it again. Any
+    /// valid Frame resets the count, so a single corrupted Frame is simply dropped.
+    pub fn with_error_threshold(mut self, threshold: usize) -> Self {
+        self.error_threshold = threshold;
+        self
+    }
+
+    /// Sets the Firmware-Version and Build-Identifier, that the Extension reports when the
+    /// Controller requests its Info, which both default to 0.
+    ///
+    /// These are usually provided at compile time, like from `env!("CARGO_PKG_VERSION")` and
+    /// the Commit-Hash of the Build.
+    pub fn with_info(mut self, fw_version: u16, build: [u8; 8]) -> Self {
+        self.fw_version = fw_version;
+        self.build = build;
+        self
+    }
+
+    /// Runs the Extension, responding to all the Requests from the Controller.
+    ///
+    /// The `configure` closure applies a Configuration and returns the Value that was actually
+    /// applied, which is echoed back to the Controller
+    ///
+    /// # Tracing
+    /// The `on_packet` closure is called with the Kind of every Packet received or send by the
+    /// Extension, including the ones addressed to other Extensions, which is useful for debugging
+    /// the Bus. Pass `|_, _| {}` if you are not interested in them.
+    ///
+    /// # Invalid Frames
+    /// Frames that can't be deserialized are reported to `on_packet` as
+    /// [`Dropped`](PacketDirection::Dropped) and then skipped, as a single corrupted Frame is
+    /// usually only transient. But after too many of them in a row the Extension stops running,
+    /// see [`with_error_threshold`](Self::with_error_threshold).
     pub async fn run<const MC: usize, M, C, ASer>(
-        mut self,
-        mut metrics: M,
-        mut configure: C,
+        self,
+        metrics: M,
+        configure: C,
         config_options: &'static [ConfigOption<'static>],
         to_async_serial: impl FnOnce(Ser) -> ASer,
+        on_packet: impl FnMut(PacketDirection, PacketKind),
     ) where
         M: FnMut() -> [DataPoint<'static>; MC],
-        C: FnMut(DataPoint<'_>),
+        C: FnMut(DataPoint<'_>) -> Value,
+        ASer: AsyncSerial<256>,
+    {
+        self.run_with(
+            (metrics, configure),
+            config_options,
+            to_async_serial,
+            on_packet,
+        )
+        .await
+    }
+
+    /// Runs the Extension, like [`run`](Self::run), but the `metrics` closure produces the
+    /// Metrics one after the other by calling the given Visitor for every DataPoint, instead of
+    /// returning all of them at once.
+    ///
+    /// Every visited DataPoint is serialized right away, so the Metrics are only read on demand
+    /// and never need to be stored in an intermediate Array.
+    ///
+    /// # Too many Metrics
+    /// If the visited Metrics don't fit into a single Packet, the Extension responds with an
+    /// Error Packet instead
+    pub async fn run_streaming<M, C, ASer>(
+        self,
+        metrics: M,
+        configure: C,
+        config_options: &'static [ConfigOption<'static>],
+        to_async_serial: impl FnOnce(Ser) -> ASer,
+        on_packet: impl FnMut(PacketDirection, PacketKind),
+    ) where
+        M: FnMut(&mut dyn FnMut(DataPoint<'_>)),
+        C: FnMut(DataPoint<'_>) -> Value,
+        ASer: AsyncSerial<256>,
+    {
+        self.run_with::<0, _, _>(
+            StreamedMetrics(metrics, configure),
+            config_options,
+            to_async_serial,
+            on_packet,
+        )
+        .await
+    }
+
+    /// Runs the Extension, like [`run`](Self::run), but lets the given [`PacketHandler`] decide
+    /// how to respond to the Requests from the Controller
+    ///
+    /// # Too many Metrics
+    /// If the Metrics don't fit into a single Packet, the Extension responds with an Error Packet
+    /// instead of a MetricsResponse. Every DataPoint takes up at least 3 Bytes plus the length of
+    /// its Name, so in debug builds this asserts that `MC` DataPoints can fit at all, which leaves
+    /// an average Name length of roughly `250 / MC - 3` Bytes.
+    pub async fn run_with<const MC: usize, H, ASer>(
+        mut self,
+        mut handler: H,
+        config_options: &'static [ConfigOption<'static>],
+        to_async_serial: impl FnOnce(Ser) -> ASer,
+        mut on_packet: impl FnMut(PacketDirection, PacketKind),
+    ) where
+        H: PacketHandler<MC>,
         ASer: AsyncSerial<256>,
     {
+        debug_assert!(
+            MC * MIN_DATAPOINT_LEN <= packet::Packet::DATA_LEN - 2,
+            "{} Metrics can never fit into a single Packet",
+            MC
+        );
+
         let mut async_serial = to_async_serial(self.serial);
 
+        // If the ConfigOptions don't fit into a single Packet, the Request for them is answered
+        // with an Error Packet
+        let prepared_options = PreparedOptions::new(config_options);
+
+        // The Frame and the applied Value of the last Configure Request, if it was the last
+        // Request for us, used to detect a Configure that was send again by the Controller. Only
+        // the exact same Frame counts as a duplicate, as a restarted Controller might reuse the
+        // Sequence-Number for a different Option or Value
+        let mut last_configure: Option<([u8; packet::Packet::FRAME_LEN], Value)> = None;
+
+        // The number of Frames in a row, that could not be deserialized
+        let mut errors = 0;
+
         loop {
             let buffer = async_serial.read().await;
-            let recv_packet = packet::Packet::deserialize(&buffer).unwrap();
-
-            match recv_packet.receiver {
-                packet::ReceiverID::Everyone if self.selection_pin.is_high().unwrap_or(false) => {}
-                packet::ReceiverID::ID(id) if id == self.id => {}
-                _ => continue,
+            let recv_packet = match packet::Packet::deserialize(&buffer) {
+                Ok(p) => {
+                    errors = 0;
+                    p
+                }
+                Err(_) => {
+                    let id = buffer[packet::Packet::HEADER_LEN];
+                    let kind = PacketKind::from_id(id).unwrap_or(PacketKind::Unknown(id));
+                    on_packet(PacketDirection::Dropped, kind);
+
+                    errors += 1;
+                    if errors >= self.error_threshold {
+                        self.ready_pin.set_low().unwrap();
+                        return;
+                    }
+                    continue;
+                }
             };
+            on_packet(PacketDirection::Receive, recv_packet.data.kind());
+
+            // The select line only matters for Packets to Everyone, so it is not read otherwise
+            let selected = recv_packet.receiver == packet::ReceiverID::Everyone
+                && self.selection_pin.is_high().unwrap_or(false);
+            if !recv_packet
+                .receiver
+                .matches(Some(self.id), selected, recv_packet.data.kind())
+            {
+                continue;
+            }
+
+            let seq = recv_packet.seq;
+            let previous_configure = last_configure.take();
 
             match recv_packet.data {
-                // All the Packets that we will just ignore and return an error for
+                // All the Packets that are only ever send to the Controller, which we answer
+                // with an Error
                 packet::PacketData::Init { .. }
                 | packet::PacketData::InitProbeResponse { .. }
                 | packet::PacketData::Acknowledge
                 | packet::PacketData::Error {}
                 | packet::PacketData::MetricsResponse { .. }
-                | packet::PacketData::ConfigureOptionsResponse { .. } => {
-                    todo!("Send Error Response")
+                | packet::PacketData::ConfigureOptionsResponse { .. }
+                | packet::PacketData::AckConfigured { .. }
+                | packet::PacketData::InfoResponse { .. } => {
+                    let error = packet::Packet::error(packet::ReceiverID::Controller).with_seq(seq);
+                    let (kind, frame) = response_frame(&error);
+
+                    on_packet(PacketDirection::Send, kind);
+                    async_serial.write(frame).await;
+                }
+                packet::PacketData::Raw { data } => {
+                    let mut response = [0; packet::RAW_MAX_LEN];
+                    let reply = match handler
+                        .on_raw(data, &mut response)
+                        .and_then(|len| response.get(..len))
+                    {
+                        Some(data) => packet::Packet::to(packet::ReceiverID::Controller)
+                            .with(packet::PacketData::Raw { data }),
+                        None => packet::Packet::error(packet::ReceiverID::Controller),
+                    }
+                    .with_seq(seq);
+                    let (kind, frame) = response_frame(&reply);
+
+                    on_packet(PacketDirection::Send, kind);
+                    async_serial.write(frame).await;
                 }
                 packet::PacketData::InitProbe => {
-                    let probe_response = packet::Packet {
-                        protocol_version: VERSION,
-                        receiver: packet::ReceiverID::Controller,
-                        data: packet::PacketData::InitProbeResponse {
+                    handler.on_heartbeat();
+
+                    let probe_response = packet::Packet::to(packet::ReceiverID::Controller)
+                        .with(packet::PacketData::InitProbeResponse {
                             status: true,
                             id: Some(self.id),
-                        },
-                    };
-                    let buffer = probe_response.serialize();
+                            version: VERSION,
+                        })
+                        .with_seq(seq);
+                    let (kind, frame) = response_frame(&probe_response);
 
-                    async_serial.write(buffer).await;
+                    on_packet(PacketDirection::Send, kind);
+                    async_serial.write(frame).await;
                 }
                 packet::PacketData::Restart => {
+                    handler.on_restart();
+
                     self.ready_pin.set_low().unwrap();
                     return;
                 }
                 packet::PacketData::Configure { option } => {
-                    configure(option);
-
-                    let ack_packet = packet::Packet::ack(packet::ReceiverID::Controller);
-                    async_serial.write(ack_packet.serialize()).await;
+                    let name = option.name;
+                    // A duplicate is only acknowledged again, as its Acknowledgement was likely
+                    // lost, but the Configuration was already applied
+                    let applied = match previous_configure {
+                        Some((last_frame, applied)) if last_frame == buffer => applied,
+                        _ => handler.on_configure(option),
+                    };
+                    last_configure = Some((buffer, applied.clone()));
+
+                    let ack_packet = packet::Packet::to(packet::ReceiverID::Controller)
+                        .with(packet::PacketData::AckConfigured {
+                            applied: DataPoint {
+                                name,
+                                value: applied,
+                            },
+                        })
+                        .with_seq(seq);
+                    let (kind, frame) = response_frame(&ack_packet);
+
+                    on_packet(PacketDirection::Send, kind);
+                    async_serial.write(frame).await;
+                }
+                packet::PacketData::ConfigureBroadcast { option } => {
+                    // Only applied if we have a matching Option and never acknowledged, as every
+                    // Extension would respond at the same time
+                    let matches = config_options.iter().any(|config| {
+                        config.name == option.name && config.ty == option.value.value_type()
+                    });
+                    if matches {
+                        handler.on_configure(option);
+                    }
+                }
+                packet::PacketData::Info => {
+                    let info_response = packet::Packet::to(packet::ReceiverID::Controller)
+                        .with(packet::PacketData::InfoResponse {
+                            fw_version: self.fw_version,
+                            build: self.build,
+                        })
+                        .with_seq(seq);
+                    let (kind, frame) = response_frame(&info_response);
+
+                    on_packet(PacketDirection::Send, kind);
+                    async_serial.write(frame).await;
                 }
                 packet::PacketData::Metrics => {
-                    let data = metrics();
-
-                    let metrics_packet = packet::Packet {
-                        protocol_version: VERSION,
-                        receiver: packet::ReceiverID::Controller,
-                        data: packet::PacketData::MetricsResponse {
-                            metrics: (&data).into(),
+                    let frame = packet::Packet::serialize_metrics_response(
+                        packet::ReceiverID::Controller,
+                        seq,
+                        |visit| handler.visit_metrics(visit),
+                    );
+                    let (kind, frame) = frame_or_error(PacketKind::MetricsResponse, frame, seq);
+
+                    on_packet(PacketDirection::Send, kind);
+                    async_serial.write(frame).await;
+                }
+                packet::PacketData::MetricsSubset { names } => {
+                    let frame = packet::Packet::serialize_metrics_response(
+                        packet::ReceiverID::Controller,
+                        seq,
+                        |visit| {
+                            // Every DataPoint, that was not requested, is omitted
+                            handler.visit_metrics_subset(names.clone(), &mut |point| {
+                                if names.clone().any(|name| name == point.name) {
+                                    visit(point);
+                                }
+                            })
                         },
-                    };
+                    );
+                    let (kind, frame) = frame_or_error(PacketKind::MetricsResponse, frame, seq);
 
-                    async_serial.write(metrics_packet.serialize()).await;
+                    on_packet(PacketDirection::Send, kind);
+                    async_serial.write(frame).await;
                 }
                 packet::PacketData::ConfigureOptions => {
-                    let opts_packet = packet::Packet {
-                        protocol_version: VERSION,
-                        receiver: packet::ReceiverID::Controller,
-                        data: packet::PacketData::ConfigureOptionsResponse {
-                            options: config_options.into(),
-                        },
-                    };
-
-                    async_serial.write(opts_packet.serialize()).await;
+                    let (kind, frame) = frame_or_error(
+                        PacketKind::ConfigureOptionsResponse,
+                        prepared_options
+                            .as_ref()
+                            .map(|prepared| prepared.frame_with_seq(seq))
+                            .map_err(|e| *e),
+                        seq,
+                    );
+
+                    on_packet(PacketDirection::Send, kind);
+                    async_serial.write(frame).await;
                 }
             };
         }
@@ -204,10 +677,12 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{
-        packet::{Packet, PacketData, ReceiverID},
+        packet::{ExtensionId, Packet, PacketData, ReceiverID},
         ConfigOption, OptionsIter, Value, ValueType,
     };
 
+    use core::future::Future;
+
     use super::*;
 
     use embedded_hal_mock::{
@@ -219,7 +694,7 @@ mod tests {
     };
 
     fn init_extension<'r, 'sel, 'ser>(
-        id: u8,
+        id: ExtensionId,
         ready: &'r mut PinMock,
         selection: &'sel mut PinMock,
         serial: &'ser mut SerialMock<u8>,
@@ -232,11 +707,13 @@ mod tests {
         let init_packet = Packet {
             protocol_version: VERSION,
             receiver: ReceiverID::Everyone,
+            seq: 0,
             data: PacketData::Init { id },
         };
         expectations.extend(
             init_packet
                 .serialize()
+                .unwrap()
                 .into_iter()
                 .map(SerialTransaction::read),
         );
@@ -244,11 +721,13 @@ mod tests {
         let ack_packet = Packet {
             protocol_version: VERSION,
             receiver: ReceiverID::Controller,
+            seq: 0,
             data: PacketData::Acknowledge,
         };
         expectations.extend(
             ack_packet
                 .serialize()
+                .unwrap()
                 .into_iter()
                 .map(SerialTransaction::write),
         );
@@ -276,11 +755,15 @@ mod tests {
             let init_packet = Packet {
                 protocol_version: VERSION,
                 receiver: ReceiverID::Everyone,
-                data: PacketData::Init { id: 13 },
+                seq: 0,
+                data: PacketData::Init {
+                    id: ExtensionId(13),
+                },
             };
             expectations.extend(
                 init_packet
                     .serialize()
+                    .unwrap()
                     .into_iter()
                     .map(SerialTransaction::read),
             );
@@ -288,11 +771,13 @@ mod tests {
             let ack_packet = Packet {
                 protocol_version: VERSION,
                 receiver: ReceiverID::Controller,
+                seq: 0,
                 data: PacketData::Acknowledge,
             };
             expectations.extend(
                 ack_packet
                     .serialize()
+                    .unwrap()
                     .into_iter()
                     .map(SerialTransaction::write),
             );
@@ -303,7 +788,7 @@ mod tests {
 
         let ext = Extension::init(&mut ready, &selection, &mut serial).expect("Should work");
 
-        assert_eq!(13, ext.id);
+        assert_eq!(ExtensionId(13), ext.id);
 
         ready.done();
         selection.done();
@@ -325,11 +810,15 @@ mod tests {
             let init_packet = Packet {
                 protocol_version: VERSION,
                 receiver: ReceiverID::Everyone,
-                data: PacketData::Init { id: 12 },
+                seq: 0,
+                data: PacketData::Init {
+                    id: ExtensionId(12),
+                },
             };
             expectations.extend(
                 init_packet
                     .serialize()
+                    .unwrap()
                     .into_iter()
                     .map(SerialTransaction::read),
             );
@@ -337,11 +826,15 @@ mod tests {
             let init_packet = Packet {
                 protocol_version: VERSION,
                 receiver: ReceiverID::Everyone,
-                data: PacketData::Init { id: 13 },
+                seq: 0,
+                data: PacketData::Init {
+                    id: ExtensionId(13),
+                },
             };
             expectations.extend(
                 init_packet
                     .serialize()
+                    .unwrap()
                     .into_iter()
                     .map(SerialTransaction::read),
             );
@@ -349,11 +842,13 @@ mod tests {
             let ack_packet = Packet {
                 protocol_version: VERSION,
                 receiver: ReceiverID::Controller,
+                seq: 0,
                 data: PacketData::Acknowledge,
             };
             expectations.extend(
                 ack_packet
                     .serialize()
+                    .unwrap()
                     .into_iter()
                     .map(SerialTransaction::write),
             );
@@ -364,7 +859,7 @@ mod tests {
 
         let ext = Extension::init(&mut ready, &selection, &mut serial).expect("Should work");
 
-        assert_eq!(13, ext.id);
+        assert_eq!(ExtensionId(13), ext.id);
 
         ready.done();
         selection.done();
@@ -377,7 +872,7 @@ mod tests {
         let mut selection = PinMock::new(&[]);
         let mut serial = SerialMock::new(&[]);
 
-        let extension = init_extension(13, &mut ready, &mut selection, &mut serial);
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
 
         extension
             .ready_pin
@@ -387,13 +882,20 @@ mod tests {
         {
             let restart_packet = Packet {
                 protocol_version: VERSION,
-                receiver: ReceiverID::ID(13),
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
                 data: PacketData::Restart,
             };
-            async_serial.read(restart_packet.serialize());
+            async_serial.read(restart_packet.serialize().unwrap());
         }
 
-        let run_fut = extension.run(|| [], |_| {}, &[], |_| &mut async_serial);
+        let run_fut = extension.run(
+            || [],
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
 
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -406,12 +908,13 @@ mod tests {
     }
 
     #[test]
-    fn run_configure() {
+    fn run_info() {
         let mut ready = PinMock::new(&[]);
         let mut selection = PinMock::new(&[]);
         let mut serial = SerialMock::new(&[]);
 
-        let extension = init_extension(13, &mut ready, &mut selection, &mut serial);
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial)
+            .with_info(0x0102, *b"1a2b3c4d");
 
         extension
             .ready_pin
@@ -419,30 +922,99 @@ mod tests {
 
         let mut async_serial = general::mocks::MockSerial::new();
         {
-            let config_packet = Packet {
+            async_serial.read(
+                Packet::info_request(ExtensionId(13))
+                    .with_seq(5)
+                    .serialize()
+                    .unwrap(),
+            );
+
+            let info_packet = Packet {
                 protocol_version: VERSION,
-                receiver: ReceiverID::ID(13),
-                data: PacketData::Configure {
-                    option: DataPoint {
-                        name: "testing",
-                        value: Value::Switch { state: true },
-                    },
+                receiver: ReceiverID::Controller,
+                seq: 5,
+                data: PacketData::InfoResponse {
+                    fw_version: 0x0102,
+                    build: *b"1a2b3c4d",
                 },
             };
-            async_serial.read(config_packet.serialize());
+            async_serial.write(info_packet.serialize().unwrap());
 
-            let ack_packet = Packet::ack(ReceiverID::Controller);
-            async_serial.write(ack_packet.serialize());
+            async_serial.read(Packet::restart(ExtensionId(13)).serialize().unwrap());
+        }
 
-            let restart_packet = Packet {
+        let run_fut = extension.run(
+            || [],
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+    }
+
+    #[test]
+    fn run_error_threshold() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial)
+            .with_error_threshold(3);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let garbage = [0xaa; 256];
+        assert!(Packet::deserialize(&garbage).is_err());
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            async_serial.read(garbage);
+            async_serial.read(garbage);
+
+            // A valid Frame resets the count of invalid Frames
+            let probe_packet = Packet {
                 protocol_version: VERSION,
-                receiver: ReceiverID::ID(13),
-                data: PacketData::Restart,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::InitProbe,
+            };
+            async_serial.read(probe_packet.serialize().unwrap());
+
+            let probe_response = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::InitProbeResponse {
+                    status: true,
+                    id: Some(ExtensionId(13)),
+                    version: VERSION,
+                },
             };
-            async_serial.read(restart_packet.serialize());
+            async_serial.write(probe_response.serialize().unwrap());
+
+            async_serial.read(garbage);
+            async_serial.read(garbage);
+            async_serial.read(garbage);
         }
 
-        let run_fut = extension.run(|| [], |_| {}, &[], |_| &mut async_serial);
+        let run_fut = extension.run(
+            || [],
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
 
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -455,12 +1027,12 @@ mod tests {
     }
 
     #[test]
-    fn run_configure_options() {
+    fn run_configure() {
         let mut ready = PinMock::new(&[]);
         let mut selection = PinMock::new(&[]);
         let mut serial = SerialMock::new(&[]);
 
-        let extension = init_extension(13, &mut ready, &mut selection, &mut serial);
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
 
         extension
             .ready_pin
@@ -468,41 +1040,47 @@ mod tests {
 
         let mut async_serial = general::mocks::MockSerial::new();
         {
-            let opts_packet = Packet {
+            let config_packet = Packet {
                 protocol_version: VERSION,
-                receiver: ReceiverID::ID(13),
-                data: PacketData::ConfigureOptions,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Configure {
+                    option: DataPoint {
+                        name: "testing",
+                        value: Value::Switch { state: true },
+                    },
+                },
             };
-            async_serial.read(opts_packet.serialize());
+            async_serial.read(config_packet.serialize().unwrap());
 
-            let opts_response_packet = Packet {
+            let ack_packet = Packet {
                 protocol_version: VERSION,
                 receiver: ReceiverID::Controller,
-                data: PacketData::ConfigureOptionsResponse {
-                    options: OptionsIter::from(&[ConfigOption {
+                seq: 0,
+                data: PacketData::AckConfigured {
+                    applied: DataPoint {
                         name: "testing",
-                        ty: ValueType::Switch,
-                    }]),
+                        value: Value::Switch { state: true },
+                    },
                 },
             };
-            async_serial.write(opts_response_packet.serialize());
+            async_serial.write(ack_packet.serialize().unwrap());
 
             let restart_packet = Packet {
                 protocol_version: VERSION,
-                receiver: ReceiverID::ID(13),
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
                 data: PacketData::Restart,
             };
-            async_serial.read(restart_packet.serialize());
+            async_serial.read(restart_packet.serialize().unwrap());
         }
 
         let run_fut = extension.run(
             || [],
-            |_| {},
-            &[ConfigOption {
-                name: "testing",
-                ty: ValueType::Switch,
-            }],
+            |option| option.value,
+            &[],
             |_| &mut async_serial,
+            |_, _| {},
         );
 
         let rt = tokio::runtime::Builder::new_current_thread()
@@ -516,56 +1094,137 @@ mod tests {
     }
 
     #[test]
-    fn run_metrics() {
+    fn run_configure_broadcast() {
+        let option = DataPoint {
+            name: "fan",
+            value: Value::Pwm { percent: 50 },
+        };
+
+        let with_fan: &'static [ConfigOption<'static>] = &[ConfigOption {
+            name: "fan",
+            ty: ValueType::Pwm,
+        }];
+        let with_switch: &'static [ConfigOption<'static>] = &[ConfigOption {
+            name: "fan",
+            ty: ValueType::Switch,
+        }];
+
+        for (config_options, expected) in [
+            (with_fan, vec![option.value.clone()]),
+            (with_switch, vec![]),
+            (&[][..], vec![]),
+        ] {
+            let mut ready = PinMock::new(&[]);
+            let mut selection = PinMock::new(&[]);
+            let mut serial = SerialMock::new(&[]);
+
+            let extension =
+                init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+            extension
+                .ready_pin
+                .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+            // The Broadcast applies, even though we are not selected
+            extension
+                .selection_pin
+                .clone()
+                .expect(&[PinTransaction::new(PinTransactionKind::Get(PinState::Low))]);
+
+            // No Acknowledgement is expected, so any Write fails the Test
+            let mut async_serial = general::mocks::MockSerial::new();
+            async_serial.read(
+                Packet::configure_broadcast(option.clone())
+                    .serialize()
+                    .unwrap(),
+            );
+            async_serial.read(Packet::restart(ExtensionId(13)).serialize().unwrap());
+
+            let mut applied = vec![];
+            let run_fut = extension.run(
+                || [],
+                |option| {
+                    applied.push(option.value.clone());
+                    option.value
+                },
+                config_options,
+                |_| &mut async_serial,
+                |_, _| {},
+            );
+
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+
+            rt.block_on(run_fut);
+
+            async_serial.assert_outstanding();
+            assert_eq!(expected, applied, "{:?}", config_options);
+        }
+    }
+
+    #[test]
+    fn run_configure_duplicate() {
         let mut ready = PinMock::new(&[]);
         let mut selection = PinMock::new(&[]);
         let mut serial = SerialMock::new(&[]);
 
-        let extension = init_extension(13, &mut ready, &mut selection, &mut serial);
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
 
         extension
             .ready_pin
             .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
 
         let mut async_serial = general::mocks::MockSerial::new();
-        {
-            let metrics_packet = Packet {
+        // The Request with Sequence-Number 4 is send twice, because its Acknowledgement was lost,
+        // followed by a new Request with the same Option
+        for seq in [4, 4, 5] {
+            let config_packet = Packet {
                 protocol_version: VERSION,
-                receiver: ReceiverID::ID(13),
-                data: PacketData::Metrics,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq,
+                data: PacketData::Configure {
+                    option: DataPoint {
+                        name: "testing",
+                        value: Value::Switch { state: true },
+                    },
+                },
             };
-            async_serial.read(metrics_packet.serialize());
+            async_serial.read(config_packet.serialize().unwrap());
 
-            let metrics_packet = Packet {
+            let ack_packet = Packet {
                 protocol_version: VERSION,
                 receiver: ReceiverID::Controller,
-                data: PacketData::MetricsResponse {
-                    metrics: OptionsIter::from(&[DataPoint {
+                seq,
+                data: PacketData::AckConfigured {
+                    applied: DataPoint {
                         name: "testing",
-                        value: Value::Pwm { percent: 10 },
-                    }]),
+                        value: Value::Switch { state: true },
+                    },
                 },
             };
-            async_serial.write(metrics_packet.serialize());
-
+            async_serial.write(ack_packet.serialize().unwrap());
+        }
+        {
             let restart_packet = Packet {
                 protocol_version: VERSION,
-                receiver: ReceiverID::ID(13),
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 6,
                 data: PacketData::Restart,
             };
-            async_serial.read(restart_packet.serialize());
+            async_serial.read(restart_packet.serialize().unwrap());
         }
 
+        let applied = std::cell::Cell::new(0);
         let run_fut = extension.run(
-            || {
-                [DataPoint {
-                    name: "testing",
-                    value: Value::Pwm { percent: 10 },
-                }]
+            || [],
+            |option| {
+                applied.set(applied.get() + 1);
+                option.value
             },
-            |_| {},
             &[],
             |_| &mut async_serial,
+            |_, _| {},
         );
 
         let rt = tokio::runtime::Builder::new_current_thread()
@@ -576,5 +1235,1139 @@ mod tests {
         rt.block_on(run_fut);
 
         async_serial.assert_outstanding();
+        assert_eq!(2, applied.get());
+    }
+
+    #[test]
+    fn run_configure_same_seq_different_value() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        // A restarted Controller starts counting from the same Sequence-Number again, but the
+        // second Request configures a different Value and is not a duplicate
+        for state in [true, false] {
+            let option = DataPoint {
+                name: "testing",
+                value: Value::Switch { state },
+            };
+
+            let config_packet = Packet::to(ReceiverID::ID(ExtensionId(13)))
+                .with(PacketData::Configure {
+                    option: option.clone(),
+                })
+                .with_seq(4);
+            async_serial.read(config_packet.serialize().unwrap());
+
+            let ack_packet = Packet::to(ReceiverID::Controller)
+                .with(PacketData::AckConfigured { applied: option })
+                .with_seq(4);
+            async_serial.write(ack_packet.serialize().unwrap());
+        }
+        async_serial.read(Packet::restart(ExtensionId(13)).serialize().unwrap());
+
+        let applied = std::cell::RefCell::new(Vec::new());
+        let run_fut = extension.run(
+            || [],
+            |option| {
+                applied.borrow_mut().push(option.value.clone());
+                option.value
+            },
+            &[],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+        assert_eq!(
+            vec![
+                Value::Switch { state: true },
+                Value::Switch { state: false }
+            ],
+            applied.into_inner()
+        );
+    }
+
+    #[test]
+    fn run_traces_configure() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let config_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Configure {
+                    option: DataPoint {
+                        name: "testing",
+                        value: Value::Switch { state: true },
+                    },
+                },
+            };
+            async_serial.read(config_packet.serialize().unwrap());
+
+            let ack_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::AckConfigured {
+                    applied: DataPoint {
+                        name: "testing",
+                        value: Value::Switch { state: true },
+                    },
+                },
+            };
+            async_serial.write(ack_packet.serialize().unwrap());
+
+            let restart_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            async_serial.read(restart_packet.serialize().unwrap());
+        }
+
+        let mut trace = Vec::new();
+        let run_fut = extension.run(
+            || [],
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |direction, kind| trace.push((direction, kind)),
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+        assert_eq!(
+            vec![
+                (PacketDirection::Receive, PacketKind::Configure),
+                (PacketDirection::Send, PacketKind::AckConfigured),
+                (PacketDirection::Receive, PacketKind::Restart),
+            ],
+            trace
+        );
+    }
+
+    #[test]
+    fn run_configure_clamped() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let config_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Configure {
+                    option: DataPoint {
+                        name: "fan",
+                        value: Value::Pwm { percent: 200 },
+                    },
+                },
+            };
+            async_serial.read(config_packet.serialize().unwrap());
+
+            let ack_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::AckConfigured {
+                    applied: DataPoint {
+                        name: "fan",
+                        value: Value::Pwm { percent: 100 },
+                    },
+                },
+            };
+            async_serial.write(ack_packet.serialize().unwrap());
+
+            let restart_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            async_serial.read(restart_packet.serialize().unwrap());
+        }
+
+        let run_fut = extension.run(
+            || [],
+            |option| match option.value {
+                Value::Pwm { percent } => Value::Pwm {
+                    percent: percent.min(100),
+                },
+                other => other,
+            },
+            &[],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+    }
+
+    #[test]
+    fn run_configure_options() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let opts_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::ConfigureOptions,
+            };
+            async_serial.read(opts_packet.serialize().unwrap());
+
+            let opts_response_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::ConfigureOptionsResponse {
+                    options: OptionsIter::from(&[ConfigOption {
+                        name: "testing",
+                        ty: ValueType::Switch,
+                    }]),
+                },
+            };
+            async_serial.write(opts_response_packet.serialize().unwrap());
+
+            let restart_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            async_serial.read(restart_packet.serialize().unwrap());
+        }
+
+        let run_fut = extension.run(
+            || [],
+            |option| option.value,
+            &[ConfigOption {
+                name: "testing",
+                ty: ValueType::Switch,
+            }],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+    }
+
+    #[test]
+    fn run_metrics() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let metrics_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Metrics,
+            };
+            async_serial.read(metrics_packet.serialize().unwrap());
+
+            let metrics_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::MetricsResponse {
+                    metrics: OptionsIter::from(&[DataPoint {
+                        name: "testing",
+                        value: Value::Pwm { percent: 10 },
+                    }]),
+                },
+            };
+            async_serial.write(metrics_packet.serialize().unwrap());
+
+            let restart_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            async_serial.read(restart_packet.serialize().unwrap());
+        }
+
+        let run_fut = extension.run(
+            || {
+                [DataPoint {
+                    name: "testing",
+                    value: Value::Pwm { percent: 10 },
+                }]
+            },
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+    }
+
+    #[test]
+    fn run_metrics_skips_garbage() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut garbage = Packet::metrics_request(ExtensionId(13))
+            .serialize()
+            .unwrap();
+        garbage[Packet::CRC_INDEX] ^= 0xff;
+        assert!(Packet::deserialize(&garbage).is_err());
+
+        let mut unknown = garbage;
+        unknown[Packet::HEADER_LEN] = 200;
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let metrics_response = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::MetricsResponse {
+                    metrics: OptionsIter::from(&[DataPoint {
+                        name: "testing",
+                        value: Value::Pwm { percent: 10 },
+                    }]),
+                },
+            };
+
+            async_serial.read(
+                Packet::metrics_request(ExtensionId(13))
+                    .serialize()
+                    .unwrap(),
+            );
+            async_serial.write(metrics_response.serialize().unwrap());
+
+            async_serial.read(garbage);
+            async_serial.read(unknown);
+
+            async_serial.read(
+                Packet::metrics_request(ExtensionId(13))
+                    .serialize()
+                    .unwrap(),
+            );
+            async_serial.write(metrics_response.serialize().unwrap());
+
+            async_serial.read(Packet::restart(ExtensionId(13)).serialize().unwrap());
+        }
+
+        let mut trace = Vec::new();
+        let run_fut = extension.run(
+            || {
+                [DataPoint {
+                    name: "testing",
+                    value: Value::Pwm { percent: 10 },
+                }]
+            },
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |direction, kind| trace.push((direction, kind)),
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+        assert_eq!(
+            vec![
+                (PacketDirection::Receive, PacketKind::Metrics),
+                (PacketDirection::Send, PacketKind::MetricsResponse),
+                (PacketDirection::Dropped, PacketKind::Metrics),
+                (PacketDirection::Dropped, PacketKind::Unknown(200)),
+                (PacketDirection::Receive, PacketKind::Metrics),
+                (PacketDirection::Send, PacketKind::MetricsResponse),
+                (PacketDirection::Receive, PacketKind::Restart),
+            ],
+            trace
+        );
+    }
+
+    #[test]
+    fn run_metrics_too_long() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let metrics_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 5,
+                data: PacketData::Metrics,
+            };
+            async_serial.read(metrics_packet.serialize().unwrap());
+
+            async_serial.write(
+                Packet::error(ReceiverID::Controller)
+                    .with_seq(5)
+                    .serialize()
+                    .unwrap(),
+            );
+
+            let restart_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            async_serial.read(restart_packet.serialize().unwrap());
+        }
+
+        let mut sent = Vec::new();
+
+        // 20 DataPoints with this Name take up 420 Bytes
+        let run_fut = extension.run(
+            || {
+                core::array::from_fn::<_, 20, _>(|_| DataPoint {
+                    name: "a long metric name",
+                    value: Value::Pwm { percent: 10 },
+                })
+            },
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |direction, kind| {
+                if direction == PacketDirection::Send {
+                    sent.push(kind);
+                }
+            },
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+        assert_eq!(vec![PacketKind::Error], sent);
+    }
+
+    #[test]
+    fn run_streaming_metrics() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let metrics_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Metrics,
+            };
+            async_serial.read(metrics_packet.serialize().unwrap());
+
+            let metrics_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::MetricsResponse {
+                    metrics: OptionsIter::from(&[
+                        DataPoint {
+                            name: "fan",
+                            value: Value::Pwm { percent: 10 },
+                        },
+                        DataPoint {
+                            name: "temp",
+                            value: Value::SignedAnalog { raw: -3 },
+                        },
+                    ]),
+                },
+            };
+            async_serial.write(metrics_packet.serialize().unwrap());
+
+            let restart_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            async_serial.read(restart_packet.serialize().unwrap());
+        }
+
+        let mut reads = 0;
+        let run_fut = extension.run_streaming(
+            |visit: &mut dyn FnMut(DataPoint<'_>)| {
+                // The Names are only borrowed for the Duration of the Visit
+                let fan = String::from("fan");
+                visit(DataPoint {
+                    name: &fan,
+                    value: Value::Pwm { percent: 10 },
+                });
+                reads += 1;
+
+                visit(DataPoint {
+                    name: "temp",
+                    value: Value::SignedAnalog { raw: -3 },
+                });
+                reads += 1;
+            },
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+        assert_eq!(2, reads);
+    }
+
+    #[test]
+    fn run_metrics_subset() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let subset_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::MetricsSubset {
+                    names: OptionsIter::from(&["humidity", "unknown", "temperature"]),
+                },
+            };
+            async_serial.read(subset_packet.serialize().unwrap());
+
+            let metrics_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::MetricsResponse {
+                    metrics: OptionsIter::from(&[
+                        DataPoint {
+                            name: "temperature",
+                            value: Value::SignedAnalog { raw: -5 },
+                        },
+                        DataPoint {
+                            name: "humidity",
+                            value: Value::Pwm { percent: 40 },
+                        },
+                    ]),
+                },
+            };
+            async_serial.write(metrics_packet.serialize().unwrap());
+
+            let restart_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            async_serial.read(restart_packet.serialize().unwrap());
+        }
+
+        let run_fut = extension.run(
+            || {
+                [
+                    DataPoint {
+                        name: "temperature",
+                        value: Value::SignedAnalog { raw: -5 },
+                    },
+                    DataPoint {
+                        name: "fan",
+                        value: Value::Pwm { percent: 10 },
+                    },
+                    DataPoint {
+                        name: "humidity",
+                        value: Value::Pwm { percent: 40 },
+                    },
+                ]
+            },
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |_, _| {},
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+    }
+
+    #[test]
+    fn prepared_options_match() {
+        static OPTIONS: [ConfigOption<'static>; 2] = [
+            ConfigOption {
+                name: "testing",
+                ty: ValueType::Switch,
+            },
+            ConfigOption {
+                name: "other",
+                ty: ValueType::Pwm,
+            },
+        ];
+
+        let prepared = PreparedOptions::new(&OPTIONS).unwrap();
+
+        let opts_packet = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: PacketData::ConfigureOptionsResponse {
+                options: OptionsIter::from(&OPTIONS),
+            },
+        };
+
+        assert_eq!(&opts_packet.serialize().unwrap(), prepared.frame());
+    }
+
+    #[test]
+    fn run_with_custom_handler() {
+        #[derive(Default)]
+        struct Recorder {
+            calls: Vec<&'static str>,
+        }
+
+        impl PacketHandler<1> for Recorder {
+            fn on_metrics(&mut self) -> [DataPoint<'static>; 1] {
+                self.calls.push("metrics");
+                [DataPoint {
+                    name: "testing",
+                    value: Value::Pwm { percent: 10 },
+                }]
+            }
+
+            fn on_configure(&mut self, option: DataPoint<'_>) -> Value {
+                self.calls.push("configure");
+                option.value
+            }
+
+            fn on_restart(&mut self) {
+                self.calls.push("restart");
+            }
+
+            fn on_heartbeat(&mut self) {
+                self.calls.push("heartbeat");
+            }
+        }
+
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let probe_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::InitProbe,
+            };
+            async_serial.read(probe_packet.serialize().unwrap());
+
+            let probe_response_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::InitProbeResponse {
+                    status: true,
+                    id: Some(ExtensionId(13)),
+                    version: VERSION,
+                },
+            };
+            async_serial.write(probe_response_packet.serialize().unwrap());
+
+            let metrics_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Metrics,
+            };
+            async_serial.read(metrics_packet.serialize().unwrap());
+
+            let metrics_response_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::MetricsResponse {
+                    metrics: OptionsIter::from(&[DataPoint {
+                        name: "testing",
+                        value: Value::Pwm { percent: 10 },
+                    }]),
+                },
+            };
+            async_serial.write(metrics_response_packet.serialize().unwrap());
+
+            let config_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Configure {
+                    option: DataPoint {
+                        name: "testing",
+                        value: Value::Switch { state: true },
+                    },
+                },
+            };
+            async_serial.read(config_packet.serialize().unwrap());
+
+            let ack_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::Controller,
+                seq: 0,
+                data: PacketData::AckConfigured {
+                    applied: DataPoint {
+                        name: "testing",
+                        value: Value::Switch { state: true },
+                    },
+                },
+            };
+            async_serial.write(ack_packet.serialize().unwrap());
+
+            let restart_packet = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            async_serial.read(restart_packet.serialize().unwrap());
+        }
+
+        let mut recorder = Recorder::default();
+        let run_fut = extension.run_with(&mut recorder, &[], |_| &mut async_serial, |_, _| {});
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+        assert_eq!(
+            vec!["heartbeat", "metrics", "configure", "restart"],
+            recorder.calls
+        );
+    }
+
+    #[test]
+    fn run_raw_default_error() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let raw_packet = Packet::to(ReceiverID::ID(ExtensionId(13)))
+                .with(PacketData::Raw { data: &[1, 2, 3] })
+                .with_seq(4);
+            async_serial.read(raw_packet.serialize().unwrap());
+            async_serial.write(
+                Packet::error(ReceiverID::Controller)
+                    .with_seq(4)
+                    .serialize()
+                    .unwrap(),
+            );
+
+            async_serial.read(Packet::restart(ExtensionId(13)).serialize().unwrap());
+        }
+
+        let mut trace = Vec::new();
+        let run_fut = extension.run(
+            || {
+                [DataPoint {
+                    name: "testing",
+                    value: Value::Pwm { percent: 10 },
+                }]
+            },
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |direction, kind| trace.push((direction, kind)),
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+        assert_eq!(
+            vec![
+                (PacketDirection::Receive, PacketKind::Raw),
+                (PacketDirection::Send, PacketKind::Error),
+                (PacketDirection::Receive, PacketKind::Restart),
+            ],
+            trace
+        );
+    }
+
+    #[test]
+    fn run_unexpected_packet() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let ack_packet = Packet::to(ReceiverID::ID(ExtensionId(13)))
+                .with(PacketData::AckConfigured {
+                    applied: DataPoint {
+                        name: "testing",
+                        value: Value::Pwm { percent: 10 },
+                    },
+                })
+                .with_seq(4);
+            async_serial.read(ack_packet.serialize().unwrap());
+            async_serial.write(
+                Packet::error(ReceiverID::Controller)
+                    .with_seq(4)
+                    .serialize()
+                    .unwrap(),
+            );
+
+            // Only the Controller receives InfoResponses
+            let info_packet = Packet::to(ReceiverID::ID(ExtensionId(13)))
+                .with(PacketData::InfoResponse {
+                    fw_version: 3,
+                    build: *b"1a2b3c4d",
+                })
+                .with_seq(5);
+            async_serial.read(info_packet.serialize().unwrap());
+            async_serial.write(
+                Packet::error(ReceiverID::Controller)
+                    .with_seq(5)
+                    .serialize()
+                    .unwrap(),
+            );
+
+            async_serial.read(Packet::restart(ExtensionId(13)).serialize().unwrap());
+        }
+
+        let mut trace = Vec::new();
+        let run_fut = extension.run(
+            || {
+                [DataPoint {
+                    name: "testing",
+                    value: Value::Pwm { percent: 10 },
+                }]
+            },
+            |option| option.value,
+            &[],
+            |_| &mut async_serial,
+            |direction, kind| trace.push((direction, kind)),
+        );
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+        assert_eq!(
+            vec![
+                (PacketDirection::Receive, PacketKind::AckConfigured),
+                (PacketDirection::Send, PacketKind::Error),
+                (PacketDirection::Receive, PacketKind::InfoResponse),
+                (PacketDirection::Send, PacketKind::Error),
+                (PacketDirection::Receive, PacketKind::Restart),
+            ],
+            trace
+        );
+    }
+
+    #[test]
+    fn run_raw_custom_handler() {
+        /// Responds to every Raw Packet with its Data reversed
+        struct Reverse;
+
+        impl PacketHandler<0> for Reverse {
+            fn on_metrics(&mut self) -> [DataPoint<'static>; 0] {
+                []
+            }
+
+            fn on_configure(&mut self, option: DataPoint<'_>) -> Value {
+                option.value
+            }
+
+            fn on_raw(
+                &mut self,
+                data: &[u8],
+                response: &mut [u8; packet::RAW_MAX_LEN],
+            ) -> Option<usize> {
+                for (target, byte) in response.iter_mut().zip(data.iter().rev()) {
+                    *target = *byte;
+                }
+                Some(data.len())
+            }
+        }
+
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let mut async_serial = general::mocks::MockSerial::new();
+        {
+            let raw_packet = Packet::to(ReceiverID::ID(ExtensionId(13)))
+                .with(PacketData::Raw { data: &[1, 2, 3] })
+                .with_seq(4);
+            async_serial.read(raw_packet.serialize().unwrap());
+
+            let raw_response = Packet::to(ReceiverID::Controller)
+                .with(PacketData::Raw { data: &[3, 2, 1] })
+                .with_seq(4);
+            async_serial.write(raw_response.serialize().unwrap());
+
+            async_serial.read(Packet::restart(ExtensionId(13)).serialize().unwrap());
+        }
+
+        let run_fut = extension.run_with(Reverse, &[], |_| &mut async_serial, |_, _| {});
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(run_fut);
+
+        async_serial.assert_outstanding();
+    }
+
+    #[test]
+    fn loopback_configure_handshake() {
+        let mut ready = PinMock::new(&[]);
+        let mut selection = PinMock::new(&[]);
+        let mut serial = SerialMock::new(&[]);
+
+        let extension = init_extension(ExtensionId(13), &mut ready, &mut selection, &mut serial);
+
+        extension
+            .ready_pin
+            .expect(&[PinTransaction::new(PinTransactionKind::Set(PinState::Low))]);
+
+        let (mut ctrl_serial, ext_serial) = general::mocks::loopback::<256>();
+
+        let applied = std::cell::Cell::new(None);
+        let ext_fut = extension.run(
+            || [],
+            |option: DataPoint<'_>| {
+                let value = match option.value {
+                    Value::Pwm { percent } => Value::Pwm {
+                        percent: percent.min(100),
+                    },
+                    other => other,
+                };
+                applied.set(Some(value.clone()));
+                value
+            },
+            &[],
+            |_| ext_serial,
+            |_, _| {},
+        );
+
+        let ctrl_fut = async move {
+            let configure = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Configure {
+                    option: DataPoint {
+                        name: "fan",
+                        value: Value::Pwm { percent: 200 },
+                    },
+                },
+            };
+            ctrl_serial.write(configure.serialize().unwrap()).await;
+
+            let response = ctrl_serial.read().await;
+            assert_eq!(
+                PacketData::AckConfigured {
+                    applied: DataPoint {
+                        name: "fan",
+                        value: Value::Pwm { percent: 100 },
+                    }
+                },
+                Packet::deserialize(&response).unwrap().data
+            );
+
+            let restart = Packet {
+                protocol_version: VERSION,
+                receiver: ReceiverID::ID(ExtensionId(13)),
+                seq: 0,
+                data: PacketData::Restart,
+            };
+            ctrl_serial.write(restart.serialize().unwrap()).await;
+        };
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        // Drives both sides at the same time, until both of them are done
+        let mut ext_fut = core::pin::pin!(ext_fut);
+        let mut ctrl_fut = core::pin::pin!(ctrl_fut);
+        let (mut ext_done, mut ctrl_done) = (false, false);
+        rt.block_on(core::future::poll_fn(|cx| {
+            if !ext_done {
+                ext_done = ext_fut.as_mut().poll(cx).is_ready();
+            }
+            if !ctrl_done {
+                ctrl_done = ctrl_fut.as_mut().poll(cx).is_ready();
+            }
+
+            if ext_done && ctrl_done {
+                core::task::Poll::Ready(())
+            } else {
+                core::task::Poll::Pending
+            }
+        }));
+
+        assert_eq!(Some(Value::Pwm { percent: 100 }), applied.take());
     }
 }
@@ -1,15 +1,21 @@
 #![cfg_attr(not(test), no_std)]
 
-const VERSION: u8 = 0;
+const VERSION: u8 = 1;
 
 pub mod packet;
 
+pub mod cobs;
+
+pub mod crc;
+
+pub mod metrics;
+
 mod extension;
 
-pub use extension::{Extension, ExtensionInitError};
+pub use extension::{Extension, ExtensionInitError, PacketHandler, PreparedOptions};
 
 mod controller;
-pub use controller::{Controller, ReadyCheck, Select};
+pub use controller::{Controller, CtrlError, MetricsPoll, ReadyCheck, Select, SlotIndex};
 
 mod traits;
 pub use traits::*;
@@ -0,0 +1,348 @@
+//! Owned storage for the Metrics received from an Extension.
+//!
+//! The [`DataPoint`]s in a [`PacketData::MetricsResponse`] borrow from the receive buffer, so
+//! they can't be kept around once the buffer is reused for the next Packet. The [`MetricsStore`]
+//! copies them into fixed size owned storage instead, so the Controller always has the latest
+//! snapshot of the Metrics of an Extension, without needing any allocation.
+
+use general::string::FixedStr;
+
+use crate::{packet::PacketData, DataPoint, Value};
+
+/// The maximum length of the Name of a stored Metric, longer Names are truncated
+pub const NAME_MAX: usize = 32;
+
+/// A single Metric stored in a [`MetricsStore`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StoredMetric {
+    name: FixedStr<NAME_MAX>,
+    value: Value,
+}
+
+impl StoredMetric {
+    fn new(name: &str, value: Value) -> Self {
+        Self {
+            name: truncate_name(name)
+                .parse()
+                .expect("The Name was truncated to NAME_MAX bytes"),
+            value,
+        }
+    }
+
+    /// The (possibly truncated) Name of the Metric
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The latest Value of the Metric
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+/// Truncates the Name to at most [`NAME_MAX`] bytes, without splitting a char
+fn truncate_name(name: &str) -> &str {
+    let mut len = name.len().min(NAME_MAX);
+    while !name.is_char_boundary(len) {
+        len -= 1;
+    }
+    &name[..len]
+}
+
+/// Hands every Metric of the given MetricsResponse to the Callback, while the Buffer it was
+/// received into is still borrowed, so the Caller can copy the Names into its own storage, like a
+/// [`FixedStr`]. Returns the number of Metrics in the Response.
+///
+/// # Example
+/// ```rust
+/// # use protocol::{metrics::for_each_point, packet::{Packet, PacketData}, DataPoint, Value};
+/// let mut buffer = [0; Packet::DATA_LEN];
+/// PacketData::MetricsResponse {
+///     metrics: (&[DataPoint {
+///         name: "fan",
+///         value: Value::Pwm { percent: 40 },
+///     }]).into(),
+/// }
+/// .serialize(&mut buffer)
+/// .unwrap();
+///
+/// let response = PacketData::parse(0, &buffer).unwrap();
+/// let mut total = 0;
+/// for_each_point(&response, |_, value| {
+///     if let Value::Pwm { percent } = value {
+///         total += percent;
+///     }
+/// })
+/// .unwrap();
+/// assert_eq!(40, total);
+/// ```
+pub fn for_each_point<F>(response: &PacketData<'_>, mut func: F) -> Result<usize, MetricsStoreError>
+where
+    F: FnMut(&str, Value),
+{
+    let metrics = match response {
+        PacketData::MetricsResponse { metrics } => metrics.clone(),
+        _ => return Err(MetricsStoreError::NotMetricsResponse),
+    };
+
+    let mut count = 0;
+    for DataPoint { name, value } in metrics {
+        func(name, value);
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MetricsStoreError {
+    /// The given PacketData was not a MetricsResponse
+    NotMetricsResponse,
+    /// Not all the Metrics fit into the Store, the ones that did fit were still stored
+    Full,
+}
+
+/// Stores the latest Values for up to `N` distinct Metrics of a single Extension
+pub struct MetricsStore<const N: usize> {
+    metrics: [Option<StoredMetric>; N],
+}
+
+impl<const N: usize> MetricsStore<N> {
+    const EMPTY: Option<StoredMetric> = None;
+
+    /// Creates a new empty Store
+    pub const fn new() -> Self {
+        Self {
+            metrics: [Self::EMPTY; N],
+        }
+    }
+
+    /// Copies all the Metrics from the given MetricsResponse into the Store, replacing the Values
+    /// of Metrics with the same Name. Returns the number of Metrics that were stored.
+    pub fn update(&mut self, response: &PacketData<'_>) -> Result<usize, MetricsStoreError> {
+        let mut stored = 0;
+        let mut full = false;
+        for_each_point(response, |name, value| {
+            if self.insert(name, value) {
+                stored += 1;
+            } else {
+                full = true;
+            }
+        })?;
+
+        if full {
+            return Err(MetricsStoreError::Full);
+        }
+        Ok(stored)
+    }
+
+    fn insert(&mut self, name: &str, value: Value) -> bool {
+        let name = truncate_name(name);
+
+        if let Some(existing) = self.metrics.iter_mut().flatten().find(|m| m.name() == name) {
+            existing.value = value;
+            return true;
+        }
+
+        match self.metrics.iter_mut().find(|m| m.is_none()) {
+            Some(slot) => {
+                *slot = Some(StoredMetric::new(name, value));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the latest Value of the Metric with the given Name
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        let name = truncate_name(name);
+        self.iter().find(|m| m.name() == name).map(|m| m.value())
+    }
+
+    /// Iterates over all the stored Metrics
+    pub fn iter(&self) -> impl Iterator<Item = &StoredMetric> {
+        self.metrics.iter().flatten()
+    }
+
+    /// The number of stored Metrics
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the stored Metrics
+    pub fn clear(&mut self) {
+        self.metrics = [Self::EMPTY; N];
+    }
+}
+
+impl<const N: usize> Default for MetricsStore<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::packet::Packet;
+
+    fn response_buffer(metrics: &[DataPoint<'_>]) -> [u8; Packet::DATA_LEN] {
+        let mut buffer = [0; Packet::DATA_LEN];
+        PacketData::MetricsResponse {
+            metrics: metrics.into(),
+        }
+        .serialize(&mut buffer)
+        .unwrap();
+        buffer
+    }
+
+    #[test]
+    fn store_outlives_buffer() {
+        let mut buffer = response_buffer(&[
+            DataPoint {
+                name: "fan",
+                value: Value::Pwm { percent: 40 },
+            },
+            DataPoint {
+                name: "power",
+                value: Value::Switch { state: true },
+            },
+        ]);
+
+        let mut store = MetricsStore::<4>::new();
+
+        let response = PacketData::parse(0, &buffer).expect("Should work");
+        assert_eq!(Ok(2), store.update(&response));
+
+        buffer.fill(0xff);
+
+        assert_eq!(2, store.len());
+        assert_eq!(Some(&Value::Pwm { percent: 40 }), store.get("fan"));
+        assert_eq!(Some(&Value::Switch { state: true }), store.get("power"));
+    }
+
+    #[test]
+    fn for_each_point_into_fixed() {
+        let buffer = response_buffer(&[
+            DataPoint {
+                name: "fan",
+                value: Value::Pwm { percent: 40 },
+            },
+            DataPoint {
+                name: "power",
+                value: Value::Switch { state: true },
+            },
+        ]);
+        let response = PacketData::parse(0, &buffer).expect("Should work");
+
+        let mut points: [(FixedStr<8>, Option<Value>); 2] = Default::default();
+        let mut index = 0;
+        let count = for_each_point(&response, |name, value| {
+            points[index] = (name.parse().unwrap(), Some(value));
+            index += 1;
+        });
+
+        assert_eq!(Ok(2), count);
+        assert_eq!(
+            [
+                ("fan".parse().unwrap(), Some(Value::Pwm { percent: 40 })),
+                (
+                    "power".parse().unwrap(),
+                    Some(Value::Switch { state: true })
+                ),
+            ],
+            points
+        );
+    }
+
+    #[test]
+    fn for_each_point_wrong_packet() {
+        assert_eq!(
+            Err(MetricsStoreError::NotMetricsResponse),
+            for_each_point(&PacketData::Metrics, |_, _| panic!("No Metrics"))
+        );
+    }
+
+    #[test]
+    fn update_replaces_existing() {
+        let mut store = MetricsStore::<1>::new();
+
+        let buffer = response_buffer(&[DataPoint {
+            name: "fan",
+            value: Value::Pwm { percent: 40 },
+        }]);
+        let response = PacketData::parse(0, &buffer).expect("Should work");
+        assert_eq!(Ok(1), store.update(&response));
+
+        let buffer = response_buffer(&[DataPoint {
+            name: "fan",
+            value: Value::Pwm { percent: 80 },
+        }]);
+        let response = PacketData::parse(0, &buffer).expect("Should work");
+        assert_eq!(Ok(1), store.update(&response));
+
+        assert_eq!(1, store.len());
+        assert_eq!(Some(&Value::Pwm { percent: 80 }), store.get("fan"));
+    }
+
+    #[test]
+    fn update_full() {
+        let mut store = MetricsStore::<1>::new();
+
+        let buffer = response_buffer(&[
+            DataPoint {
+                name: "fan",
+                value: Value::Pwm { percent: 40 },
+            },
+            DataPoint {
+                name: "power",
+                value: Value::Switch { state: true },
+            },
+        ]);
+        let response = PacketData::parse(0, &buffer).expect("Should work");
+
+        assert_eq!(Err(MetricsStoreError::Full), store.update(&response));
+        assert_eq!(Some(&Value::Pwm { percent: 40 }), store.get("fan"));
+        assert_eq!(None, store.get("power"));
+    }
+
+    #[test]
+    fn update_wrong_packet() {
+        let mut store = MetricsStore::<1>::new();
+
+        assert_eq!(
+            Err(MetricsStoreError::NotMetricsResponse),
+            store.update(&PacketData::Metrics)
+        );
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn long_name_truncated() {
+        let mut store = MetricsStore::<1>::new();
+
+        let name = "a-very-long-metric-name-that-does-not-fit";
+        let buffer = response_buffer(&[DataPoint {
+            name,
+            value: Value::Switch { state: false },
+        }]);
+        let response = PacketData::parse(0, &buffer).expect("Should work");
+        assert_eq!(Ok(1), store.update(&response));
+
+        let stored = store.iter().next().unwrap();
+        assert_eq!(&name[..NAME_MAX], stored.name());
+        assert_eq!(Some(&Value::Switch { state: false }), store.get(name));
+    }
+
+    #[test]
+    fn truncate_at_char_boundary() {
+        let name = "bäääääääääääääääää";
+        assert_eq!(35, name.len());
+        assert_eq!(31, truncate_name(name).len());
+    }
+}
@@ -1,46 +1,98 @@
-use crate::Sendable;
+use crate::{BufferTooSmall, NameSerializeError, Sendable};
 
 /// The Values possible for Configuration-Options and Metrics
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Value {
-    Switch { state: bool },
-    Pwm { percent: u8 },
+    Switch {
+        state: bool,
+    },
+    Pwm {
+        percent: u8,
+    },
+    /// A signed reading, like a temperature that can also be below zero
+    SignedAnalog {
+        raw: i16,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ValueDeserializeError {
     UnknownType(u8),
+    /// The Buffer ended before the Value
+    MissingData,
+}
+
+impl From<()> for ValueDeserializeError {
+    fn from(_: ()) -> Self {
+        Self::MissingData
+    }
 }
 
 impl Value {
-    pub fn serialize(&self) -> [u8; 2] {
-        let mut buffer = [0; 2];
+    /// Checks if the Value is in the range allowed for its Type, like a Pwm percentage of at
+    /// most 100
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Switch { .. } => true,
+            Self::Pwm { percent } => *percent <= 100,
+            Self::SignedAnalog { .. } => true,
+        }
+    }
+
+    /// Get the Type of the Value
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Self::Switch { .. } => ValueType::Switch,
+            Self::Pwm { .. } => ValueType::Pwm,
+            Self::SignedAnalog { .. } => ValueType::SignedAnalog,
+        }
+    }
+}
+
+/// A Value is serialized as the Tag-Byte of its [`ValueType`] followed by its Data, which is a
+/// single Byte for Switch and Pwm and two Bytes for SignedAnalog
+impl<'r> Sendable<'r> for Value {
+    type SerError = BufferTooSmall;
+    type DeSerError = ValueDeserializeError;
+
+    fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+        let rest = self.value_type().serialize(buffer)?;
 
         match self {
             Self::Switch { state } => {
-                buffer[0] = 0;
-                buffer[1] = u8::from(*state);
+                let (first, rest) = rest.split_first_mut().ok_or(BufferTooSmall)?;
+                *first = u8::from(*state);
+                Ok(rest)
             }
             Self::Pwm { percent } => {
-                buffer[0] = 1;
-                buffer[1] = *percent;
+                let (first, rest) = rest.split_first_mut().ok_or(BufferTooSmall)?;
+                *first = *percent;
+                Ok(rest)
             }
-        };
-
-        buffer
+            Self::SignedAnalog { raw } => raw.serialize(rest),
+        }
     }
 
-    pub fn deserialize(buffer: &[u8; 2]) -> Result<Self, ValueDeserializeError> {
-        match buffer[0] {
-            0 => {
-                let state = buffer[1] == 1;
-                Ok(Self::Switch { state })
+    fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
+        let (ty, rest) = ValueType::deserialize(buffer)?;
+
+        match ty {
+            ValueType::Switch => {
+                let (state, rest) = rest
+                    .split_first()
+                    .ok_or(ValueDeserializeError::MissingData)?;
+                Ok((Self::Switch { state: *state == 1 }, rest))
+            }
+            ValueType::Pwm => {
+                let (percent, rest) = rest
+                    .split_first()
+                    .ok_or(ValueDeserializeError::MissingData)?;
+                Ok((Self::Pwm { percent: *percent }, rest))
             }
-            1 => {
-                let percent = buffer[1];
-                Ok(Self::Pwm { percent })
+            ValueType::SignedAnalog => {
+                let (raw, rest) = i16::deserialize(rest)?;
+                Ok((Self::SignedAnalog { raw }, rest))
             }
-            val => Err(ValueDeserializeError::UnknownType(val)),
         }
     }
 }
@@ -62,8 +114,21 @@ impl From<()> for DataPointDeserializeError {
     }
 }
 
+/// The Error returned when constructing an invalid [`DataPoint`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum DataPointError {
+    /// The Name is longer than the 255 bytes, that can be stored in its single length byte
+    NameTooLong(usize),
+    /// The Value is outside of the range allowed for its Type
+    ValueOutOfRange(Value),
+}
+
 /// A combination of Name and Value, that can be used to represent a Metric or a Configuration
 /// depending on the Context
+///
+/// # Construction
+/// The Fields are public, so that received DataPoints can be inspected, but new DataPoints should
+/// be constructed using [`DataPoint::new`], which makes sure that they can actually be send.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct DataPoint<'r> {
     /// The Name for the DataPoint
@@ -72,33 +137,67 @@ pub struct DataPoint<'r> {
     pub value: Value,
 }
 
-impl<'r> Sendable<'r> for DataPoint<'r> {
-    type SerError = ();
-    type DeSerError = DataPointDeserializeError;
-
-    fn serialize<'b>(&self, mut buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
-        buffer = self.name.serialize(buffer)?;
-
-        if buffer.len() < 2 {
-            return Err(());
+impl<'r> DataPoint<'r> {
+    /// Creates a new DataPoint, after checking that the Name fits into a Packet and the Value is
+    /// in the range allowed for its Type
+    pub fn new(name: &'r str, value: Value) -> Result<Self, DataPointError> {
+        if name.len() > u8::MAX as usize {
+            return Err(DataPointError::NameTooLong(name.len()));
+        }
+        if !value.is_valid() {
+            return Err(DataPointError::ValueOutOfRange(value));
         }
-        buffer[0..2].copy_from_slice(&self.value.serialize());
 
-        Ok(&mut buffer[2..])
+        Ok(Self { name, value })
     }
+}
 
-    fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
-        let (name, buffer) = Sendable::deserialize(buffer)?;
-        let value = Value::deserialize(buffer[0..2].try_into().unwrap())?;
-
-        Ok((Self { name, value }, &buffer[2..]))
+// A DataPoint is serialized as its Name followed by its Value
+crate::sendable_struct! {
+    DataPoint<'r> {
+        name: &'r str,
+        value: Value,
     }
+    SerError = NameSerializeError;
+    DeSerError = DataPointDeserializeError;
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ValueType {
     Switch,
     Pwm,
+    SignedAnalog,
+}
+
+impl<'r> Sendable<'r> for ValueType {
+    type SerError = BufferTooSmall;
+    type DeSerError = ValueDeserializeError;
+
+    fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+        if buffer.is_empty() {
+            return Err(BufferTooSmall);
+        }
+
+        buffer[0] = match self {
+            Self::Switch => 0,
+            Self::Pwm => 1,
+            Self::SignedAnalog => 2,
+        };
+
+        Ok(&mut buffer[1..])
+    }
+
+    fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
+        let ty = match buffer.first() {
+            Some(0) => Self::Switch,
+            Some(1) => Self::Pwm,
+            Some(2) => Self::SignedAnalog,
+            Some(other) => return Err(ValueDeserializeError::UnknownType(*other)),
+            None => return Err(ValueDeserializeError::MissingData),
+        };
+
+        Ok((ty, &buffer[1..]))
+    }
 }
 
 /// A single Configuration option provided by an Extension-Board. This allows you to communicate
@@ -112,32 +211,30 @@ pub struct ConfigOption<'r> {
 }
 
 impl<'r> Sendable<'r> for ConfigOption<'r> {
-    type SerError = ();
+    type SerError = NameSerializeError;
     type DeSerError = ();
 
     fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
         let rest = self.name.serialize(buffer)?;
-        rest[0] = match &self.ty {
-            ValueType::Switch => 0,
-            ValueType::Pwm => 1,
-        };
-
-        Ok(&mut rest[1..])
+        Ok(self.ty.serialize(rest)?)
     }
 
     fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
         let (name, rest) = Sendable::deserialize(buffer)?;
-        let ty = match rest[0] {
-            0 => ValueType::Switch,
-            1 => ValueType::Pwm,
-            _ => todo!(),
-        };
+        let (ty, rest) = ValueType::deserialize(rest).map_err(|_| ())?;
 
-        Ok((Self { name, ty }, &rest[1..]))
+        Ok((Self { name, ty }, rest))
     }
 }
 
 /// An Iterator for Data being send or received, allowing for lists in the Packets
+///
+/// # Forwarding
+/// Serializing a received List simply copies the original received Bytes, which is only correct
+/// if the Items are encoded the same way on both ends, meaning that both Boards speak the same
+/// Protocol-Version. There is only a single Encoding of the Items so far and Frames of any other
+/// Version are already rejected by [`Packet::validate`](crate::packet::Packet::validate), so a
+/// List is never forwarded to a Peer with a different Version.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum OptionsIter<'r, T> {
     Received { buffer: &'r [u8], length: usize },
@@ -211,6 +308,10 @@ impl<E> From<E> for OptionsIterDeserializeError<E> {
 #[derive(Debug, PartialEq, Eq)]
 pub enum OptionsIterSerializeError<E> {
     EmptyBuffer,
+    /// The List contains more Items than can be stored in its single length byte
+    TooManyItems(usize),
+    /// The Buffer is too small to hold the serialized Items
+    BufferTooSmall,
     InnerError(E),
 }
 impl<E> From<E> for OptionsIterSerializeError<E> {
@@ -233,7 +334,8 @@ where
 
         match self {
             Self::Fixed { data, .. } => {
-                buffer[0] = data.len() as u8;
+                buffer[0] = u8::try_from(data.len())
+                    .map_err(|_| OptionsIterSerializeError::TooManyItems(data.len()))?;
 
                 buffer = &mut buffer[1..];
                 for item in data.iter() {
@@ -246,9 +348,13 @@ where
                 buffer: r_buf,
                 length,
             } => {
-                buffer[0] = *length as u8;
+                buffer[0] = u8::try_from(*length)
+                    .map_err(|_| OptionsIterSerializeError::TooManyItems(*length))?;
 
                 let r_length = r_buf.len();
+                if buffer.len() < r_length + 1 {
+                    return Err(OptionsIterSerializeError::BufferTooSmall);
+                }
                 buffer[1..r_length + 1].copy_from_slice(&r_buf[..r_length]);
 
                 Ok(&mut buffer[r_length + 1..])
@@ -286,6 +392,144 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn value_signed_analog_roundtrip() {
+        for raw in [i16::MIN, -1, 0, 1, i16::MAX] {
+            let value = Value::SignedAnalog { raw };
+
+            let mut buffer = [0; 4];
+            let rest = value.serialize(&mut buffer).unwrap();
+            assert_eq!(1, rest.len());
+            assert_eq!([2, raw.to_be_bytes()[0], raw.to_be_bytes()[1]], buffer[..3]);
+
+            let (result, rest) = Value::deserialize(&buffer).unwrap();
+            assert_eq!(value, result);
+            assert_eq!(1, rest.len());
+        }
+    }
+
+    #[test]
+    fn value_signed_analog_negative() {
+        let mut buffer = [0; 3];
+        Value::SignedAnalog { raw: -40 }
+            .serialize(&mut buffer)
+            .unwrap();
+        assert_eq!([2, 0xff, 0xd8], buffer);
+
+        assert_eq!(
+            Err(ValueDeserializeError::MissingData),
+            Value::deserialize(&buffer[..2])
+        );
+        assert_eq!(
+            Ok((Value::SignedAnalog { raw: -40 }, &[][..])),
+            Value::deserialize(&buffer)
+        );
+    }
+
+    #[test]
+    fn value_switch_pwm_layout() {
+        let mut buffer = [0; 4];
+        let rest = Value::Switch { state: true }
+            .serialize(&mut buffer)
+            .unwrap();
+        let rest = Value::Pwm { percent: 42 }.serialize(rest).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!([0, 1, 1, 42], buffer);
+    }
+
+    #[test]
+    fn datapoint_new_valid() {
+        let point = DataPoint::new("fan", Value::Pwm { percent: 100 }).unwrap();
+
+        assert_eq!(
+            DataPoint {
+                name: "fan",
+                value: Value::Pwm { percent: 100 }
+            },
+            point
+        );
+    }
+
+    #[test]
+    fn datapoint_new_invalid() {
+        assert_eq!(
+            Err(DataPointError::ValueOutOfRange(Value::Pwm { percent: 101 })),
+            DataPoint::new("fan", Value::Pwm { percent: 101 })
+        );
+
+        let name = "a".repeat(256);
+        assert_eq!(
+            Err(DataPointError::NameTooLong(256)),
+            DataPoint::new(&name, Value::Switch { state: true })
+        );
+    }
+
+    /// The previous, hand-written Implementation of Sendable for DataPoint
+    struct HandWritten<'r>(DataPoint<'r>);
+
+    impl<'r> Sendable<'r> for HandWritten<'r> {
+        type SerError = NameSerializeError;
+        type DeSerError = DataPointDeserializeError;
+
+        fn serialize<'b>(&self, mut buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+            buffer = self.0.name.serialize(buffer)?;
+
+            Ok(self.0.value.serialize(buffer)?)
+        }
+
+        fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
+            let (name, buffer) = Sendable::deserialize(buffer)?;
+            let (value, buffer) = Value::deserialize(buffer)?;
+
+            Ok((Self(DataPoint { name, value }), buffer))
+        }
+    }
+
+    #[test]
+    fn datapoint_matches_hand_written() {
+        let points = [
+            DataPoint::new("fan", Value::Pwm { percent: 40 }).unwrap(),
+            DataPoint::new("", Value::Switch { state: true }).unwrap(),
+            DataPoint::new("temperature", Value::SignedAnalog { raw: -40 }).unwrap(),
+        ];
+
+        for point in points {
+            for size in 0..20 {
+                let mut generated = [0; 20];
+                let generated_rest = point
+                    .serialize(&mut generated[..size])
+                    .map(|rest| rest.len());
+
+                let mut expected = [0; 20];
+                let expected_rest = HandWritten(point.clone())
+                    .serialize(&mut expected[..size])
+                    .map(|rest| rest.len());
+
+                assert_eq!(
+                    expected_rest, generated_rest,
+                    "{:?} in {} Bytes",
+                    point, size
+                );
+                assert_eq!(expected, generated);
+
+                if generated_rest.is_err() {
+                    continue;
+                }
+
+                let (generated, generated_rest) = DataPoint::deserialize(&generated).unwrap();
+                let (expected, expected_rest) = HandWritten::deserialize(&expected).unwrap();
+                assert_eq!(expected.0, generated);
+                assert_eq!(expected_rest, generated_rest);
+            }
+        }
+
+        let invalid = [3, b'f', b'a', b'n', 9, 0];
+        assert_eq!(
+            HandWritten::deserialize(&invalid).map(|_| ()),
+            DataPoint::deserialize(&invalid).map(|_| ())
+        );
+    }
+
     #[test]
     fn optioniter_serialize_deserialize() {
         let fixed_iter: OptionsIter<'static, ConfigOption> = (&[
@@ -341,4 +585,129 @@ mod tests {
 
         assert_eq!(buffer, buffer2);
     }
+
+    #[test]
+    fn optioniter_serialize_too_many_items() {
+        let items: [ValueType; 256] = core::array::from_fn(|_| ValueType::Switch);
+        let fixed_iter: OptionsIter<'_, ValueType> = (&items).into();
+
+        let mut buffer = [0; 512];
+        assert_eq!(
+            Err(OptionsIterSerializeError::TooManyItems(256)),
+            fixed_iter.serialize(&mut buffer).map(|_| ())
+        );
+
+        // 255 Items still fit into the length byte
+        let fixed_iter: OptionsIter<'_, ValueType> = (&items[..255]).into();
+        assert!(fixed_iter.serialize(&mut buffer).is_ok());
+        assert_eq!(255, buffer[0]);
+    }
+
+    #[test]
+    fn optioniter_serialize_received_too_large() {
+        let mut buffer = [0; 256];
+        let fixed_iter: OptionsIter<'static, ConfigOption> = (&[
+            ConfigOption {
+                name: "testing1",
+                ty: ValueType::Pwm,
+            },
+            ConfigOption {
+                name: "testing2",
+                ty: ValueType::Switch,
+            },
+        ])
+            .into();
+        fixed_iter.serialize(&mut buffer).expect("Should work");
+
+        let (received, _): (OptionsIter<'_, ConfigOption>, _) =
+            Sendable::deserialize(&buffer).expect("Should work");
+
+        let mut small_buffer = [0; 8];
+        assert_eq!(
+            Err(OptionsIterSerializeError::BufferTooSmall),
+            received.serialize(&mut small_buffer).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn datapoint_name_too_long() {
+        let name = "a".repeat(300);
+        let point = DataPoint {
+            name: &name,
+            value: Value::Switch { state: true },
+        };
+
+        let mut buffer = [0; 512];
+        assert_eq!(
+            Err(NameSerializeError::NameTooLong),
+            point.serialize(&mut buffer).map(|_| ())
+        );
+
+        let option = ConfigOption {
+            name: &name,
+            ty: ValueType::Switch,
+        };
+        assert_eq!(
+            Err(NameSerializeError::NameTooLong),
+            option.serialize(&mut buffer).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn value_type_of() {
+        assert_eq!(
+            ValueType::Switch,
+            Value::Switch { state: true }.value_type()
+        );
+        assert_eq!(ValueType::Pwm, Value::Pwm { percent: 13 }.value_type());
+    }
+
+    #[test]
+    fn value_type_sendable() {
+        for ty in [ValueType::Switch, ValueType::Pwm] {
+            let mut buffer = [0; 4];
+
+            let rest = ty.serialize(&mut buffer).expect("Should work");
+            assert_eq!(3, rest.len());
+
+            let (deserialized, rest) = ValueType::deserialize(&buffer).expect("Should work");
+            assert_eq!(ty, deserialized);
+            assert_eq!(3, rest.len());
+        }
+    }
+
+    #[test]
+    fn options_iter_empty_roundtrip() {
+        let mut buffer = [0xff; 1];
+        let rest = OptionsIter::<DataPoint<'_>>::from(&[])
+            .serialize(&mut buffer)
+            .expect("Should work");
+        assert!(rest.is_empty());
+        assert_eq!([0], buffer);
+
+        let (received, rest) =
+            OptionsIter::<DataPoint<'_>>::deserialize(&buffer).expect("Should work");
+        assert!(rest.is_empty());
+        assert_eq!(0, received.length());
+
+        // Serializing the received List again needs exactly the same single Byte
+        let mut again = [0xff; 1];
+        let rest = received.serialize(&mut again).expect("Should work");
+        assert!(rest.is_empty());
+        assert_eq!(buffer, again);
+
+        assert_eq!(0, received.count());
+    }
+
+    #[test]
+    fn value_type_deserialize_invalid() {
+        assert_eq!(
+            Err(ValueDeserializeError::UnknownType(13)),
+            ValueType::deserialize(&[13]).map(|(ty, _)| ty)
+        );
+        assert_eq!(
+            Err(ValueDeserializeError::MissingData),
+            ValueType::deserialize(&[]).map(|(ty, _)| ty)
+        );
+    }
 }
@@ -1,6 +1,41 @@
 use core::convert::TryInto;
 
-use crate::{ConfigOption, DataPoint, OptionsIter, Sendable, Value, VERSION};
+use crate::{
+    cobs,
+    crc::{crc8, crc8_update},
+    ConfigOption, DataPoint, DataPointDeserializeError, OptionsIter, OptionsIterDeserializeError,
+    Sendable, VERSION,
+};
+
+/// The maximum size of a COBS encoded Packet, including the zero delimiter
+pub const COBS_FRAME_SIZE: usize = cobs::max_encoded_len(Packet::FRAME_LEN) + 1;
+
+/// The maximum number of Bytes that can be send in a single [`PacketData::Raw`] Packet
+pub const RAW_MAX_LEN: usize = Packet::DATA_LEN - 2;
+
+/// The logical ID assigned to an Extension by the Controller.
+///
+/// This is intentionally a distinct type from the physical [`SlotIndex`](crate::SlotIndex) of an
+/// Extension, so the two can't be accidentally mixed up.
+///
+/// # Example
+/// ```rust,compile_fail
+/// # use protocol::{packet::ExtensionId, SlotIndex};
+/// let id: ExtensionId = SlotIndex(3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExtensionId(pub u8);
+
+impl From<u8> for ExtensionId {
+    fn from(raw: u8) -> Self {
+        Self(raw)
+    }
+}
+impl From<ExtensionId> for u8 {
+    fn from(id: ExtensionId) -> Self {
+        id.0
+    }
+}
 
 /// The ID of the Receiver of a Packet
 #[derive(Debug, PartialEq, Eq)]
@@ -11,7 +46,24 @@ pub enum ReceiverID {
     /// measure, like the select line
     Everyone,
     /// Only the extension with the specified ID should react to this Packet
-    ID(u8),
+    ID(ExtensionId),
+}
+
+impl ReceiverID {
+    /// Whether the Extension with the given ID should react to a Packet of the given Kind for
+    /// this Receiver, where `selected` is the State of its select line.
+    ///
+    /// A Packet for Everyone is only meant for the selected Extension, except for a
+    /// [`PacketKind::ConfigureBroadcast`], which is meant for every Extension. An Extension
+    /// without an ID, because it was not initialized yet, only reacts to Packets for Everyone.
+    /// Packets for the Controller never match an Extension.
+    pub fn matches(&self, my_id: Option<ExtensionId>, selected: bool, kind: PacketKind) -> bool {
+        match self {
+            Self::Controller => false,
+            Self::Everyone => selected || kind == PacketKind::ConfigureBroadcast,
+            Self::ID(id) => my_id == Some(*id),
+        }
+    }
 }
 
 impl From<u8> for ReceiverID {
@@ -19,7 +71,7 @@ impl From<u8> for ReceiverID {
         match raw {
             0x00 => Self::Controller,
             0xff => Self::Everyone,
-            id => Self::ID(id),
+            id => Self::ID(ExtensionId(id)),
         }
     }
 }
@@ -28,7 +80,7 @@ impl From<ReceiverID> for u8 {
         match id {
             ReceiverID::Controller => 0x00,
             ReceiverID::Everyone => 0xff,
-            ReceiverID::ID(id) => id,
+            ReceiverID::ID(id) => id.0,
         }
     }
 }
@@ -37,7 +89,7 @@ impl From<&ReceiverID> for u8 {
         match id {
             ReceiverID::Controller => 0x00,
             ReceiverID::Everyone => 0xff,
-            ReceiverID::ID(id) => *id,
+            ReceiverID::ID(id) => id.0,
         }
     }
 }
@@ -46,19 +98,44 @@ impl From<&ReceiverID> for u8 {
 pub struct Packet<'r> {
     pub(crate) protocol_version: u8,
     pub(crate) receiver: ReceiverID,
+    /// The Sequence-Number of the Packet, which is incremented by the Sender for every Request
+    /// and echoed in the Response to it, allowing the Receiver to detect duplicated Requests
+    pub(crate) seq: u8,
     pub(crate) data: PacketData<'r>,
 }
 
+/// Builds a [`Packet`] for a single Receiver, see [`Packet::to`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct PacketBuilder {
+    receiver: ReceiverID,
+}
+
+impl PacketBuilder {
+    /// Finishes the Packet with the given Data, stamped with the current Protocol-Version and a
+    /// Sequence-Number of 0, which can be changed using [`Packet::with_seq`]
+    pub fn with(self, data: PacketData<'_>) -> Packet<'_> {
+        Packet {
+            protocol_version: VERSION,
+            receiver: self.receiver,
+            seq: 0,
+            data,
+        }
+    }
+}
+
 /// The Data containde in a Packet
 #[derive(Debug, PartialEq, Eq)]
 pub enum PacketData<'r> {
     InitProbe,
     InitProbeResponse {
         status: bool,
-        id: Option<u8>,
+        id: Option<ExtensionId>,
+        /// The Protocol-Version the Extension speaks, which allows the Controller to detect
+        /// incompatible Extensions while probing them
+        version: u8,
     },
     Init {
-        id: u8,
+        id: ExtensionId,
     },
     Acknowledge,
     Error {},
@@ -74,6 +151,103 @@ pub enum PacketData<'r> {
     ConfigureOptionsResponse {
         options: OptionsIter<'r, ConfigOption<'r>>,
     },
+    /// The Response to a Configure Packet, containing the Value that was actually applied by the
+    /// Extension, which might differ from the requested one, like when it was clamped
+    AckConfigured {
+        applied: DataPoint<'r>,
+    },
+    /// Requests only the Metrics with the given Names, which is cheaper than requesting all of
+    /// them when polling a single Sensor. Names the Extension does not know are omitted from the
+    /// MetricsResponse.
+    MetricsSubset {
+        names: OptionsIter<'r, &'r str>,
+    },
+    /// Applies the Configuration on every Extension, that has a ConfigOption with the same Name
+    /// and Type, like setting all the Fans to the same Speed at once. This is send to
+    /// [`ReceiverID::Everyone`] and is never acknowledged, as all the Extensions would otherwise
+    /// respond at the same time.
+    ConfigureBroadcast {
+        option: DataPoint<'r>,
+    },
+    /// Requests the Firmware-Version and Build-Identifier of the Extension
+    Info,
+    /// The Response to an Info Request
+    InfoResponse {
+        /// The Version of the Firmware running on the Extension
+        fw_version: u16,
+        /// An opaque Identifier of the Build, like the start of a Commit-Hash
+        build: [u8; 8],
+    },
+    /// Opaque Data, that does not fit into any of the other Packets, like a Chunk of a Firmware
+    /// Image. The Data is send with a length prefix and can be at most [`RAW_MAX_LEN`] Bytes
+    /// long.
+    Raw {
+        data: &'r [u8],
+    },
+}
+
+/// The Kind of a [`PacketData`], without any of its Data, which allows for reporting what kind of
+/// Packet was received without borrowing from the received Frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketKind {
+    InitProbe,
+    InitProbeResponse,
+    Init,
+    Acknowledge,
+    Error,
+    Restart,
+    Configure,
+    Metrics,
+    MetricsResponse,
+    ConfigureOptions,
+    ConfigureOptionsResponse,
+    AckConfigured,
+    Raw,
+    MetricsSubset,
+    ConfigureBroadcast,
+    Info,
+    InfoResponse,
+    /// The Type-ID of a dropped Frame, that does not belong to any known Kind
+    Unknown(u8),
+}
+
+impl PacketKind {
+    /// The Kind belonging to the Type-ID, that is the first Byte of a serialized PacketData
+    pub(crate) fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::InitProbe),
+            1 => Some(Self::InitProbeResponse),
+            2 => Some(Self::Init),
+            3 => Some(Self::Acknowledge),
+            4 => Some(Self::Error),
+            5 => Some(Self::Restart),
+            6 => Some(Self::Configure),
+            7 => Some(Self::Metrics),
+            8 => Some(Self::MetricsResponse),
+            9 => Some(Self::ConfigureOptions),
+            10 => Some(Self::ConfigureOptionsResponse),
+            11 => Some(Self::AckConfigured),
+            12 => Some(Self::Raw),
+            13 => Some(Self::MetricsSubset),
+            14 => Some(Self::ConfigureBroadcast),
+            15 => Some(Self::Info),
+            16 => Some(Self::InfoResponse),
+            _ => None,
+        }
+    }
+}
+
+/// The Direction in which a Packet travelled, from the Point of View of whoever observed it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketDirection {
+    /// The Packet was serialized and send
+    Send,
+    /// The Packet was received and deserialized
+    Receive,
+    /// A Frame was received, but could not be deserialized and was dropped. Its Kind is taken
+    /// from the Type-ID of the Frame, which might be corrupted as well, and is
+    /// [`PacketKind::Unknown`] for unknown IDs
+    Dropped,
 }
 
 /// The Error that can be raised while parsing a raw received PacketData
@@ -81,11 +255,58 @@ pub enum PacketData<'r> {
 pub enum PacketDataParseError {
     /// The ID of the PacketData is not a known valid ID
     UnknownID(u8),
+    /// The length of a Raw Packet exceeds the available space
+    InvalidRawLength(u8),
+    /// The DataPoint of a Packet could not be deserialized
+    InvalidDataPoint(DataPointDeserializeError),
+    /// The Names of a MetricsSubset Request could not be deserialized
+    InvalidNames(OptionsIterDeserializeError<()>),
+    /// The Firmware-Version or Build of an InfoResponse could not be deserialized
+    InvalidInfoResponse,
+}
+
+/// The Error that can be raised while serializing a PacketData
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketSerializeError {
+    /// The Data of a Raw Packet is longer than [`RAW_MAX_LEN`]
+    RawTooLong(usize),
+    /// The Metrics don't fit into a single Packet, either in a [`PacketData::MetricsResponse`] or
+    /// when streaming them, see [`Packet::serialize_metrics_response`]
+    MetricsTooLong,
+    /// The Data does not fit into the [`Packet::DATA_LEN`] Bytes of the Data area, like a
+    /// Configure with a very long Name
+    DataTooLarge,
 }
 
 impl<'r> PacketData<'r> {
+    /// The Kind of this PacketData
+    pub fn kind(&self) -> PacketKind {
+        match self {
+            Self::InitProbe => PacketKind::InitProbe,
+            Self::InitProbeResponse { .. } => PacketKind::InitProbeResponse,
+            Self::Init { .. } => PacketKind::Init,
+            Self::Acknowledge => PacketKind::Acknowledge,
+            Self::Error {} => PacketKind::Error,
+            Self::Restart => PacketKind::Restart,
+            Self::Configure { .. } => PacketKind::Configure,
+            Self::Metrics => PacketKind::Metrics,
+            Self::MetricsResponse { .. } => PacketKind::MetricsResponse,
+            Self::ConfigureOptions => PacketKind::ConfigureOptions,
+            Self::ConfigureOptionsResponse { .. } => PacketKind::ConfigureOptionsResponse,
+            Self::AckConfigured { .. } => PacketKind::AckConfigured,
+            Self::Raw { .. } => PacketKind::Raw,
+            Self::MetricsSubset { .. } => PacketKind::MetricsSubset,
+            Self::ConfigureBroadcast { .. } => PacketKind::ConfigureBroadcast,
+            Self::Info => PacketKind::Info,
+            Self::InfoResponse { .. } => PacketKind::InfoResponse,
+        }
+    }
+
     /// Attempt to parse the Data from a raw packet
-    pub fn parse<'b>(prot_version: u8, value: &'b [u8; 253]) -> Result<Self, PacketDataParseError>
+    pub fn parse<'b>(
+        prot_version: u8,
+        value: &'b [u8; Packet::DATA_LEN],
+    ) -> Result<Self, PacketDataParseError>
     where
         'b: 'r,
     {
@@ -96,27 +317,34 @@ impl<'r> PacketData<'r> {
             1 => {
                 let status = value[1] != 0;
 
-                let id = if status { Some(value[2]) } else { None };
+                let id = if status {
+                    Some(ExtensionId(value[2]))
+                } else {
+                    None
+                };
+
+                let version = value[3];
 
-                Ok(Self::InitProbeResponse { status, id })
+                Ok(Self::InitProbeResponse {
+                    status,
+                    id,
+                    version,
+                })
             }
             2 => {
                 let n_id = value[1];
-                Ok(Self::Init { id: n_id })
+                Ok(Self::Init {
+                    id: ExtensionId(n_id),
+                })
             }
             3 => Ok(Self::Acknowledge),
-            4 => {
-                todo!("Parse Error Packet")
-            }
+            4 => Ok(Self::Error {}),
             5 => Ok(Self::Restart),
             6 => {
-                let (name, rest): (&str, _) = Sendable::deserialize(&value[1..]).unwrap();
-
-                let value = Value::deserialize((&rest[..2]).try_into().unwrap()).unwrap();
+                let (option, _) = DataPoint::deserialize(&value[1..])
+                    .map_err(PacketDataParseError::InvalidDataPoint)?;
 
-                Ok(Self::Configure {
-                    option: DataPoint { name, value },
-                })
+                Ok(Self::Configure { option })
             }
             7 => Ok(Self::Metrics),
             8 => {
@@ -130,38 +358,85 @@ impl<'r> PacketData<'r> {
 
                 Ok(Self::ConfigureOptionsResponse { options })
             }
+            11 => {
+                let (applied, _) = DataPoint::deserialize(&value[1..])
+                    .map_err(PacketDataParseError::InvalidDataPoint)?;
+
+                Ok(Self::AckConfigured { applied })
+            }
+            12 => {
+                let length = value[1];
+                if length as usize > RAW_MAX_LEN {
+                    return Err(PacketDataParseError::InvalidRawLength(length));
+                }
+
+                Ok(Self::Raw {
+                    data: &value[2..2 + length as usize],
+                })
+            }
+            13 => {
+                let (names, _) = Sendable::deserialize(&value[1..])
+                    .map_err(PacketDataParseError::InvalidNames)?;
+
+                Ok(Self::MetricsSubset { names })
+            }
+            14 => {
+                let (option, _) = DataPoint::deserialize(&value[1..])
+                    .map_err(PacketDataParseError::InvalidDataPoint)?;
+
+                Ok(Self::ConfigureBroadcast { option })
+            }
+            15 => Ok(Self::Info),
+            16 => {
+                let (fw_version, rest) = u16::deserialize(&value[1..])
+                    .map_err(|_| PacketDataParseError::InvalidInfoResponse)?;
+                let build = rest
+                    .get(..8)
+                    .and_then(|build| build.try_into().ok())
+                    .ok_or(PacketDataParseError::InvalidInfoResponse)?;
+
+                Ok(Self::InfoResponse { fw_version, build })
+            }
             id => Err(PacketDataParseError::UnknownID(id)),
         }
     }
 
-    /// Serialize the Packet Data into the provided Buffer for transmittion
-    pub fn serialize(&self, data: &mut [u8; 253]) {
+    /// Serialize the Packet Data into the provided Buffer for transmittion, returning an Error if
+    /// the Data does not fit
+    pub fn serialize(&self, data: &mut [u8; Packet::DATA_LEN]) -> Result<(), PacketSerializeError> {
         match self {
             Self::InitProbe => {
                 data[0] = 0;
             }
-            Self::InitProbeResponse { status, id } => {
+            Self::InitProbeResponse {
+                status,
+                id,
+                version,
+            } => {
                 data[0] = 1;
                 data[1] = u8::from(*status);
-                data[2] = id.unwrap_or(0);
+                data[2] = id.map(u8::from).unwrap_or(0);
+                data[3] = *version;
             }
             Self::Init { id } => {
                 data[0] = 2;
-                data[1] = *id;
+                data[1] = id.0;
             }
             Self::Acknowledge => {
                 data[0] = 3;
             }
-            Self::Error {} => todo!("Serialize Error"),
+            Self::Error {} => {
+                data[0] = 4;
+            }
             Self::Restart => {
                 data[0] = 5;
             }
             Self::Configure { option } => {
                 data[0] = 6;
 
-                let rest = option.name.serialize(&mut data[1..]).unwrap();
-
-                rest[0..2].copy_from_slice(&option.value.serialize());
+                option
+                    .serialize(&mut data[1..])
+                    .map_err(|_| PacketSerializeError::DataTooLarge)?;
             }
             Self::Metrics => {
                 data[0] = 7;
@@ -169,7 +444,9 @@ impl<'r> PacketData<'r> {
             Self::MetricsResponse { metrics } => {
                 data[0] = 8;
 
-                metrics.serialize(&mut data[1..]).unwrap();
+                metrics
+                    .serialize(&mut data[1..])
+                    .map_err(|_| PacketSerializeError::MetricsTooLong)?;
             }
             Self::ConfigureOptions => {
                 data[0] = 9;
@@ -177,9 +454,56 @@ impl<'r> PacketData<'r> {
             Self::ConfigureOptionsResponse { options } => {
                 data[0] = 10;
 
-                options.serialize(&mut data[1..]).unwrap();
+                options
+                    .serialize(&mut data[1..])
+                    .map_err(|_| PacketSerializeError::DataTooLarge)?;
+            }
+            Self::AckConfigured { applied } => {
+                data[0] = 11;
+
+                applied
+                    .serialize(&mut data[1..])
+                    .map_err(|_| PacketSerializeError::DataTooLarge)?;
+            }
+            Self::Raw { data: raw } => {
+                if raw.len() > RAW_MAX_LEN {
+                    return Err(PacketSerializeError::RawTooLong(raw.len()));
+                }
+
+                data[0] = 12;
+                data[1] = raw.len() as u8;
+                data[2..2 + raw.len()].copy_from_slice(raw);
+            }
+            Self::MetricsSubset { names } => {
+                data[0] = 13;
+
+                names
+                    .serialize(&mut data[1..])
+                    .map_err(|_| PacketSerializeError::DataTooLarge)?;
+            }
+            Self::ConfigureBroadcast { option } => {
+                data[0] = 14;
+
+                option
+                    .serialize(&mut data[1..])
+                    .map_err(|_| PacketSerializeError::DataTooLarge)?;
+            }
+            Self::Info => {
+                data[0] = 15;
+            }
+            Self::InfoResponse { fw_version, build } => {
+                data[0] = 16;
+
+                let rest = fw_version
+                    .serialize(&mut data[1..])
+                    .map_err(|_| PacketSerializeError::DataTooLarge)?;
+                rest.get_mut(..8)
+                    .ok_or(PacketSerializeError::DataTooLarge)?
+                    .copy_from_slice(build);
             }
         }
+
+        Ok(())
     }
 }
 
@@ -187,74 +511,254 @@ impl<'r> PacketData<'r> {
 pub enum PacketReadError<E> {
     SerialRead(nb::Error<E>),
     Deserialize(PacketDeserializeError),
+    /// The serial did not provide the next Byte in time
+    Timeout,
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum PacketDeserializeError {
     Deserialize(PacketDataParseError),
     Checksum,
+    /// The Frame was send by a Peer speaking a different Protocol-Version
+    UnsupportedVersion(u8),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CobsDeserializeError {
+    Decode(cobs::CobsDecodeError),
+    /// The decoded Frame did not contain exactly one Packet
+    Length(usize),
+    Deserialize(PacketDeserializeError),
 }
 
 impl<'r> Packet<'r> {
+    /// The Size of a single serialized Packet
+    pub const FRAME_LEN: usize = 256;
+    /// The Size of the Header, consisting of the Protocol-Version, the Receiver and the
+    /// Sequence-Number
+    pub const HEADER_LEN: usize = 3;
+    /// The Size of the Data area, which holds the serialized [`PacketData`]
+    pub const DATA_LEN: usize = Self::FRAME_LEN - Self::HEADER_LEN - 1;
+    /// The Index of the CRC, which is the last Byte of the Packet
+    pub const CRC_INDEX: usize = Self::HEADER_LEN + Self::DATA_LEN;
+
+    /// Starts building a Packet for the given Receiver, which avoids spelling out the entire
+    /// Packet.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use protocol::packet::{ExtensionId, Packet, PacketData, ReceiverID};
+    /// let packet = Packet::to(ReceiverID::ID(ExtensionId(3))).with(PacketData::Metrics);
+    /// assert_eq!(&PacketData::Metrics, packet.data());
+    /// ```
+    pub fn to(receiver: ReceiverID) -> PacketBuilder {
+        PacketBuilder { receiver }
+    }
+
     /// Construct an Init-Probe Packet
     pub fn init_probe() -> Self {
-        Self {
-            protocol_version: VERSION,
-            receiver: ReceiverID::Everyone,
-            data: PacketData::InitProbe,
-        }
+        Self::to(ReceiverID::Everyone).with(PacketData::InitProbe)
+    }
+
+    /// Construct a Request for all the Metrics of the Extension with the given ID
+    pub fn metrics_request(id: ExtensionId) -> Self {
+        Self::to(ReceiverID::ID(id)).with(PacketData::Metrics)
+    }
+
+    /// Construct a Request to apply the given Configuration on the Extension with the given ID
+    pub fn configure(id: ExtensionId, option: DataPoint<'r>) -> Self {
+        Self::to(ReceiverID::ID(id)).with(PacketData::Configure { option })
+    }
+
+    /// Construct a Request for every Extension to apply the given Configuration, if it has a
+    /// matching ConfigOption, see [`PacketData::ConfigureBroadcast`]
+    pub fn configure_broadcast(option: DataPoint<'r>) -> Self {
+        Self::to(ReceiverID::Everyone).with(PacketData::ConfigureBroadcast { option })
+    }
+
+    /// Construct a Request for the Firmware-Info of the Extension with the given ID
+    pub fn info_request(id: ExtensionId) -> Self {
+        Self::to(ReceiverID::ID(id)).with(PacketData::Info)
+    }
+
+    /// Construct a Request for the Extension with the given ID to restart
+    pub fn restart(id: ExtensionId) -> Self {
+        Self::to(ReceiverID::ID(id)).with(PacketData::Restart)
+    }
+
+    /// Sets the Sequence-Number of the Packet, like for echoing the Sequence-Number of the
+    /// Request in its Response
+    pub fn with_seq(mut self, seq: u8) -> Self {
+        self.seq = seq;
+        self
+    }
+
+    /// The Sequence-Number of the Packet
+    pub fn seq(&self) -> u8 {
+        self.seq
+    }
+
+    /// The Sequence-Number of an already serialized Packet
+    pub(crate) fn frame_seq(buffer: &[u8; Packet::FRAME_LEN]) -> u8 {
+        buffer[2]
+    }
+
+    /// Updates the Sequence-Number of an already serialized Packet, including its CRC
+    pub(crate) fn set_seq(buffer: &mut [u8; Packet::FRAME_LEN], seq: u8) {
+        buffer[2] = seq;
+        buffer[Self::CRC_INDEX] = crc8(&buffer[..Self::CRC_INDEX]);
+    }
+
+    /// Construct an Error Packet targeting the given Receiver, which is send in Response to a
+    /// Request, that could not be handled
+    pub fn error(recv: ReceiverID) -> Self {
+        Self::to(recv).with(PacketData::Error {})
+    }
+
+    /// The serialized [`Packet::error`] with the given Sequence-Number. An Error Packet has no
+    /// Data, so unlike serializing any other Packet this can't fail, which makes it the fallback
+    /// Response, when the actual Response could not be serialized.
+    pub fn error_frame(recv: ReceiverID, seq: u8) -> [u8; Packet::FRAME_LEN] {
+        let mut buffer = [0; Packet::FRAME_LEN];
+
+        buffer[0] = VERSION;
+        buffer[1] = recv.into();
+        buffer[2] = seq;
+        buffer[Self::HEADER_LEN] = 4;
+        buffer[Self::CRC_INDEX] = crc8(&buffer[..Self::CRC_INDEX]);
+
+        buffer
     }
 
     /// Construct an Acknowledgement Packet targeting the given Receiver
     pub fn ack(recv: ReceiverID) -> Self {
-        Self {
-            protocol_version: VERSION,
-            receiver: recv,
-            data: PacketData::Acknowledge,
-        }
+        Self::to(recv).with(PacketData::Acknowledge)
     }
 
     /// Attempt to read a Packet from serial blocking
     pub fn read_blocking<'b, S>(
         serial: &mut S,
-        buffer: &'b mut [u8; 256],
+        buffer: &'b mut [u8; Packet::FRAME_LEN],
     ) -> Result<Self, PacketReadError<S::Error>>
     where
         'b: 'r,
         S: embedded_hal::serial::nb::Read,
     {
         for buffer_entry in buffer.iter_mut() {
-            loop {
-                match serial.read() {
-                    Ok(d) => {
-                        *buffer_entry = d;
-                    }
-                    Err(nb::Error::WouldBlock) => continue,
-                    Err(err) => {
-                        return Err(PacketReadError::SerialRead(err));
-                    }
-                };
-                break;
+            *buffer_entry = Self::read_byte_blocking(serial)?;
+        }
+
+        Self::deserialize(buffer).map_err(PacketReadError::Deserialize)
+    }
+
+    /// Attempt to read a Packet from serial blocking, while trying to resynchronize with the
+    /// stream in case of a CRC mismatch.
+    ///
+    /// # Resynchronization
+    /// If the CRC of the received Data does not match, we assume that we are not aligned with the
+    /// start of a Packet, like when a single Byte was dropped or some garbage was received. We
+    /// then drop the first Byte in the Buffer, read one more Byte and check again. This is done at
+    /// most `max_resync` times, before giving up and returning a Checksum error.
+    pub fn read_blocking_resync<'b, S>(
+        serial: &mut S,
+        buffer: &'b mut [u8; Packet::FRAME_LEN],
+        max_resync: usize,
+    ) -> Result<Self, PacketReadError<S::Error>>
+    where
+        'b: 'r,
+        S: embedded_hal::serial::nb::Read,
+    {
+        for buffer_entry in buffer.iter_mut() {
+            *buffer_entry = Self::read_byte_blocking(serial)?;
+        }
+
+        let mut attempts = 0;
+        while !Self::check_crc(buffer) {
+            if attempts >= max_resync {
+                return Err(PacketReadError::Deserialize(
+                    PacketDeserializeError::Checksum,
+                ));
             }
+            attempts += 1;
+
+            buffer.copy_within(1.., 0);
+            buffer[Self::CRC_INDEX] = Self::read_byte_blocking(serial)?;
+        }
+
+        Self::deserialize(buffer).map_err(PacketReadError::Deserialize)
+    }
+
+    /// Attempt to read a Packet from serial blocking, but give up if the serial would block for
+    /// more than `max_spins` times in a row while waiting for a single Byte.
+    ///
+    /// This allows for reading Packets without hanging forever, if the other side stops sending
+    /// in the middle of a Packet.
+    pub fn read_blocking_bounded<'b, S>(
+        serial: &mut S,
+        buffer: &'b mut [u8; Packet::FRAME_LEN],
+        max_spins: u32,
+    ) -> Result<Self, PacketReadError<S::Error>>
+    where
+        'b: 'r,
+        S: embedded_hal::serial::nb::Read,
+    {
+        for buffer_entry in buffer.iter_mut() {
+            *buffer_entry = Self::read_byte_bounded(serial, max_spins)?;
         }
 
         Self::deserialize(buffer).map_err(PacketReadError::Deserialize)
     }
 
+    fn read_byte_bounded<S>(serial: &mut S, max_spins: u32) -> Result<u8, PacketReadError<S::Error>>
+    where
+        S: embedded_hal::serial::nb::Read,
+    {
+        let mut spins = 0;
+        loop {
+            match serial.read() {
+                Ok(d) => return Ok(d),
+                Err(nb::Error::WouldBlock) if spins < max_spins => spins += 1,
+                Err(nb::Error::WouldBlock) => return Err(PacketReadError::Timeout),
+                Err(err) => return Err(PacketReadError::SerialRead(err)),
+            };
+        }
+    }
+
+    fn read_byte_blocking<S>(serial: &mut S) -> Result<u8, PacketReadError<S::Error>>
+    where
+        S: embedded_hal::serial::nb::Read,
+    {
+        loop {
+            match serial.read() {
+                Ok(d) => return Ok(d),
+                Err(nb::Error::WouldBlock) => continue,
+                Err(err) => return Err(PacketReadError::SerialRead(err)),
+            };
+        }
+    }
+
+    /// Checks if the CRC stored in the raw Buffer matches its content
+    fn check_crc(buffer: &[u8; Packet::FRAME_LEN]) -> bool {
+        crc8(&buffer[..Self::CRC_INDEX]) == buffer[Self::CRC_INDEX]
+    }
+
     /// Attempt to deserialize the raw Buffer into a valid Packet
-    pub fn deserialize<'b>(buffer: &'b [u8; 256]) -> Result<Self, PacketDeserializeError>
+    pub fn deserialize<'b>(
+        buffer: &'b [u8; Packet::FRAME_LEN],
+    ) -> Result<Self, PacketDeserializeError>
     where
         'b: 'r,
     {
         let protocol_version = buffer[0];
         let raw_receiver_id = buffer[1];
-        let raw_data: &'b [u8; 253] = (&buffer[2..255])
+        let seq = buffer[2];
+        let raw_data: &'b [u8; Packet::DATA_LEN] = (&buffer[Self::HEADER_LEN..Self::CRC_INDEX])
             .try_into()
-            .expect("We always select a 253 byte sized area");
-        let crc = buffer[255];
+            .expect("The Data area is exactly DATA_LEN Bytes long");
 
-        // TODO
-        // Validate the Packet with the CRC
+        if !Self::check_crc(buffer) {
+            return Err(PacketDeserializeError::Checksum);
+        }
 
         let receiver_id: ReceiverID = raw_receiver_id.into();
         let packet_data = PacketData::parse(protocol_version, raw_data)
@@ -263,26 +767,162 @@ impl<'r> Packet<'r> {
         Ok(Self {
             protocol_version,
             receiver: receiver_id,
+            seq,
             data: packet_data,
         })
     }
 
-    /// Serialize the Packet for transmition
-    pub fn serialize(&self) -> [u8; 256] {
-        let mut buffer = [0; 256];
+    /// Checks the CRC, the Protocol-Version and the Type-ID of the raw Buffer and returns the
+    /// Kind of the Packet, without parsing its Data. This is a cheap way to reject garbage, before actually
+    /// deserializing the Packet with [`Packet::deserialize`], which can still fail on malformed
+    /// Data.
+    pub fn validate(
+        buffer: &[u8; Packet::FRAME_LEN],
+    ) -> Result<PacketKind, PacketDeserializeError> {
+        if !Self::check_crc(buffer) {
+            return Err(PacketDeserializeError::Checksum);
+        }
+
+        let version = buffer[0];
+        if version != VERSION {
+            return Err(PacketDeserializeError::UnsupportedVersion(version));
+        }
+
+        let id = buffer[Self::HEADER_LEN];
+        PacketKind::from_id(id).ok_or(PacketDeserializeError::Deserialize(
+            PacketDataParseError::UnknownID(id),
+        ))
+    }
+
+    /// Serialize the Packet for transmition, returning an Error if its Data does not fit into a
+    /// single Packet
+    pub fn serialize(&self) -> Result<[u8; Packet::FRAME_LEN], PacketSerializeError> {
+        let mut buffer = [0; Packet::FRAME_LEN];
 
         buffer[0] = VERSION;
         buffer[1] = (&self.receiver).into();
+        buffer[2] = self.seq;
 
-        self.data
-            .serialize((&mut buffer[2..255]).try_into().unwrap());
+        self.data.serialize(
+            (&mut buffer[Self::HEADER_LEN..Self::CRC_INDEX])
+                .try_into()
+                .expect("The Data area is exactly DATA_LEN Bytes long"),
+        )?;
 
-        // TODO
-        // Calculate CRC
-        let crc = 0;
-        buffer[255] = crc;
+        buffer[Self::CRC_INDEX] = crc8(&buffer[..Self::CRC_INDEX]);
 
-        buffer
+        Ok(buffer)
+    }
+
+    /// Serialize the Packet one Byte at a time, for Transports that write single Bytes instead
+    /// of entire Frames, yielding the same Bytes as [`Packet::serialize`].
+    ///
+    /// Only the Data area is serialized up front, as the [`PacketData`] can only be serialized
+    /// into a Buffer, while the Header is produced on demand and the CRC is updated with every
+    /// yielded Byte.
+    ///
+    /// # Errors
+    /// If the Data does not fit into a single Packet, like [`Packet::serialize`]
+    ///
+    /// # Example
+    /// ```rust
+    /// # use protocol::packet::Packet;
+    /// let packet = Packet::init_probe();
+    /// assert!(packet
+    ///     .serialize_iter()
+    ///     .unwrap()
+    ///     .eq(packet.serialize().unwrap()));
+    /// ```
+    pub fn serialize_iter(&self) -> Result<PacketBytes, PacketSerializeError> {
+        let mut data = [0; Packet::DATA_LEN];
+        self.data.serialize(&mut data)?;
+
+        Ok(PacketBytes {
+            header: [VERSION, (&self.receiver).into(), self.seq],
+            data,
+            index: 0,
+            crc: 0,
+        })
+    }
+
+    /// Serializes a MetricsResponse Packet, whose DataPoints are produced by `metrics` one after
+    /// the other and serialized directly into the Frame, instead of collecting them up front.
+    ///
+    /// This results in the same Frame as serializing a [`PacketData::MetricsResponse`] with the
+    /// same DataPoints.
+    pub fn serialize_metrics_response(
+        receiver: ReceiverID,
+        seq: u8,
+        metrics: impl FnOnce(&mut dyn FnMut(DataPoint<'_>)),
+    ) -> Result<[u8; Packet::FRAME_LEN], PacketSerializeError> {
+        let mut buffer = [0; Packet::FRAME_LEN];
+
+        buffer[0] = VERSION;
+        buffer[1] = (&receiver).into();
+        buffer[2] = seq;
+
+        let (type_id, data) = buffer[Self::HEADER_LEN..Self::CRC_INDEX]
+            .split_first_mut()
+            .expect("The Data area is not empty");
+        *type_id = 8;
+        let (count, mut remaining) = data.split_first_mut().expect("The Data area is not empty");
+
+        let mut length: usize = 0;
+        let mut fits = true;
+        metrics(&mut |point| {
+            if !fits {
+                return;
+            }
+
+            match point.serialize(core::mem::take(&mut remaining)) {
+                Ok(rest) => {
+                    remaining = rest;
+                    length += 1;
+                }
+                Err(_) => fits = false,
+            };
+        });
+
+        *count = match u8::try_from(length) {
+            Ok(count) if fits => count,
+            _ => return Err(PacketSerializeError::MetricsTooLong),
+        };
+
+        buffer[Self::CRC_INDEX] = crc8(&buffer[..Self::CRC_INDEX]);
+
+        Ok(buffer)
+    }
+
+    /// Serialize the Packet into a COBS encoded Frame, which contains no zero bytes apart from
+    /// the delimiter at the end. Returns the Frame and the number of bytes used, including the
+    /// delimiter.
+    ///
+    /// This allows for sending Packets over Links, that can't transmit raw binary data or where
+    /// the Packet boundaries could otherwise not be detected.
+    pub fn serialize_cobs(&self) -> Result<([u8; COBS_FRAME_SIZE], usize), PacketSerializeError> {
+        let mut frame = [0; COBS_FRAME_SIZE];
+
+        let length = cobs::encode(&self.serialize()?, &mut frame);
+        frame[length] = 0;
+
+        Ok((frame, length + 1))
+    }
+
+    /// Attempt to decode and deserialize a COBS encoded Frame, the decoded Packet is stored in
+    /// the given Buffer. The Frame ends at the first zero byte, if there is any.
+    pub fn deserialize_cobs<'b>(
+        frame: &[u8],
+        buffer: &'b mut [u8; Packet::FRAME_LEN],
+    ) -> Result<Self, CobsDeserializeError>
+    where
+        'b: 'r,
+    {
+        let length = cobs::decode(frame, buffer).map_err(CobsDeserializeError::Decode)?;
+        if length != buffer.len() {
+            return Err(CobsDeserializeError::Length(length));
+        }
+
+        Self::deserialize(buffer).map_err(CobsDeserializeError::Deserialize)
     }
 
     /// Get the ReceiverID for this Packet
@@ -293,17 +933,105 @@ impl<'r> Packet<'r> {
     pub fn data(&self) -> &PacketData {
         &self.data
     }
+
+    /// Copies the Packet, including all the Names and Options it borrows, into an
+    /// [`OwnedPacket`], which does not depend on the Buffer it was received in.
+    ///
+    /// # Errors
+    /// If the Packet does not fit into a single serialized Frame, which is never the case for a
+    /// received Packet
+    pub fn to_owned(&self) -> Result<OwnedPacket, PacketSerializeError> {
+        Ok(OwnedPacket {
+            buffer: self.serialize()?,
+        })
+    }
+}
+
+/// The Bytes of a serialized Packet, see [`Packet::serialize_iter`]
+#[derive(Debug, Clone)]
+pub struct PacketBytes {
+    header: [u8; Packet::HEADER_LEN],
+    data: [u8; Packet::DATA_LEN],
+    /// The Index of the next Byte in the Frame
+    index: usize,
+    /// The CRC over all the Bytes yielded so far
+    crc: u8,
+}
+
+impl Iterator for PacketBytes {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = match self.index {
+            idx if idx < Packet::HEADER_LEN => self.header[idx],
+            idx if idx < Packet::CRC_INDEX => self.data[idx - Packet::HEADER_LEN],
+            Packet::CRC_INDEX => self.crc,
+            _ => return None,
+        };
+
+        self.index += 1;
+        self.crc = crc8_update(self.crc, byte);
+
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = Packet::FRAME_LEN.saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PacketBytes {}
+
+/// A Packet, that owns all of its Data and can therefore be stored after the receive Buffer has
+/// been reused.
+///
+/// # Storage
+/// Every Packet fits into a single serialized Frame, so the Packet is stored in its serialized
+/// form and deserialized again when it is accessed. This bounds the Size of an OwnedPacket without
+/// needing `alloc`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct OwnedPacket {
+    buffer: [u8; Packet::FRAME_LEN],
+}
+
+impl OwnedPacket {
+    /// Get the stored Packet, which borrows from this OwnedPacket
+    pub fn packet(&self) -> Packet<'_> {
+        Packet::deserialize(&self.buffer).expect("The Buffer was serialized from a valid Packet")
+    }
+}
+
+impl core::fmt::Debug for OwnedPacket {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let packet = self.packet();
+
+        f.debug_struct("OwnedPacket")
+            .field("receiver", &packet.receiver)
+            .field("data", &packet.data)
+            .finish()
+    }
+}
+
+impl<'r> TryFrom<&Packet<'r>> for OwnedPacket {
+    type Error = PacketSerializeError;
+
+    fn try_from(packet: &Packet<'r>) -> Result<Self, Self::Error> {
+        packet.to_owned()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::{Value, ValueDeserializeError};
+
     #[test]
     fn packet_data_init_probe() {
-        let data: [u8; 253] = {
+        let data: [u8; Packet::DATA_LEN] = {
             let mut raw = vec![0];
-            raw.resize_with(253, || 0);
+            raw.resize_with(Packet::DATA_LEN, || 0);
             raw.try_into().unwrap()
         };
 
@@ -314,9 +1042,9 @@ mod tests {
 
     #[test]
     fn packet_data_init_probe_response_false() {
-        let data: [u8; 253] = {
-            let mut raw = vec![1, 0, 13];
-            raw.resize_with(253, || 0);
+        let data: [u8; Packet::DATA_LEN] = {
+            let mut raw = vec![1, 0, 13, 2];
+            raw.resize_with(Packet::DATA_LEN, || 0);
             raw.try_into().unwrap()
         };
 
@@ -325,16 +1053,17 @@ mod tests {
         assert_eq!(
             PacketData::InitProbeResponse {
                 status: false,
-                id: None
+                id: None,
+                version: 2,
             },
             result
         );
     }
     #[test]
     fn packet_data_init_probe_response_true() {
-        let data: [u8; 253] = {
-            let mut raw = vec![1, 1, 13];
-            raw.resize_with(253, || 0);
+        let data: [u8; Packet::DATA_LEN] = {
+            let mut raw = vec![1, 1, 13, 2];
+            raw.resize_with(Packet::DATA_LEN, || 0);
             raw.try_into().unwrap()
         };
 
@@ -343,30 +1072,58 @@ mod tests {
         assert_eq!(
             PacketData::InitProbeResponse {
                 status: true,
-                id: Some(13)
+                id: Some(ExtensionId(13)),
+                version: 2,
             },
             result
         );
     }
 
+    #[test]
+    fn packet_data_init_probe_response_roundtrip() {
+        for data in [
+            PacketData::InitProbeResponse {
+                status: true,
+                id: Some(ExtensionId(13)),
+                version: VERSION,
+            },
+            PacketData::InitProbeResponse {
+                status: false,
+                id: None,
+                version: 7,
+            },
+        ] {
+            let mut raw = [0; Packet::DATA_LEN];
+            data.serialize(&mut raw).unwrap();
+
+            let result = PacketData::parse(0, &raw).expect("Should work");
+            assert_eq!(data, result);
+        }
+    }
+
     #[test]
     fn packet_data_init() {
-        let data: [u8; 253] = {
+        let data: [u8; Packet::DATA_LEN] = {
             let mut raw = vec![2, 123];
-            raw.resize_with(253, || 0);
+            raw.resize_with(Packet::DATA_LEN, || 0);
             raw.try_into().unwrap()
         };
 
         let result = PacketData::parse(0, &data).expect("Should be parseable");
 
-        assert_eq!(PacketData::Init { id: 123 }, result);
+        assert_eq!(
+            PacketData::Init {
+                id: ExtensionId(123)
+            },
+            result
+        );
     }
 
     #[test]
     fn packet_data_acknowledge() {
-        let data: [u8; 253] = {
+        let data: [u8; Packet::DATA_LEN] = {
             let mut raw = vec![3];
-            raw.resize_with(253, || 0);
+            raw.resize_with(Packet::DATA_LEN, || 0);
             raw.try_into().unwrap()
         };
 
@@ -377,4 +1134,870 @@ mod tests {
 
     #[test]
     fn packet_metrics_response() {}
+
+    #[test]
+    fn packet_seq_roundtrip() {
+        let packet = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::ID(ExtensionId(13)),
+            seq: 42,
+            data: PacketData::Restart,
+        };
+
+        let buffer = packet.serialize().unwrap();
+        assert_eq!([VERSION, 13, 42], buffer[..Packet::HEADER_LEN]);
+
+        let result = Packet::deserialize(&buffer).expect("Should work");
+        assert_eq!(42, result.seq());
+        assert_eq!(ReceiverID::ID(ExtensionId(13)), result.receiver);
+        assert_eq!(PacketData::Restart, result.data);
+    }
+
+    #[test]
+    fn packet_seq_covered_by_crc() {
+        let mut buffer = Packet::ack(ReceiverID::Controller)
+            .with_seq(7)
+            .serialize()
+            .unwrap();
+        buffer[2] = 8;
+
+        assert!(matches!(
+            Packet::deserialize(&buffer),
+            Err(PacketDeserializeError::Checksum)
+        ));
+
+        // Updating the Sequence-Number of a serialized Packet keeps it valid
+        Packet::set_seq(&mut buffer, 9);
+        assert_eq!(9, Packet::deserialize(&buffer).expect("Should work").seq());
+    }
+
+    #[test]
+    fn serialize_metrics_response_streamed() {
+        let metrics = [
+            DataPoint {
+                name: "first",
+                value: Value::Switch { state: true },
+            },
+            DataPoint {
+                name: "second",
+                value: Value::Pwm { percent: 30 },
+            },
+        ];
+
+        let expected = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 3,
+            data: PacketData::MetricsResponse {
+                metrics: (&metrics).into(),
+            },
+        }
+        .serialize()
+        .unwrap();
+
+        let result = Packet::serialize_metrics_response(ReceiverID::Controller, 3, |visit| {
+            for point in metrics.iter() {
+                visit(point.clone());
+            }
+        });
+        assert_eq!(Ok(expected), result);
+    }
+
+    #[test]
+    fn serialize_metrics_response_too_long() {
+        let result = Packet::serialize_metrics_response(ReceiverID::Controller, 0, |visit| {
+            for _ in 0..100 {
+                visit(DataPoint {
+                    name: "testing",
+                    value: Value::Switch { state: true },
+                });
+            }
+        });
+        assert_eq!(Err(PacketSerializeError::MetricsTooLong), result);
+    }
+
+    #[test]
+    fn serialize_metrics_response_fixed_too_long() {
+        let metrics: [DataPoint; 100] = core::array::from_fn(|_| DataPoint {
+            name: "testing",
+            value: Value::Switch { state: true },
+        });
+        let packet = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: PacketData::MetricsResponse {
+                metrics: OptionsIter::from(&metrics),
+            },
+        };
+
+        assert_eq!(
+            Err(PacketSerializeError::MetricsTooLong),
+            packet.serialize()
+        );
+    }
+
+    #[test]
+    fn configure_name_too_long() {
+        let name = "a".repeat(255);
+        let packet = Packet::configure(
+            ExtensionId(13),
+            DataPoint {
+                name: &name,
+                value: Value::Switch { state: true },
+            },
+        );
+
+        assert_eq!(Err(PacketSerializeError::DataTooLarge), packet.serialize());
+    }
+
+    #[test]
+    fn metrics_subset_too_long() {
+        let names: [&str; 30] = ["temperature"; 30];
+        let packet = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::ID(ExtensionId(13)),
+            seq: 0,
+            data: PacketData::MetricsSubset {
+                names: OptionsIter::from(&names),
+            },
+        };
+
+        assert_eq!(Err(PacketSerializeError::DataTooLarge), packet.serialize());
+    }
+
+    #[test]
+    fn packet_error_roundtrip() {
+        let buffer = Packet::error(ReceiverID::Controller)
+            .with_seq(7)
+            .serialize()
+            .unwrap();
+        assert_eq!(4, buffer[Packet::HEADER_LEN]);
+
+        let result = Packet::deserialize(&buffer).expect("Should work");
+        assert_eq!(7, result.seq());
+        assert_eq!(&PacketData::Error {}, result.data());
+    }
+
+    #[test]
+    fn error_frame_matches_serialize() {
+        let expected = Packet::error(ReceiverID::ID(ExtensionId(3)))
+            .with_seq(9)
+            .serialize()
+            .unwrap();
+        assert_eq!(
+            expected,
+            Packet::error_frame(ReceiverID::ID(ExtensionId(3)), 9)
+        );
+    }
+
+    #[test]
+    fn builder_matches_manual() {
+        let option = DataPoint {
+            name: "fan",
+            value: Value::Pwm { percent: 40 },
+        };
+
+        let cases = [
+            (
+                Packet::metrics_request(ExtensionId(3)),
+                Packet {
+                    protocol_version: VERSION,
+                    receiver: ReceiverID::ID(ExtensionId(3)),
+                    seq: 0,
+                    data: PacketData::Metrics,
+                },
+            ),
+            (
+                Packet::configure(ExtensionId(4), option.clone()).with_seq(9),
+                Packet {
+                    protocol_version: VERSION,
+                    receiver: ReceiverID::ID(ExtensionId(4)),
+                    seq: 9,
+                    data: PacketData::Configure { option },
+                },
+            ),
+            (
+                Packet::restart(ExtensionId(5)),
+                Packet {
+                    protocol_version: VERSION,
+                    receiver: ReceiverID::ID(ExtensionId(5)),
+                    seq: 0,
+                    data: PacketData::Restart,
+                },
+            ),
+            (
+                Packet::to(ReceiverID::Everyone).with(PacketData::ConfigureOptions),
+                Packet {
+                    protocol_version: VERSION,
+                    receiver: ReceiverID::Everyone,
+                    seq: 0,
+                    data: PacketData::ConfigureOptions,
+                },
+            ),
+            (
+                Packet::init_probe(),
+                Packet {
+                    protocol_version: VERSION,
+                    receiver: ReceiverID::Everyone,
+                    seq: 0,
+                    data: PacketData::InitProbe,
+                },
+            ),
+        ];
+
+        for (built, manual) in cases {
+            assert_eq!(
+                manual.serialize().unwrap(),
+                built.serialize().unwrap(),
+                "{:?}",
+                manual.data
+            );
+        }
+    }
+
+    #[test]
+    fn serialize_iter_matches_serialize() {
+        let packets = [
+            Packet::init_probe(),
+            Packet::ack(ReceiverID::Controller).with_seq(200),
+            Packet::configure(
+                ExtensionId(4),
+                DataPoint {
+                    name: "fan",
+                    value: Value::Pwm { percent: 40 },
+                },
+            ),
+            Packet::to(ReceiverID::ID(ExtensionId(2))).with(PacketData::Raw { data: &[0, 1, 2] }),
+        ];
+
+        for packet in packets {
+            let mut bytes = packet.serialize_iter().unwrap();
+            assert_eq!(Packet::FRAME_LEN, bytes.len());
+
+            let mut buffer = [0; Packet::FRAME_LEN];
+            for slot in buffer.iter_mut() {
+                *slot = bytes.next().expect("The Iterator yields an entire Frame");
+            }
+            assert_eq!(None, bytes.next());
+
+            assert_eq!(packet.serialize().unwrap(), buffer, "{:?}", packet.data);
+        }
+    }
+
+    #[test]
+    fn frame_consts_consistent() {
+        assert_eq!(Packet::FRAME_LEN, Packet::HEADER_LEN + Packet::DATA_LEN + 1);
+        assert_eq!(Packet::FRAME_LEN - 1, Packet::CRC_INDEX);
+        assert_eq!((252, 255), (Packet::DATA_LEN, Packet::CRC_INDEX));
+
+        // The Length of Raw Data is stored in a single Byte after the Type-ID
+        assert_eq!(Packet::DATA_LEN - 2, RAW_MAX_LEN);
+        assert!(RAW_MAX_LEN <= u8::MAX as usize);
+
+        assert_eq!(
+            Packet::FRAME_LEN,
+            Packet::init_probe().serialize().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn deserialize_checksum_mismatch() {
+        let mut buffer = Packet::ack(ReceiverID::Controller).serialize().unwrap();
+        buffer[255] = buffer[255].wrapping_add(1);
+
+        assert_eq!(
+            Some(PacketDeserializeError::Checksum),
+            Packet::deserialize(&buffer).err()
+        );
+    }
+
+    #[test]
+    fn validate_kind() {
+        let buffer = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: PacketData::AckConfigured {
+                applied: DataPoint {
+                    name: "fan",
+                    value: Value::Pwm { percent: 50 },
+                },
+            },
+        }
+        .serialize()
+        .unwrap();
+
+        assert_eq!(Ok(PacketKind::AckConfigured), Packet::validate(&buffer));
+        assert_eq!(
+            Ok(PacketKind::InitProbe),
+            Packet::validate(&Packet::init_probe().serialize().unwrap())
+        );
+    }
+
+    #[test]
+    fn validate_checksum_mismatch() {
+        let mut buffer = Packet::ack(ReceiverID::Controller).serialize().unwrap();
+        buffer[255] = buffer[255].wrapping_add(1);
+
+        assert_eq!(
+            Err(PacketDeserializeError::Checksum),
+            Packet::validate(&buffer)
+        );
+    }
+
+    #[test]
+    fn validate_other_version() {
+        let mut buffer = Packet::ack(ReceiverID::Controller).serialize().unwrap();
+        buffer[0] = VERSION.wrapping_add(1);
+        buffer[255] = crc8(&buffer[..255]);
+
+        assert_eq!(
+            Err(PacketDeserializeError::UnsupportedVersion(
+                VERSION.wrapping_add(1)
+            )),
+            Packet::validate(&buffer)
+        );
+    }
+
+    #[test]
+    fn validate_unknown_id() {
+        let mut buffer = Packet::ack(ReceiverID::Controller).serialize().unwrap();
+        buffer[Packet::HEADER_LEN] = 200;
+        buffer[Packet::CRC_INDEX] = crc8(&buffer[..Packet::CRC_INDEX]);
+
+        assert_eq!(
+            Err(PacketDeserializeError::Deserialize(
+                PacketDataParseError::UnknownID(200)
+            )),
+            Packet::validate(&buffer)
+        );
+        assert_eq!(
+            Some(PacketDeserializeError::Deserialize(
+                PacketDataParseError::UnknownID(200)
+            )),
+            Packet::deserialize(&buffer).err()
+        );
+    }
+
+    #[test]
+    fn read_blocking_resync_garbage_byte() {
+        use embedded_hal_mock::serial::{Mock as SerialMock, Transaction as SerialTransaction};
+
+        let packet = Packet::ack(ReceiverID::Controller);
+
+        let mut expectations = vec![SerialTransaction::read(0xaa)];
+        expectations.extend(
+            packet
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::read),
+        );
+        let mut serial = SerialMock::new(&expectations);
+
+        let mut buffer = [0; 256];
+        let result =
+            Packet::read_blocking_resync(&mut serial, &mut buffer, 4).expect("Should resync");
+
+        assert_eq!(&ReceiverID::Controller, result.receiver());
+        assert_eq!(&PacketData::Acknowledge, result.data());
+
+        serial.done();
+    }
+
+    #[test]
+    fn read_blocking_resync_gives_up() {
+        use embedded_hal_mock::serial::{Mock as SerialMock, Transaction as SerialTransaction};
+
+        let packet = Packet::ack(ReceiverID::Controller);
+
+        let mut expectations = vec![SerialTransaction::read(0xaa); 3];
+        expectations.extend(
+            packet
+                .serialize()
+                .unwrap()
+                .into_iter()
+                .map(SerialTransaction::read),
+        );
+        // The last Byte of the Packet is never read, as we give up after the second resync
+        expectations.truncate(Packet::FRAME_LEN + 2);
+        let mut serial = SerialMock::new(&expectations);
+
+        let mut buffer = [0; 256];
+        let result = Packet::read_blocking_resync(&mut serial, &mut buffer, 2);
+
+        assert!(matches!(
+            result,
+            Err(PacketReadError::Deserialize(
+                PacketDeserializeError::Checksum
+            ))
+        ));
+
+        serial.done();
+    }
+
+    /// A serial that returns the given Bytes and then blocks forever
+    struct StallingSerial {
+        data: std::vec::IntoIter<u8>,
+        reads: u32,
+    }
+
+    impl embedded_hal::serial::ErrorType for StallingSerial {
+        type Error = core::convert::Infallible;
+    }
+    impl embedded_hal::serial::nb::Read for StallingSerial {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            self.reads += 1;
+            self.data.next().ok_or(nb::Error::WouldBlock)
+        }
+    }
+
+    #[test]
+    fn read_blocking_bounded_timeout() {
+        let packet = Packet::ack(ReceiverID::Controller);
+
+        let mut serial = StallingSerial {
+            data: packet.serialize().unwrap()[..100].to_vec().into_iter(),
+            reads: 0,
+        };
+
+        let mut buffer = [0; 256];
+        let result = Packet::read_blocking_bounded(&mut serial, &mut buffer, 10);
+
+        assert!(matches!(result, Err(PacketReadError::Timeout)));
+        // 100 successful reads and then the initial attempt plus 10 retries for the next Byte
+        assert_eq!(100 + 11, serial.reads);
+    }
+
+    #[test]
+    fn read_blocking_bounded_complete() {
+        let packet = Packet::ack(ReceiverID::Controller);
+
+        let mut serial = StallingSerial {
+            data: packet.serialize().unwrap().to_vec().into_iter(),
+            reads: 0,
+        };
+
+        let mut buffer = [0; 256];
+        let result =
+            Packet::read_blocking_bounded(&mut serial, &mut buffer, 0).expect("Should work");
+
+        assert_eq!(&PacketData::Acknowledge, result.data());
+    }
+
+    #[test]
+    fn extension_id_conversions() {
+        assert_eq!(ExtensionId(13), ExtensionId::from(13));
+        assert_eq!(13u8, u8::from(ExtensionId(13)));
+    }
+
+    #[test]
+    fn receiver_id_matches() {
+        let me = Some(ExtensionId(13));
+        let kind = PacketKind::Configure;
+
+        assert!(ReceiverID::Everyone.matches(me, true, kind));
+        assert!(!ReceiverID::Everyone.matches(me, false, kind));
+        assert!(ReceiverID::ID(ExtensionId(13)).matches(me, false, kind));
+        assert!(!ReceiverID::ID(ExtensionId(12)).matches(me, true, kind));
+        assert!(!ReceiverID::Controller.matches(me, true, kind));
+        assert!(!ReceiverID::Controller.matches(me, false, kind));
+
+        // Without an ID, only the selection matters
+        assert!(ReceiverID::Everyone.matches(None, true, kind));
+        assert!(!ReceiverID::ID(ExtensionId(13)).matches(None, true, kind));
+    }
+
+    #[test]
+    fn receiver_id_matches_broadcast() {
+        let kind = PacketKind::ConfigureBroadcast;
+
+        // A Broadcast to Everyone ignores the selection
+        assert!(ReceiverID::Everyone.matches(Some(ExtensionId(13)), false, kind));
+        assert!(ReceiverID::Everyone.matches(None, false, kind));
+        assert!(!ReceiverID::ID(ExtensionId(12)).matches(Some(ExtensionId(13)), true, kind));
+        assert!(!ReceiverID::Controller.matches(Some(ExtensionId(13)), true, kind));
+    }
+
+    #[test]
+    fn receiver_id_conversions() {
+        assert_eq!(ReceiverID::Controller, ReceiverID::from(0x00));
+        assert_eq!(ReceiverID::Everyone, ReceiverID::from(0xff));
+        assert_eq!(ReceiverID::ID(ExtensionId(13)), ReceiverID::from(13));
+
+        assert_eq!(13u8, u8::from(ReceiverID::ID(ExtensionId(13))));
+        assert_eq!(0xffu8, u8::from(&ReceiverID::Everyone));
+    }
+
+    #[test]
+    fn packet_data_ack_configured_roundtrip() {
+        let data = PacketData::AckConfigured {
+            applied: DataPoint {
+                name: "fan",
+                value: Value::Pwm { percent: 100 },
+            },
+        };
+
+        let mut raw = [0; Packet::DATA_LEN];
+        data.serialize(&mut raw).unwrap();
+        assert_eq!(11, raw[0]);
+
+        let result = PacketData::parse(0, &raw).expect("Should work");
+        assert_eq!(data, result);
+    }
+
+    #[test]
+    fn packet_data_configure_invalid() {
+        let mut raw = [0; Packet::DATA_LEN];
+        raw[0] = 6;
+        raw[1..5].copy_from_slice(&[3, b'f', b'a', b'n']);
+        raw[5] = 200;
+        assert_eq!(
+            Err(PacketDataParseError::InvalidDataPoint(
+                DataPointDeserializeError::ValueError(ValueDeserializeError::UnknownType(200))
+            )),
+            PacketData::parse(0, &raw)
+        );
+    }
+
+    #[test]
+    fn packet_data_configure_broadcast_invalid() {
+        let mut raw = [0; Packet::DATA_LEN];
+        raw[0] = 14;
+        raw[1..5].copy_from_slice(&[3, b'f', b'a', b'n']);
+        raw[5] = 200;
+        assert_eq!(
+            Err(PacketDataParseError::InvalidDataPoint(
+                DataPointDeserializeError::ValueError(ValueDeserializeError::UnknownType(200))
+            )),
+            PacketData::parse(0, &raw)
+        );
+    }
+
+    #[test]
+    fn packet_data_ack_configured_invalid() {
+        let mut raw = [0; Packet::DATA_LEN];
+        raw[0] = 11;
+        raw[1..5].copy_from_slice(&[3, b'f', b'a', b'n']);
+        raw[5] = 200;
+        assert_eq!(
+            Err(PacketDataParseError::InvalidDataPoint(
+                DataPointDeserializeError::ValueError(ValueDeserializeError::UnknownType(200))
+            )),
+            PacketData::parse(0, &raw)
+        );
+
+        // The Name is not valid UTF-8
+        raw[1..5].copy_from_slice(&[3, 0xff, 0xfe, 0xfd]);
+        raw[5] = 1;
+        assert_eq!(
+            Err(PacketDataParseError::InvalidDataPoint(
+                DataPointDeserializeError::Other
+            )),
+            PacketData::parse(0, &raw)
+        );
+
+        // The Name is longer than the remaining Data
+        raw[1] = 255;
+        assert_eq!(
+            Err(PacketDataParseError::InvalidDataPoint(
+                DataPointDeserializeError::Other
+            )),
+            PacketData::parse(0, &raw)
+        );
+    }
+
+    #[test]
+    fn packet_data_metrics_subset_roundtrip() {
+        let data = PacketData::MetricsSubset {
+            names: OptionsIter::from(&["temperature", "fan"]),
+        };
+
+        let mut raw = [0; Packet::DATA_LEN];
+        data.serialize(&mut raw).unwrap();
+        assert_eq!([13, 2], raw[..2]);
+
+        let names = match PacketData::parse(0, &raw).expect("Should work") {
+            PacketData::MetricsSubset { names } => names,
+            other => panic!("Expected MetricsSubset, got {:?}", other),
+        };
+        assert_eq!(vec!["temperature", "fan"], names.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn packet_data_metrics_subset_invalid() {
+        // The second Name is longer than the remaining Data
+        let mut raw = [0; Packet::DATA_LEN];
+        raw[0] = 13;
+        raw[1] = 2;
+        raw[2..6].copy_from_slice(&[3, b'f', b'a', b'n']);
+        raw[6] = 250;
+        assert_eq!(
+            Err(PacketDataParseError::InvalidNames(
+                OptionsIterDeserializeError::InnerError(())
+            )),
+            PacketData::parse(0, &raw)
+        );
+    }
+
+    #[test]
+    fn packet_data_configure_broadcast_roundtrip() {
+        let packet = Packet::configure_broadcast(DataPoint {
+            name: "fan",
+            value: Value::Pwm { percent: 50 },
+        });
+        assert_eq!(&ReceiverID::Everyone, packet.receiver());
+
+        let buffer = packet.serialize().unwrap();
+        assert_eq!(
+            Ok(PacketKind::ConfigureBroadcast),
+            Packet::validate(&buffer)
+        );
+
+        let result = Packet::deserialize(&buffer).expect("Should work");
+        assert_eq!(packet.data, result.data);
+    }
+
+    #[test]
+    fn packet_data_info_roundtrip() {
+        let packet = Packet::info_request(ExtensionId(3)).with_seq(4);
+
+        let buffer = packet.serialize().unwrap();
+        assert_eq!(Ok(PacketKind::Info), Packet::validate(&buffer));
+
+        let result = Packet::deserialize(&buffer).expect("Should work");
+        assert_eq!(PacketData::Info, result.data);
+        assert_eq!(4, result.seq());
+    }
+
+    #[test]
+    fn packet_data_info_response_roundtrip() {
+        let data = PacketData::InfoResponse {
+            fw_version: 0x0102,
+            build: *b"1a2b3c4d",
+        };
+
+        let mut raw = [0; Packet::DATA_LEN];
+        data.serialize(&mut raw).unwrap();
+        assert_eq!([16, 0x01, 0x02], raw[..3]);
+        assert_eq!(b"1a2b3c4d", &raw[3..11]);
+
+        let result = PacketData::parse(0, &raw).expect("Should work");
+        assert_eq!(PacketKind::InfoResponse, result.kind());
+        assert_eq!(data, result);
+    }
+
+    #[test]
+    fn packet_data_metrics_subset_empty_roundtrip() {
+        let data = PacketData::MetricsSubset {
+            names: OptionsIter::from(&[]),
+        };
+
+        let mut raw = [0; Packet::DATA_LEN];
+        data.serialize(&mut raw).unwrap();
+
+        let result = PacketData::parse(0, &raw).expect("Should work");
+        assert_eq!(PacketKind::MetricsSubset, result.kind());
+        match result {
+            PacketData::MetricsSubset { names } => assert_eq!(0, names.length()),
+            other => panic!("Expected MetricsSubset, got {:?}", other),
+        };
+    }
+
+    #[test]
+    fn packet_data_metrics_response_empty_roundtrip() {
+        let data = PacketData::MetricsResponse {
+            metrics: OptionsIter::from(&[]),
+        };
+
+        let mut raw = [0; Packet::DATA_LEN];
+        data.serialize(&mut raw).unwrap();
+        assert_eq!([8, 0], raw[..2]);
+
+        let result = PacketData::parse(0, &raw).expect("Should work");
+
+        // The received, empty List serializes to the same Data again
+        let mut again = [0; Packet::DATA_LEN];
+        result.serialize(&mut again).unwrap();
+        assert_eq!(raw, again);
+
+        match result {
+            PacketData::MetricsResponse { metrics } => assert_eq!(0, metrics.count()),
+            other => panic!("Expected MetricsResponse, got {:?}", other),
+        };
+    }
+
+    #[test]
+    fn packet_data_configure_options_response_empty_roundtrip() {
+        let data = PacketData::ConfigureOptionsResponse {
+            options: OptionsIter::from(&[]),
+        };
+
+        let mut raw = [0; Packet::DATA_LEN];
+        data.serialize(&mut raw).unwrap();
+        assert_eq!([10, 0], raw[..2]);
+
+        let result = PacketData::parse(0, &raw).expect("Should work");
+
+        let mut again = [0; Packet::DATA_LEN];
+        result.serialize(&mut again).unwrap();
+        assert_eq!(raw, again);
+
+        match result {
+            PacketData::ConfigureOptionsResponse { options } => assert_eq!(0, options.count()),
+            other => panic!("Expected ConfigureOptionsResponse, got {:?}", other),
+        };
+    }
+
+    #[test]
+    fn packet_data_raw_empty_roundtrip() {
+        let data = PacketData::Raw { data: &[] };
+
+        let mut raw = [0; Packet::DATA_LEN];
+        data.serialize(&mut raw).expect("Should work");
+        assert_eq!([12, 0], raw[..2]);
+
+        let result = PacketData::parse(0, &raw).expect("Should work");
+        assert_eq!(data, result);
+    }
+
+    #[test]
+    fn packet_data_raw_max_roundtrip() {
+        let payload: Vec<u8> = (0..RAW_MAX_LEN).map(|i| i as u8).collect();
+        let packet = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: PacketData::Raw { data: &payload },
+        };
+
+        let buffer = packet.serialize().expect("Should work");
+
+        let result = Packet::deserialize(&buffer).expect("Should work");
+        assert_eq!(&PacketData::Raw { data: &payload }, result.data());
+    }
+
+    #[test]
+    fn packet_data_raw_too_long() {
+        let payload = [0xab; RAW_MAX_LEN + 1];
+        let packet = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: PacketData::Raw { data: &payload },
+        };
+
+        assert_eq!(
+            Err(PacketSerializeError::RawTooLong(RAW_MAX_LEN + 1)),
+            packet.serialize()
+        );
+
+        // A received length, that exceeds the available space, is rejected as well
+        let mut raw = [0; Packet::DATA_LEN];
+        raw[0] = 12;
+        raw[1] = RAW_MAX_LEN as u8 + 1;
+        assert_eq!(
+            Err(PacketDataParseError::InvalidRawLength(
+                RAW_MAX_LEN as u8 + 1
+            )),
+            PacketData::parse(0, &raw)
+        );
+    }
+
+    #[test]
+    fn owned_packet_outlives_buffer() {
+        let option = DataPoint {
+            name: "fan",
+            value: Value::Pwm { percent: 42 },
+        };
+        let mut buffer = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::ID(ExtensionId(13)),
+            seq: 0,
+            data: PacketData::Configure {
+                option: option.clone(),
+            },
+        }
+        .serialize()
+        .unwrap();
+
+        let owned = Packet::deserialize(&buffer)
+            .expect("Should work")
+            .to_owned()
+            .unwrap();
+
+        // Reuse the receive Buffer for another Packet
+        buffer = Packet::ack(ReceiverID::Controller).serialize().unwrap();
+        assert!(Packet::deserialize(&buffer).is_ok());
+
+        let packet = owned.packet();
+        assert_eq!(&ReceiverID::ID(ExtensionId(13)), packet.receiver());
+        assert_eq!(&PacketData::Configure { option }, packet.data());
+    }
+
+    #[test]
+    fn cobs_roundtrip() {
+        let packet = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::ID(ExtensionId(13)),
+            seq: 0,
+            data: PacketData::Init {
+                id: ExtensionId(13),
+            },
+        };
+
+        let (frame, length) = packet.serialize_cobs().unwrap();
+        assert!(frame[..length - 1].iter().all(|b| *b != 0));
+        assert_eq!(0, frame[length - 1]);
+
+        let mut buffer = [0; 256];
+        let result = Packet::deserialize_cobs(&frame[..length], &mut buffer).expect("Should work");
+
+        assert_eq!(packet.receiver, result.receiver);
+        assert_eq!(packet.data, result.data);
+    }
+
+    #[test]
+    fn cobs_roundtrip_zero_heavy() {
+        // Most of the Packet consists of zeros, the name and value add some more in between
+        let options = [
+            DataPoint {
+                name: "\0\0\0",
+                value: Value::Switch { state: false },
+            },
+            DataPoint {
+                name: "",
+                value: Value::Pwm { percent: 0 },
+            },
+        ];
+        let packet = Packet {
+            protocol_version: VERSION,
+            receiver: ReceiverID::Controller,
+            seq: 0,
+            data: PacketData::MetricsResponse {
+                metrics: (&options).into(),
+            },
+        };
+
+        let (frame, length) = packet.serialize_cobs().unwrap();
+        assert!(frame[..length - 1].iter().all(|b| *b != 0));
+
+        let mut buffer = [0; 256];
+        let result = Packet::deserialize_cobs(&frame[..length], &mut buffer).expect("Should work");
+
+        assert_eq!(packet.serialize().unwrap(), result.serialize().unwrap());
+    }
+
+    #[test]
+    fn cobs_wrong_length() {
+        let mut frame = [0; COBS_FRAME_SIZE];
+        let length = cobs::encode(&[1, 2, 3], &mut frame);
+
+        let mut buffer = [0; 256];
+        assert!(matches!(
+            Packet::deserialize_cobs(&frame[..length], &mut buffer),
+            Err(CobsDeserializeError::Length(3))
+        ));
+    }
 }
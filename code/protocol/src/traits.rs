@@ -1,3 +1,5 @@
+use general::string::FixedStr;
+
 /// A general Serialize/Deserialize trait to make composition of types easier
 pub trait Sendable<'r>: Sized {
     type SerError;
@@ -12,13 +14,37 @@ pub trait Sendable<'r>: Sized {
     fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError>;
 }
 
+/// The Error returned when the Buffer is too small to hold the serialized Data
+#[derive(Debug, PartialEq, Eq)]
+pub struct BufferTooSmall;
+
+/// The Error returned when serializing a Name
+#[derive(Debug, PartialEq, Eq)]
+pub enum NameSerializeError {
+    /// The Name is longer than the 255 bytes, that can be stored in its single length byte
+    NameTooLong,
+    /// The Buffer is too small to hold the serialized Data
+    BufferTooSmall,
+}
+
+impl From<BufferTooSmall> for NameSerializeError {
+    fn from(_: BufferTooSmall) -> Self {
+        Self::BufferTooSmall
+    }
+}
+
+/// A str is serialized as a single length byte followed by its raw bytes, so it can be at most
+/// 255 bytes long
 impl<'r> Sendable<'r> for &'r str {
-    type SerError = ();
+    type SerError = NameSerializeError;
     type DeSerError = ();
 
     fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+        if self.len() > u8::MAX as usize {
+            return Err(NameSerializeError::NameTooLong);
+        }
         if buffer.len() < self.len() + 1 {
-            return Err(());
+            return Err(NameSerializeError::BufferTooSmall);
         }
 
         buffer[0] = self.len() as u8;
@@ -27,18 +53,260 @@ impl<'r> Sendable<'r> for &'r str {
         Ok(&mut buffer[(1 + self.len())..])
     }
 
+    /// Fails if the Buffer is shorter than the Length-Byte claims or the Name is not valid UTF-8
+    fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
+        let (len, rest) = buffer.split_first().ok_or(())?;
+        let len = *len as usize;
+        if rest.len() < len {
+            return Err(());
+        }
+
+        let value = core::str::from_utf8(&rest[..len]).map_err(|_| ())?;
+
+        Ok((value, &rest[len..]))
+    }
+}
+
+/// A FixedStr is serialized exactly like a `&str` with the same content, so either of them can
+/// be used on both sides of the Link
+impl<'r, const N: usize> Sendable<'r> for FixedStr<N> {
+    type SerError = NameSerializeError;
+    type DeSerError = ();
+
+    fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+        self.as_str().serialize(buffer)
+    }
+
+    /// Fails if the received Name does not fit into the Capacity of the FixedStr
+    fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
+        let (value, rest): (&str, _) = Sendable::deserialize(buffer)?;
+        let value = value.parse().map_err(|_| ())?;
+
+        Ok((value, rest))
+    }
+}
+
+impl<'r> Sendable<'r> for u16 {
+    type SerError = BufferTooSmall;
+    type DeSerError = ();
+
+    fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+        if buffer.len() < 2 {
+            return Err(BufferTooSmall);
+        }
+
+        buffer[0..2].copy_from_slice(&self.to_be_bytes());
+
+        Ok(&mut buffer[2..])
+    }
+
+    fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
+        if buffer.len() < 2 {
+            return Err(());
+        }
+
+        Ok((u16::from_be_bytes([buffer[0], buffer[1]]), &buffer[2..]))
+    }
+}
+
+/// An i16 is serialized as its two's-complement big-endian Bytes
+impl<'r> Sendable<'r> for i16 {
+    type SerError = BufferTooSmall;
+    type DeSerError = ();
+
+    fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+        if buffer.len() < 2 {
+            return Err(BufferTooSmall);
+        }
+
+        buffer[0..2].copy_from_slice(&self.to_be_bytes());
+
+        Ok(&mut buffer[2..])
+    }
+
+    fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
+        if buffer.len() < 2 {
+            return Err(());
+        }
+
+        Ok((i16::from_be_bytes([buffer[0], buffer[1]]), &buffer[2..]))
+    }
+}
+
+/// An Option is serialized as a single Tag-Byte, `0` for None and `1` for Some, followed by the
+/// inner Value if it is present
+impl<'r, T> Sendable<'r> for Option<T>
+where
+    T: Sendable<'r>,
+    T::SerError: From<BufferTooSmall>,
+    T::DeSerError: From<()>,
+{
+    type SerError = T::SerError;
+    type DeSerError = T::DeSerError;
+
+    fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+        if buffer.is_empty() {
+            return Err(BufferTooSmall.into());
+        }
+
+        match self {
+            Some(inner) => {
+                buffer[0] = 1;
+                inner.serialize(&mut buffer[1..])
+            }
+            None => {
+                buffer[0] = 0;
+                Ok(&mut buffer[1..])
+            }
+        }
+    }
+
     fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
-        let len = buffer[0] as usize;
-        let value = core::str::from_utf8(&buffer[1..(len + 1)]).expect("");
+        match buffer.first() {
+            Some(0) => Ok((None, &buffer[1..])),
+            Some(1) => {
+                let (inner, rest) = T::deserialize(&buffer[1..])?;
+                Ok((Some(inner), rest))
+            }
+            _ => Err(().into()),
+        }
+    }
+}
 
-        Ok((value, &buffer[(1 + len)..]))
+/// The Error for (De-)Serializing a Pair, indicating which of the two Elements failed
+#[derive(Debug, PartialEq, Eq)]
+pub enum PairError<A, B> {
+    First(A),
+    Second(B),
+}
+
+/// A Pair is serialized as the first Element directly followed by the second Element
+impl<'r, A, B> Sendable<'r> for (A, B)
+where
+    A: Sendable<'r>,
+    B: Sendable<'r>,
+{
+    type SerError = PairError<A::SerError, B::SerError>;
+    type DeSerError = PairError<A::DeSerError, B::DeSerError>;
+
+    fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+        let rest = self.0.serialize(buffer).map_err(PairError::First)?;
+        self.1.serialize(rest).map_err(PairError::Second)
     }
+
+    fn deserialize(buffer: &'r [u8]) -> Result<(Self, &'r [u8]), Self::DeSerError> {
+        let (first, rest) = A::deserialize(buffer).map_err(PairError::First)?;
+        let (second, rest) = B::deserialize(rest).map_err(PairError::Second)?;
+
+        Ok(((first, second), rest))
+    }
+}
+
+/// Implements [`Sendable`] for a Struct, by serializing the given Fields one after the other in
+/// the given order, just like a Tuple.
+///
+/// The Errors of the Fields are converted into the given `SerError` and `DeSerError` using
+/// [`From`], so they need to be implemented for the Errors of all the Fields.
+///
+/// # Example
+/// ```rust
+/// # use protocol::{sendable_struct, NameSerializeError, Sendable};
+/// struct Reading<'r> {
+///     sensor: &'r str,
+///     raw: i16,
+/// }
+///
+/// sendable_struct! {
+///     Reading<'r> {
+///         sensor: &'r str,
+///         raw: i16,
+///     }
+///     SerError = NameSerializeError;
+///     DeSerError = ();
+/// }
+///
+/// let mut buffer = [0; 8];
+/// Reading { sensor: "temp", raw: -1 }.serialize(&mut buffer).unwrap();
+/// assert_eq!([4, b't', b'e', b'm', b'p', 0xff, 0xff, 0], buffer);
+///
+/// let (reading, _) = Reading::deserialize(&buffer).unwrap();
+/// assert_eq!(("temp", -1), (reading.sensor, reading.raw));
+/// ```
+#[macro_export]
+macro_rules! sendable_struct {
+    (
+        $name:ident<$lt:lifetime> { $($field:ident : $ty:ty),+ $(,)? }
+        SerError = $ser:ty;
+        DeSerError = $de:ty;
+    ) => {
+        impl<$lt> $crate::Sendable<$lt> for $name<$lt> {
+            type SerError = $ser;
+            type DeSerError = $de;
+
+            $crate::sendable_struct!(@methods $lt, $($field : $ty),+);
+        }
+    };
+    (
+        $name:ident { $($field:ident : $ty:ty),+ $(,)? }
+        SerError = $ser:ty;
+        DeSerError = $de:ty;
+    ) => {
+        impl<'r> $crate::Sendable<'r> for $name {
+            type SerError = $ser;
+            type DeSerError = $de;
+
+            $crate::sendable_struct!(@methods 'r, $($field : $ty),+);
+        }
+    };
+    (@methods $lt:lifetime, $($field:ident : $ty:ty),+) => {
+        fn serialize<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b mut [u8], Self::SerError> {
+            let rest = buffer;
+            $(
+                let rest = $crate::Sendable::serialize(&self.$field, rest)?;
+            )+
+            Ok(rest)
+        }
+
+        fn deserialize(buffer: &$lt [u8]) -> Result<(Self, &$lt [u8]), Self::DeSerError> {
+            let rest = buffer;
+            $(
+                let ($field, rest) = <$ty as $crate::Sendable<$lt>>::deserialize(rest)?;
+            )+
+            Ok((Self { $($field),+ }, rest))
+        }
+    };
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn fixed_str_sendable() {
+        let content: FixedStr<16> = "testing stuff".parse().unwrap();
+
+        let mut buffer = [0; 100];
+        let rest_len = content.serialize(&mut buffer).expect("Should work").len();
+
+        let mut str_buffer = [0; 100];
+        "testing stuff".serialize(&mut str_buffer).unwrap();
+        assert_eq!(str_buffer, buffer);
+
+        let (deserialized, rest): (FixedStr<16>, _) =
+            Sendable::deserialize(&buffer).expect("Should work");
+        assert_eq!(content, deserialized);
+        assert_eq!(rest_len, rest.len());
+    }
+
+    #[test]
+    fn fixed_str_deserialize_too_long() {
+        let mut buffer = [0; 100];
+        "testing stuff".serialize(&mut buffer).unwrap();
+
+        let result: Result<(FixedStr<4>, _), _> = Sendable::deserialize(&buffer);
+        assert_eq!(Err(()), result);
+    }
+
     #[test]
     fn str_sendable() {
         let mut buffer = [0; 100];
@@ -58,4 +326,132 @@ mod tests {
         let content = "testing";
         assert!(content.serialize(&mut buffer).is_err());
     }
+
+    #[test]
+    fn str_serialize_too_long() {
+        let mut buffer = [0; 512];
+        let content = "a".repeat(300);
+
+        assert_eq!(
+            Err(NameSerializeError::NameTooLong),
+            content.as_str().serialize(&mut buffer).map(|_| ())
+        );
+        assert!(buffer.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn str_serialize_max_length() {
+        let mut buffer = [0; 512];
+        let content = "a".repeat(255);
+
+        let rest = content
+            .as_str()
+            .serialize(&mut buffer)
+            .expect("Should work");
+        assert_eq!(512 - 256, rest.len());
+        assert_eq!(255, buffer[0]);
+    }
+
+    #[test]
+    fn option_some_sendable() {
+        let mut buffer = [0; 100];
+
+        let content = Some("testing stuff");
+
+        let rest = content.serialize(&mut buffer).expect("Should work");
+        assert_eq!(100 - 15, rest.len());
+
+        let (deserialized, _): (Option<&str>, _) =
+            Sendable::deserialize(&buffer).expect("Should work");
+
+        assert_eq!(content, deserialized);
+    }
+
+    #[test]
+    fn option_none_sendable() {
+        let mut buffer = [0xff; 100];
+
+        let content: Option<&str> = None;
+
+        let rest = content.serialize(&mut buffer).expect("Should work");
+        assert_eq!(99, rest.len());
+
+        let (deserialized, rest): (Option<&str>, _) =
+            Sendable::deserialize(&buffer).expect("Should work");
+
+        assert_eq!(content, deserialized);
+        assert_eq!(99, rest.len());
+    }
+
+    #[test]
+    fn option_unknown_tag() {
+        let buffer = [2; 10];
+
+        assert!(<Option<&str> as Sendable>::deserialize(&buffer).is_err());
+    }
+
+    #[test]
+    fn pair_sendable() {
+        let mut buffer = [0; 100];
+
+        let content: (u16, &str) = (0x1234, "testing stuff");
+
+        content.serialize(&mut buffer).expect("Should work");
+        assert_eq!([0x12, 0x34, 13], buffer[0..3]);
+
+        let (deserialized, _): ((u16, &str), _) =
+            Sendable::deserialize(&buffer).expect("Should work");
+
+        assert_eq!(content, deserialized);
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Reading {
+        id: u16,
+        raw: i16,
+    }
+
+    sendable_struct! {
+        Reading {
+            id: u16,
+            raw: i16,
+        }
+        SerError = BufferTooSmall;
+        DeSerError = ();
+    }
+
+    #[test]
+    fn sendable_struct_without_lifetime() {
+        let mut buffer = [0; 5];
+
+        let reading = Reading {
+            id: 0x1234,
+            raw: -2,
+        };
+        let rest = reading.serialize(&mut buffer).expect("Should work");
+        assert_eq!(1, rest.len());
+        assert_eq!([0x12, 0x34, 0xff, 0xfe, 0], buffer);
+
+        let (deserialized, rest) = Reading::deserialize(&buffer).expect("Should work");
+        assert_eq!(reading, deserialized);
+        assert_eq!(&[0], rest);
+
+        assert_eq!(
+            Err(BufferTooSmall),
+            reading.serialize(&mut buffer[..3]).map(|_| ())
+        );
+        assert_eq!(Err(()), Reading::deserialize(&buffer[..3]).map(|_| ()));
+    }
+
+    #[test]
+    fn pair_serialize_second_too_small() {
+        let mut buffer = [0; 4];
+
+        let content: (u16, &str) = (13, "testing");
+
+        assert_eq!(
+            Err(PairError::Second(NameSerializeError::BufferTooSmall)),
+            content.serialize(&mut buffer).map(|_| ())
+        );
+    }
 }
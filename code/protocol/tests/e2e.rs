@@ -0,0 +1,389 @@
+//! Runs an Extension using the `block_on` of the executor and drives it using the Controller
+//! over a loopback serial, to test the executor, the protocol and the serial abstraction together
+//! instead of in isolation.
+//!
+//! The Controller only has a blocking API, so it can't be polled together with the Extension on
+//! a single Thread. Instead it runs on its own Thread, while the Extension is driven to completion
+//! on the main Thread, which also checks that the Extension is woken from another Thread.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    convert::Infallible,
+    future::{ready, Future, Ready},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    thread,
+    time::Duration,
+};
+
+use embedded_hal::digital::blocking::{InputPin, OutputPin};
+use executor::block_on;
+use general::AsyncSerial;
+use protocol::{
+    packet::{ExtensionId, Packet, PacketData, PacketDirection, PacketKind, ReceiverID},
+    ConfigOption, Controller, DataPoint, Extension, ReadyCheck, Select, SlotIndex, Value,
+    ValueType,
+};
+
+/// How long a blocking read waits for the next Frame, before reporting that it would block
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A single Line between the Controller and the Extension, like the ready or the select line
+#[derive(Clone, Default)]
+struct Line(Arc<AtomicBool>);
+
+impl embedded_hal::digital::ErrorType for Line {
+    type Error = Infallible;
+}
+impl OutputPin for Line {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+impl InputPin for Line {
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.0.load(Ordering::SeqCst))
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        Ok(!self.0.load(Ordering::SeqCst))
+    }
+}
+impl ReadyCheck<1> for Line {
+    fn check(&self, _: SlotIndex) -> bool {
+        // Gives the Extension a chance to react, as it runs on another Thread
+        thread::yield_now();
+        self.0.load(Ordering::SeqCst)
+    }
+
+    fn check_all(&self) -> [bool; 1] {
+        [self.check(SlotIndex(0))]
+    }
+}
+impl Select<1> for Line {
+    fn select(&mut self, slot: SlotIndex) {
+        self.0.store(slot == SlotIndex(0), Ordering::SeqCst);
+    }
+}
+
+/// The Frames send in one Direction of the serial connection
+#[derive(Default)]
+struct WireState {
+    frames: VecDeque<[u8; 256]>,
+    /// The Waker of the Extension waiting for the next Frame
+    waker: Option<Waker>,
+}
+
+/// One Direction of the serial connection, shared between both Threads
+#[derive(Clone, Default)]
+struct Wire(Arc<(Mutex<WireState>, Condvar)>);
+
+impl Wire {
+    fn push(&self, frame: [u8; 256]) {
+        let (state, arrived) = &*self.0;
+        let mut state = state.lock().unwrap();
+        state.frames.push_back(frame);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        arrived.notify_all();
+    }
+
+    /// Waits up to [`READ_TIMEOUT`] for the next Frame
+    fn pop_blocking(&self) -> Option<[u8; 256]> {
+        let (state, arrived) = &*self.0;
+        let state = state.lock().unwrap();
+        let (mut state, _) = arrived
+            .wait_timeout_while(state, READ_TIMEOUT, |s| s.frames.is_empty())
+            .unwrap();
+        state.frames.pop_front()
+    }
+}
+
+/// The async half of one end of the serial connection, used by the running Extension
+struct WireSerial {
+    incoming: Wire,
+    outgoing: Wire,
+}
+
+/// Creates both ends of the serial connection
+fn wires() -> (WireSerial, WireSerial) {
+    let (first, second) = (Wire::default(), Wire::default());
+    (
+        WireSerial {
+            incoming: first.clone(),
+            outgoing: second.clone(),
+        },
+        WireSerial {
+            incoming: second,
+            outgoing: first,
+        },
+    )
+}
+
+struct WireRead<'w>(&'w Wire);
+
+impl<'w> Future for WireRead<'w> {
+    type Output = [u8; 256];
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = (self.0).0 .0.lock().unwrap();
+        match state.frames.pop_front() {
+            Some(frame) => Poll::Ready(frame),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl AsyncSerial<256> for WireSerial {
+    type ReceiveFuture<'f> = WireRead<'f> where Self: 'f;
+    type WriteFuture<'f> = Ready<()> where Self: 'f;
+
+    fn read<'s, 'f>(&'s mut self) -> Self::ReceiveFuture<'f>
+    where
+        's: 'f,
+    {
+        WireRead(&self.incoming)
+    }
+
+    fn write<'s, 'f>(&'s mut self, buffer: [u8; 256]) -> Self::WriteFuture<'f>
+    where
+        's: 'f,
+    {
+        self.outgoing.push(buffer);
+        ready(())
+    }
+}
+
+/// One end of the serial connection, that can be used blocking, like by the Controller, and
+/// converted into the async serial used by the running Extension
+struct Link {
+    serial: WireSerial,
+    /// The rest of the Frame, that is currently being read
+    incoming: Vec<u8>,
+    /// The Frame, that is currently being written
+    outgoing: Vec<u8>,
+}
+
+impl Link {
+    fn new(serial: WireSerial) -> Self {
+        Self {
+            serial,
+            incoming: Vec::new(),
+            outgoing: Vec::new(),
+        }
+    }
+}
+
+impl embedded_hal::serial::ErrorType for Link {
+    type Error = Infallible;
+}
+impl embedded_hal::serial::nb::Read for Link {
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        if self.incoming.is_empty() {
+            let frame = self
+                .serial
+                .incoming
+                .pop_blocking()
+                .ok_or(nb::Error::WouldBlock)?;
+            self.incoming.extend(frame.into_iter().rev());
+        }
+
+        Ok(self.incoming.pop().expect("A Frame was just received"))
+    }
+}
+impl embedded_hal::serial::nb::Write for Link {
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.outgoing.push(word);
+
+        if self.outgoing.len() == 256 {
+            let frame: [u8; 256] = self.outgoing.as_slice().try_into().unwrap();
+            self.outgoing.clear();
+            self.serial.outgoing.push(frame);
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Restarts the Extension, if the Controller panics, so the Extension stops as well and the
+/// panic is reported instead of the Test hanging
+struct RestartOnPanic(Wire);
+
+impl Drop for RestartOnPanic {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0
+                .push(Packet::restart(ExtensionId(13)).serialize().unwrap());
+        }
+    }
+}
+
+static OPTIONS: [ConfigOption<'static>; 1] = [ConfigOption {
+    name: "fan",
+    ty: ValueType::Pwm,
+}];
+
+#[test]
+fn discover_configure_metrics_restart() {
+    let ready = Line::default();
+    let mut select = Line::default();
+    let (ctrl_serial, ext_serial) = wires();
+
+    // The Controller can't assign IDs, so the Extension is initialized by hand
+    let mut ctrl_link = Link::new(ctrl_serial);
+    select.select(SlotIndex(0));
+    for byte in Packet::to(ReceiverID::Everyone)
+        .with(PacketData::Init {
+            id: ExtensionId(13),
+        })
+        .serialize()
+        .unwrap()
+    {
+        nb::block!(embedded_hal::serial::nb::Write::write(&mut ctrl_link, byte)).unwrap();
+    }
+
+    let extension = Extension::init(ready.clone(), select.clone(), Link::new(ext_serial))
+        .expect("The Init Packet is already waiting");
+    assert!(ready.0.load(Ordering::SeqCst));
+
+    let mut buffer = [0; 256];
+    let ack = Packet::read_blocking(&mut ctrl_link, &mut buffer).expect("Extension acknowledged");
+    assert_eq!(&PacketData::Acknowledge, ack.data());
+
+    let fan = Cell::new(0);
+    let ext_packets = RefCell::new(Vec::new());
+    let ext_task = extension.run(
+        || {
+            [DataPoint {
+                name: "fan",
+                value: Value::Pwm { percent: fan.get() },
+            }]
+        },
+        |option| {
+            if let Value::Pwm { percent } = option.value {
+                fan.set(percent);
+            }
+            option.value
+        },
+        &OPTIONS,
+        |link| link.serial,
+        |direction, kind| ext_packets.borrow_mut().push((direction, kind)),
+    );
+
+    let ctrl_packets = thread::scope(|scope| {
+        let controller = scope.spawn(|| {
+            let _guard = RestartOnPanic(ctrl_link.serial.outgoing.clone());
+
+            let mut ctrl_packets = Vec::new();
+            let mut controller: Controller<1, _, _, _, 256, _> =
+                Controller::init_traced(select, ready.clone(), ctrl_link, |direction, kind| {
+                    ctrl_packets.push((direction, kind))
+                })
+                .expect("Extension responds to the Probe");
+
+            // Discover
+            assert_eq!([Some(ExtensionId(13))], controller.discover());
+
+            // Configure
+            let mut frame = [0; 256];
+            let applied = controller
+                .configure(
+                    SlotIndex(0),
+                    DataPoint {
+                        name: "fan",
+                        value: Value::Pwm { percent: 40 },
+                    },
+                    &mut frame,
+                )
+                .expect("Extension acknowledges the Configuration");
+            assert_eq!(
+                DataPoint {
+                    name: "fan",
+                    value: Value::Pwm { percent: 40 },
+                },
+                applied
+            );
+
+            // Metrics
+            let mut poll = controller.metrics_poll(SlotIndex(0)).unwrap();
+            while controller.step_metrics(&mut poll).is_pending() {}
+            let metrics: Vec<_> = match controller.step_metrics(&mut poll) {
+                Poll::Ready(Ok(metrics)) => metrics.collect(),
+                other => panic!("Expected Metrics, got {:?}", other),
+            };
+            assert_eq!(
+                vec![DataPoint {
+                    name: "fan",
+                    value: Value::Pwm { percent: 40 },
+                }],
+                metrics
+            );
+
+            // Restart
+            controller.restart(SlotIndex(0)).unwrap();
+            assert_eq!([None], controller.discover());
+            drop(controller);
+
+            ctrl_packets
+        });
+
+        // The Extension stops, once it received the Restart
+        block_on(ext_task);
+
+        controller
+            .join()
+            .expect("The Controller ran without any Errors")
+    });
+
+    assert_eq!(40, fan.get());
+    assert!(!ready.0.load(Ordering::SeqCst));
+
+    use PacketDirection::{Receive, Send};
+    assert_eq!(
+        vec![
+            (Send, PacketKind::InitProbe),
+            (Receive, PacketKind::InitProbeResponse),
+            (Send, PacketKind::InitProbe),
+            (Receive, PacketKind::InitProbeResponse),
+            (Send, PacketKind::Configure),
+            (Receive, PacketKind::AckConfigured),
+            (Send, PacketKind::Metrics),
+            (Receive, PacketKind::MetricsResponse),
+            (Send, PacketKind::Restart),
+        ],
+        ctrl_packets
+    );
+    assert_eq!(
+        vec![
+            (Receive, PacketKind::InitProbe),
+            (Send, PacketKind::InitProbeResponse),
+            (Receive, PacketKind::InitProbe),
+            (Send, PacketKind::InitProbeResponse),
+            (Receive, PacketKind::Configure),
+            (Send, PacketKind::AckConfigured),
+            (Receive, PacketKind::Metrics),
+            (Send, PacketKind::MetricsResponse),
+            (Receive, PacketKind::Restart),
+        ],
+        *ext_packets.borrow()
+    );
+}
@@ -1,11 +1,17 @@
 use core::{alloc::Allocator, ptr::NonNull};
 
-use crate::atomic::{self, AtomicPtr};
+use crate::atomic::{self, AtomicPtr, AtomicUsize};
+
+/// The Byte freed Blocks are filled with, when the Allocator was created using
+/// `LinkedListAllocator::new_poisoned`
+pub const POISON: u8 = 0xDD;
 
 pub struct LinkedListAllocator<const N: usize> {
     head: AtomicPtr<u8>,
     start: *mut u8,
     end: *mut u8,
+    /// Whether freed Blocks should be filled with [`POISON`]
+    poison: bool,
 }
 
 unsafe impl<const N: usize> Sync for LinkedListAllocator<N> {}
@@ -36,6 +42,21 @@ macro_rules! alloc_impl {
                     head: AtomicPtr::new(start as *mut u8),
                     start,
                     end,
+                    poison: false,
+                }
+            }
+
+            /// Creates the Allocator like `new`, but every freed Block is filled with [`POISON`],
+            /// so that reading from a Block after it was freed obviously returns garbage. This is
+            /// meant as a debugging aid for tracking down use-after-free bugs.
+            ///
+            /// The first pointer-sized word of a freed Block stores the link to the next free
+            /// Block, so it is not poisoned.
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub fn new_poisoned(start: *mut u8, end: *mut u8) -> Self {
+                Self {
+                    poison: true,
+                    ..Self::new(start, end)
                 }
             }
         }
@@ -86,6 +107,19 @@ macro_rules! alloc_impl {
                 if layout.size() <= $size {
                     let ptr_block = ptr.as_ptr() as *mut *mut u8;
 
+                    // The Block needs to be poisoned before it is put back into the List, as it
+                    // can be allocated again right after that
+                    if self.poison {
+                        let link_size = core::mem::size_of::<*mut u8>();
+                        unsafe {
+                            core::ptr::write_bytes(
+                                ptr.as_ptr().add(link_size),
+                                POISON,
+                                $size - link_size,
+                            );
+                        }
+                    }
+
                     loop {
                         let current_head = self.head.load(atomic::Ordering::SeqCst);
 
@@ -120,6 +154,57 @@ alloc_impl!(256);
 alloc_impl!(512);
 alloc_impl!(1024);
 
+/// Wraps another Allocator and keeps track of the number of allocations, that have not been
+/// deallocated yet. This is mostly useful in tests to detect leaks.
+pub struct CountingAlloc<'a, A> {
+    inner: &'a A,
+    outstanding: AtomicUsize,
+    allocations: AtomicUsize,
+}
+
+impl<'a, A> CountingAlloc<'a, A> {
+    pub fn new(inner: &'a A) -> Self {
+        Self {
+            inner,
+            outstanding: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of allocations, that have not been deallocated yet
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.load(atomic::Ordering::SeqCst)
+    }
+
+    /// The total number of allocations so far, including the ones that were already
+    /// deallocated, which shows how much Allocation-Churn something causes
+    pub fn allocations(&self) -> usize {
+        self.allocations.load(atomic::Ordering::SeqCst)
+    }
+}
+
+unsafe impl<'a, A> Allocator for CountingAlloc<'a, A>
+where
+    A: Allocator,
+{
+    fn allocate(
+        &self,
+        layout: core::alloc::Layout,
+    ) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        let ptr = self.inner.allocate(layout)?;
+        self.outstanding.fetch_add(1, atomic::Ordering::SeqCst);
+        self.allocations.fetch_add(1, atomic::Ordering::SeqCst);
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: core::alloc::Layout) {
+        let previous = self.outstanding.fetch_sub(1, atomic::Ordering::SeqCst);
+        debug_assert!(previous > 0, "Deallocated more than was allocated");
+
+        unsafe { self.inner.deallocate(ptr, layout) }
+    }
+}
+
 #[cfg(all(test, not(loom)))]
 mod tests {
     use super::*;
@@ -183,6 +268,65 @@ mod tests {
         drop(boxed1);
     }
 
+    #[test]
+    fn poisoned_dealloc() {
+        let mut buffer: Vec<u8> = vec![0; 1024];
+        let ptr = buffer.as_mut_ptr_range();
+
+        let allocator = LinkedListAllocator::<256>::new_poisoned(ptr.start, ptr.end);
+        let layout = core::alloc::Layout::new::<[u8; 200]>();
+
+        let first = allocator.allocate(layout).unwrap();
+        unsafe { core::ptr::write_bytes(first.as_ptr() as *mut u8, 0xAB, 200) };
+        unsafe { allocator.deallocate(first.cast(), layout) };
+
+        // The last freed Block is the first one to be allocated again
+        let second = allocator.allocate(layout).unwrap();
+        assert_eq!(first.cast::<u8>(), second.cast::<u8>());
+
+        let block = unsafe { second.as_ref() };
+        let link_size = core::mem::size_of::<*mut u8>();
+        assert_eq!(256, block.len());
+        assert!(block[link_size..].iter().all(|b| *b == POISON));
+
+        unsafe { allocator.deallocate(second.cast(), layout) };
+    }
+
+    #[test]
+    fn unpoisoned_dealloc() {
+        let mut buffer: Vec<u8> = vec![0; 1024];
+        let ptr = buffer.as_mut_ptr_range();
+
+        let allocator = LinkedListAllocator::<256>::new(ptr.start, ptr.end);
+        let layout = core::alloc::Layout::new::<[u8; 200]>();
+
+        let first = allocator.allocate(layout).unwrap();
+        unsafe { core::ptr::write_bytes(first.as_ptr() as *mut u8, 0xAB, 200) };
+        unsafe { allocator.deallocate(first.cast(), layout) };
+
+        let second = allocator.allocate(layout).unwrap();
+        let block = unsafe { second.as_ref() };
+        let link_size = core::mem::size_of::<*mut u8>();
+        assert!(block[link_size..200].iter().all(|b| *b == 0xAB));
+
+        unsafe { allocator.deallocate(second.cast(), layout) };
+    }
+
+    #[test]
+    fn counting_alloc_box() {
+        let allocator = CountingAlloc::new(&std::alloc::System);
+
+        let boxed1 = Box::new_in(13, &allocator);
+        let boxed2 = Box::new_in(14, &allocator);
+        assert_eq!(2, allocator.outstanding());
+
+        drop(boxed1);
+        assert_eq!(1, allocator.outstanding());
+
+        drop(boxed2);
+        assert_eq!(0, allocator.outstanding());
+    }
+
     #[test]
     #[ignore = "The Allocator is currently WIP and not a priority"]
     fn big_allocation_double() {
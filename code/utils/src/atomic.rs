@@ -3,3 +3,16 @@ pub(crate) use core::sync::atomic::*;
 
 #[cfg(loom)]
 pub(crate) use loom::sync::atomic::*;
+
+/// Signals that we are busy-waiting on another thread, under loom this yields to the other
+/// threads instead, as otherwise the model would never make any progress
+#[cfg(not(loom))]
+pub(crate) fn spin_loop() {
+    core::hint::spin_loop();
+}
+/// Signals that we are busy-waiting on another thread, under loom this yields to the other
+/// threads instead, as otherwise the model would never make any progress
+#[cfg(loom)]
+pub(crate) fn spin_loop() {
+    loom::thread::yield_now();
+}
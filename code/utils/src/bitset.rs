@@ -0,0 +1,206 @@
+//! A fixed size Bitset, that can be shared and modified without any locks.
+//!
+//! This is a building block for tracking which entries of some static Storage are used, like
+//! the Wakers in an executor or the Blocks of an allocator.
+
+use crate::atomic::{self, AtomicUsize};
+
+const WORD_BITS: usize = usize::BITS as usize;
+
+/// The number of Words needed to store the given number of Bits
+pub const fn words(bits: usize) -> usize {
+    bits.div_ceil(WORD_BITS)
+}
+
+/// A Bitset of `BITS` bits, where every operation is performed atomically.
+///
+/// # Consistency
+/// Every single operation is atomic, but the `find_*` operations look at one Word after another,
+/// so while other operations are running concurrently, the result might already be outdated when
+/// it is returned.
+///
+/// # Example
+/// ```rust
+/// # #![feature(generic_const_exprs)]
+/// # use utils::bitset::AtomicBitset;
+/// static USED: AtomicBitset<100> = AtomicBitset::new();
+///
+/// assert_eq!(Some(0), USED.find_first_clear());
+/// USED.set(0);
+/// assert_eq!(Some(1), USED.find_first_clear());
+/// ```
+pub struct AtomicBitset<const BITS: usize>
+where
+    [(); words(BITS)]:,
+{
+    words: [AtomicUsize; words(BITS)],
+}
+
+impl<const BITS: usize> AtomicBitset<BITS>
+where
+    [(); words(BITS)]:,
+{
+    /// Creates a new Bitset with all bits cleared
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const WORD: AtomicUsize = AtomicUsize::new(0);
+
+        Self {
+            words: [WORD; words(BITS)],
+        }
+    }
+    /// Creates a new Bitset with all bits cleared
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        Self {
+            words: core::array::from_fn(|_| AtomicUsize::new(0)),
+        }
+    }
+
+    /// The number of bits in the Bitset
+    pub const fn len(&self) -> usize {
+        BITS
+    }
+
+    /// Whether or not the Bitset has no bits at all
+    pub const fn is_empty(&self) -> bool {
+        BITS == 0
+    }
+
+    fn position(bit: usize) -> (usize, usize) {
+        assert!(bit < BITS, "Bit {} is out of range", bit);
+
+        (bit / WORD_BITS, 1 << (bit % WORD_BITS))
+    }
+
+    /// Sets the given bit and returns whether it was already set before
+    pub fn set(&self, bit: usize) -> bool {
+        let (word, mask) = Self::position(bit);
+        self.words[word].fetch_or(mask, atomic::Ordering::SeqCst) & mask != 0
+    }
+
+    /// Clears the given bit and returns whether it was set before
+    pub fn clear(&self, bit: usize) -> bool {
+        let (word, mask) = Self::position(bit);
+        self.words[word].fetch_and(!mask, atomic::Ordering::SeqCst) & mask != 0
+    }
+
+    /// Checks if the given bit is set
+    pub fn test(&self, bit: usize) -> bool {
+        let (word, mask) = Self::position(bit);
+        self.words[word].load(atomic::Ordering::SeqCst) & mask != 0
+    }
+
+    /// Finds the lowest bit that is set
+    pub fn find_first_set(&self) -> Option<usize> {
+        self.find_first(|word| word)
+    }
+
+    /// Finds the lowest bit that is cleared
+    pub fn find_first_clear(&self) -> Option<usize> {
+        self.find_first(|word| !word)
+    }
+
+    /// Finds the lowest bit, that is set after transforming every Word using `map`
+    fn find_first<F>(&self, map: F) -> Option<usize>
+    where
+        F: Fn(usize) -> usize,
+    {
+        self.words.iter().enumerate().find_map(|(index, word)| {
+            let bits = map(word.load(atomic::Ordering::SeqCst));
+            if bits == 0 {
+                return None;
+            }
+
+            let bit = index * WORD_BITS + bits.trailing_zeros() as usize;
+            // The last Word might contain bits past the end of the Bitset
+            (bit < BITS).then_some(bit)
+        })
+    }
+}
+
+impl<const BITS: usize> Default for AtomicBitset<BITS>
+where
+    [(); words(BITS)]:,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_count() {
+        assert_eq!(0, words(0));
+        assert_eq!(1, words(1));
+        assert_eq!(1, words(WORD_BITS));
+        assert_eq!(2, words(WORD_BITS + 1));
+    }
+
+    #[test]
+    fn set_clear_across_words() {
+        let bitset = AtomicBitset::<100>::new();
+
+        assert!(!bitset.set(3));
+        assert!(!bitset.set(70));
+        assert!(bitset.set(70));
+
+        assert!(bitset.test(3));
+        assert!(bitset.test(70));
+        assert!(!bitset.test(69));
+        assert!(!bitset.test(71));
+
+        assert!(bitset.clear(70));
+        assert!(!bitset.clear(70));
+        assert!(!bitset.test(70));
+        assert!(bitset.test(3));
+    }
+
+    #[test]
+    fn find_first_set_across_words() {
+        let bitset = AtomicBitset::<100>::new();
+        assert_eq!(None, bitset.find_first_set());
+
+        bitset.set(99);
+        assert_eq!(Some(99), bitset.find_first_set());
+
+        bitset.set(64);
+        assert_eq!(Some(64), bitset.find_first_set());
+
+        bitset.set(5);
+        assert_eq!(Some(5), bitset.find_first_set());
+    }
+
+    #[test]
+    fn find_first_clear_across_words() {
+        let bitset = AtomicBitset::<100>::new();
+
+        for bit in 0..64 {
+            bitset.set(bit);
+        }
+        assert_eq!(Some(64), bitset.find_first_clear());
+
+        for bit in 64..99 {
+            bitset.set(bit);
+        }
+        assert_eq!(Some(99), bitset.find_first_clear());
+
+        // The bits past the end of the last Word are never reported
+        bitset.set(99);
+        assert_eq!(None, bitset.find_first_clear());
+
+        bitset.clear(10);
+        assert_eq!(Some(10), bitset.find_first_clear());
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        let bitset = AtomicBitset::<100>::new();
+        bitset.set(100);
+    }
+}
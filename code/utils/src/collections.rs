@@ -0,0 +1,231 @@
+//! Collections with a fixed Capacity, that don't perform any dynamic memory allocation.
+//!
+//! Unlike the [`queue`](crate::queue), these are not meant to be shared between threads or
+//! interrupt-handlers and are simply owned by a single Task, like the Logger reordering its
+//! Messages.
+
+use core::mem::MaybeUninit;
+
+/// A double-ended Queue, that stores up to `N` Items in a fixed Ring-Buffer.
+///
+/// # Example
+/// ```rust
+/// # use utils::collections::Deque;
+/// let mut deque: Deque<u8, 2> = Deque::new();
+///
+/// deque.push_back(1).unwrap();
+/// deque.push_front(0).unwrap();
+/// // The Deque is full, so the Item is handed back
+/// assert_eq!(Err(2), deque.push_back(2));
+///
+/// assert_eq!(Some(0), deque.pop_front());
+/// assert_eq!(Some(1), deque.pop_back());
+/// assert_eq!(None, deque.pop_front());
+/// ```
+pub struct Deque<T, const N: usize> {
+    buffer: [MaybeUninit<T>; N],
+    /// The Index of the first Item in the Buffer
+    head: usize,
+    /// The number of initialized Items, starting at `head` and wrapping around the end
+    len: usize,
+}
+
+impl<T, const N: usize> Deque<T, N> {
+    /// Creates a new empty Deque
+    pub const fn new() -> Self {
+        Self {
+            buffer: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// The number of Items currently stored in the Deque
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether or not the Deque contains no Items
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether or not the Deque can't hold any more Items
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// The maximum number of Items the Deque can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The Index in the Buffer for the Item at the given Offset from the head
+    fn index(&self, offset: usize) -> usize {
+        (self.head + offset) % N
+    }
+
+    /// Appends the Item at the back of the Deque, returning the Item again if the Deque is full
+    pub fn push_back(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+
+        let index = self.index(self.len);
+        self.buffer[index].write(item);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Prepends the Item at the front of the Deque, returning the Item again if the Deque is full
+    pub fn push_front(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+
+        self.head = self.index(N - 1);
+        self.buffer[self.head].write(item);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes the first Item of the Deque
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let index = self.head;
+        self.head = self.index(1);
+        self.len -= 1;
+
+        // Safety: The Item at the old head was initialized and is no longer part of the Deque
+        Some(unsafe { self.buffer[index].assume_init_read() })
+    }
+
+    /// Removes the last Item of the Deque
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.len -= 1;
+        let index = self.index(self.len);
+
+        // Safety: The Item at the old end was initialized and is no longer part of the Deque
+        Some(unsafe { self.buffer[index].assume_init_read() })
+    }
+}
+
+impl<T, const N: usize> Default for Deque<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for Deque<T, N> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_edges() {
+        let mut deque: Deque<u8, 3> = Deque::new();
+        assert!(deque.is_empty());
+        assert_eq!(0, deque.len());
+        assert_eq!(None, deque.pop_front());
+        assert_eq!(None, deque.pop_back());
+
+        deque.push_back(1).unwrap();
+        assert_eq!(Some(1), deque.pop_back());
+        assert_eq!(None, deque.pop_front());
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn full_edges() {
+        let mut deque: Deque<u8, 3> = Deque::new();
+        deque.push_back(1).unwrap();
+        deque.push_front(0).unwrap();
+        deque.push_back(2).unwrap();
+
+        assert!(deque.is_full());
+        assert_eq!(3, deque.len());
+        assert_eq!(Err(3), deque.push_back(3));
+        assert_eq!(Err(4), deque.push_front(4));
+
+        assert_eq!(Some(2), deque.pop_back());
+        deque.push_front(5).unwrap();
+        assert_eq!(Some(5), deque.pop_front());
+        assert_eq!(Some(0), deque.pop_front());
+        assert_eq!(Some(1), deque.pop_front());
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut deque: Deque<u8, 0> = Deque::new();
+        assert!(deque.is_full());
+        assert_eq!(Err(1), deque.push_back(1));
+        assert_eq!(Err(2), deque.push_front(2));
+        assert_eq!(None, deque.pop_front());
+    }
+
+    #[test]
+    fn wraparound_back() {
+        let mut deque: Deque<usize, 4> = Deque::new();
+
+        // Moves through the Buffer a few times, always keeping two Items in it
+        deque.push_back(0).unwrap();
+        for item in 1..10 {
+            deque.push_back(item).unwrap();
+            assert_eq!(Some(item - 1), deque.pop_front());
+        }
+
+        assert_eq!(1, deque.len());
+        assert_eq!(Some(9), deque.pop_front());
+    }
+
+    #[test]
+    fn wraparound_front() {
+        let mut deque: Deque<usize, 4> = Deque::new();
+
+        // The first push to the front already wraps around to the end of the Buffer
+        deque.push_front(0).unwrap();
+        for item in 1..10 {
+            deque.push_front(item).unwrap();
+            assert_eq!(Some(item - 1), deque.pop_back());
+        }
+
+        deque.push_front(10).unwrap();
+        deque.push_back(11).unwrap();
+        deque.push_front(12).unwrap();
+        assert!(deque.is_full());
+
+        assert_eq!(Some(12), deque.pop_front());
+        assert_eq!(Some(10), deque.pop_front());
+        assert_eq!(Some(9), deque.pop_front());
+        assert_eq!(Some(11), deque.pop_front());
+    }
+
+    #[test]
+    fn drop_remaining_items() {
+        use std::rc::Rc;
+
+        let item = Rc::new(());
+        {
+            let mut deque: Deque<Rc<()>, 3> = Deque::new();
+            deque.push_back(item.clone()).unwrap();
+            deque.push_front(item.clone()).unwrap();
+            assert_eq!(3, Rc::strong_count(&item));
+        }
+
+        assert_eq!(1, Rc::strong_count(&item));
+    }
+}
@@ -0,0 +1,49 @@
+//! Comparisons whose duration does not depend on the compared Data, for security sensitive
+//! values like Tokens, where an early return would leak how many Bytes were guessed correctly.
+
+/// Compares the two Slices in constant time, meaning that every Byte is compared, even after the
+/// first mismatch.
+///
+/// Only the Content is protected, Slices with different lengths are rejected right away, so the
+/// length of a Token should not be secret.
+///
+/// # Example
+/// ```rust
+/// # use utils::ct_eq;
+/// assert!(ct_eq(b"token", b"token"));
+/// assert!(!ct_eq(b"token", b"tokem"));
+/// ```
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+
+    // Keeps the compiler from turning the fold into a comparison, that returns early
+    core::hint::black_box(diff) == 0
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal() {
+        assert!(ct_eq(&[], &[]));
+        assert!(ct_eq(&[1, 2, 3, 4], &[1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn unequal_same_length() {
+        assert!(!ct_eq(&[1, 2, 3, 4], &[0, 2, 3, 4]));
+        assert!(!ct_eq(&[1, 2, 3, 4], &[1, 2, 3, 5]));
+        assert!(!ct_eq(&[0xff; 16], &[0x00; 16]));
+    }
+
+    #[test]
+    fn different_length() {
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2, 3, 4]));
+        assert!(!ct_eq(&[1, 2, 3, 4], &[]));
+    }
+}
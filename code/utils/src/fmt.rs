@@ -0,0 +1,121 @@
+//! Formatting helpers, that work without `alloc` and can be used for debugging output over a
+//! serial connection
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Writes the given Data as lowercase hex ASCII into `dst` and returns the number of bytes
+/// written.
+///
+/// Every byte of `src` takes up two bytes in `dst`, if `dst` is too small only as many bytes of
+/// `src` are written as fit entirely, so the output never contains half a byte.
+///
+/// # Example
+/// ```rust
+/// # use utils::fmt::hex_into;
+/// let mut dst = [0; 8];
+/// let written = hex_into(&[0x12, 0xab], &mut dst);
+/// assert_eq!(b"12ab", &dst[..written]);
+/// ```
+pub fn hex_into(src: &[u8], dst: &mut [u8]) -> usize {
+    let mut written = 0;
+    for (byte, target) in src.iter().zip(dst.chunks_exact_mut(2)) {
+        target[0] = HEX_DIGITS[(byte >> 4) as usize];
+        target[1] = HEX_DIGITS[(byte & 0x0f) as usize];
+        written += 2;
+    }
+
+    written
+}
+
+/// Writes the given Value as decimal ASCII into `dst` and returns the number of bytes written.
+///
+/// If `dst` is too small to hold all the digits, nothing is written and None is returned, so the
+/// output never contains a truncated number.
+///
+/// # Example
+/// ```rust
+/// # use utils::fmt::write_u64_ascii;
+/// let mut dst = [0; 8];
+/// let written = write_u64_ascii(1234, &mut dst).unwrap();
+/// assert_eq!(b"1234", &dst[..written]);
+///
+/// assert_eq!(None, write_u64_ascii(123456789, &mut dst));
+/// ```
+pub fn write_u64_ascii(value: u64, dst: &mut [u8]) -> Option<usize> {
+    let mut digits = 1;
+    let mut rest = value / 10;
+    while rest != 0 {
+        digits += 1;
+        rest /= 10;
+    }
+
+    let target = dst.get_mut(..digits)?;
+
+    let mut rest = value;
+    for digit in target.iter_mut().rev() {
+        *digit = b'0' + (rest % 10) as u8;
+        rest /= 10;
+    }
+
+    Some(digits)
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_exact_fit() {
+        let mut dst = [0; 6];
+
+        assert_eq!(6, hex_into(&[0x00, 0x7f, 0xff], &mut dst));
+        assert_eq!(b"007fff", &dst);
+    }
+
+    #[test]
+    fn hex_too_small() {
+        let mut dst = [b'x'; 5];
+
+        assert_eq!(4, hex_into(&[0x01, 0x23, 0x45], &mut dst));
+        assert_eq!(b"0123x", &dst);
+    }
+
+    #[test]
+    fn hex_empty() {
+        let mut dst = [b'x'; 4];
+
+        assert_eq!(0, hex_into(&[], &mut dst));
+        assert_eq!(b"xxxx", &dst);
+    }
+
+    #[test]
+    fn u64_zero() {
+        let mut dst = [b'x'; 4];
+
+        assert_eq!(Some(1), write_u64_ascii(0, &mut dst));
+        assert_eq!(b"0xxx", &dst);
+    }
+
+    #[test]
+    fn u64_exact_fit() {
+        let mut dst = [b'x'; 5];
+
+        assert_eq!(Some(5), write_u64_ascii(40213, &mut dst));
+        assert_eq!(b"40213", &dst);
+
+        let mut dst = [b'x'; 20];
+
+        assert_eq!(Some(20), write_u64_ascii(u64::MAX, &mut dst));
+        assert_eq!(b"18446744073709551615", &dst);
+    }
+
+    #[test]
+    fn u64_too_small() {
+        let mut dst = [b'x'; 3];
+
+        assert_eq!(None, write_u64_ascii(1000, &mut dst));
+        assert_eq!(b"xxx", &dst);
+
+        assert_eq!(None, write_u64_ascii(0, &mut []));
+    }
+}
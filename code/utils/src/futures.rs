@@ -1,8 +1,19 @@
-use core::future::Future;
+use core::{future::Future, task::Waker};
+
+use crate::{
+    atomic::{self, AtomicU8},
+    UnsafeCell,
+};
 
 /// Allows for Yielding from the current async Task once, but still being marked as executable
 /// immediately. This is useful for making sure that an async Task yields at least once, when it
 /// hits this Future and allows other Futures to be run
+///
+/// # Idling
+/// As the Task is woken again right away, a Task that yields in a loop is ready in every pass of
+/// the Runtime, so the Runtime never has an idle pass in which the core could sleep. Tasks that
+/// poll something in a loop should instead use the `cooperative_sleep` of the Timer, which parks
+/// the Task until the next Tick.
 pub struct YieldNow {
     polled: bool,
 }
@@ -40,3 +51,357 @@ impl Future for YieldNow {
         }
     }
 }
+
+/// Like [`YieldNow`], but yields from the current async Task `n` times before completing. This
+/// allows for giving other Tasks a couple of turns without needing an actual Timer, which is
+/// useful during early boot before any Timer has been configured
+pub struct YieldN {
+    remaining: usize,
+}
+
+impl YieldN {
+    pub fn new(n: usize) -> Self {
+        YieldN { remaining: n }
+    }
+}
+
+pub fn yield_n(n: usize) -> YieldN {
+    YieldN::new(n)
+}
+
+impl Future for YieldN {
+    type Output = ();
+
+    fn poll(
+        mut self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Self::Output> {
+        if self.remaining == 0 {
+            core::task::Poll::Ready(())
+        } else {
+            self.remaining -= 1;
+            cx.waker().wake_by_ref();
+
+            core::task::Poll::Pending
+        }
+    }
+}
+
+/// The Sender has completed, either by sending a Value or by being dropped
+const COMPLETE: u8 = 0b0001;
+/// A Value was sent, only valid together with [`COMPLETE`]
+const VALUE: u8 = 0b0010;
+/// The Waker of the Receiver is currently being accessed
+const WAKER_LOCK: u8 = 0b0100;
+/// A Sender/Receiver pair was already created for the Shared State
+const USED: u8 = 0b1000;
+
+/// The Shared State of a [`oneshot`] Channel, which needs to be stored somewhere that outlives
+/// both the [`Sender`] and the [`Receiver`], like a `static`, as there is no allocation.
+///
+/// Every Shared State can only be used for a single hand-off.
+pub struct OneshotShared<T> {
+    state: AtomicU8,
+    value: UnsafeCell<Option<T>>,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// Safety:
+// The Value is only written by the Sender before it sets `COMPLETE` and only read by the Receiver
+// after it saw `COMPLETE`, and the Waker is only accessed while holding the `WAKER_LOCK`.
+unsafe impl<T> Sync for OneshotShared<T> where T: Send {}
+
+impl<T> OneshotShared<T> {
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(0),
+            value: UnsafeCell::new(None),
+            waker: UnsafeCell::new(None),
+        }
+    }
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        Self {
+            state: AtomicU8::new(0),
+            value: UnsafeCell::new(None),
+            waker: UnsafeCell::new(None),
+        }
+    }
+}
+
+impl<T> Default for OneshotShared<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The Error returned by the [`Receiver`], when the [`Sender`] was dropped without sending a
+/// Value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+/// Creates a Channel for handing a single Value from one Task to another, using the given Shared
+/// State.
+///
+/// # Panics
+/// If the Shared State was already used for another Channel
+pub fn oneshot<T>(shared: &OneshotShared<T>) -> (Sender<'_, T>, Receiver<'_, T>) {
+    let previous = shared.state.fetch_or(USED, atomic::Ordering::SeqCst);
+    assert!(
+        previous & USED == 0,
+        "The Shared State was already used for a Channel"
+    );
+
+    (Sender { shared }, Receiver { shared })
+}
+
+/// The sending half of a [`oneshot`] Channel
+pub struct Sender<'a, T> {
+    shared: &'a OneshotShared<T>,
+}
+
+impl<'a, T> Sender<'a, T> {
+    /// Sends the Value to the [`Receiver`] and wakes it
+    pub fn send(self, value: T) {
+        self.complete(Some(value));
+        core::mem::forget(self);
+    }
+
+    fn complete(&self, value: Option<T>) {
+        let mut flags = COMPLETE | WAKER_LOCK;
+        if let Some(value) = value {
+            self.shared.value.with_mut(|v| {
+                // Safety: The Receiver only accesses the Value after `COMPLETE` is set
+                let v_ref = unsafe { &mut *v };
+                *v_ref = Some(value);
+            });
+            flags |= VALUE;
+        }
+
+        let previous = self.shared.state.fetch_or(flags, atomic::Ordering::SeqCst);
+        if previous & WAKER_LOCK != 0 {
+            // The Receiver is currently registering its Waker and will notice that we completed,
+            // once it releases the lock
+            return;
+        }
+
+        // We now hold the lock and never release it, as the Receiver does not touch the Waker
+        // anymore once we completed
+        let waker = self.shared.waker.with_mut(|w| {
+            // Safety: We hold the `WAKER_LOCK`
+            let w_ref = unsafe { &mut *w };
+            w_ref.take()
+        });
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<'a, T> Drop for Sender<'a, T> {
+    fn drop(&mut self) {
+        self.complete(None);
+    }
+}
+
+/// The receiving half of a [`oneshot`] Channel, which resolves to the sent Value or to an Error,
+/// if the [`Sender`] was dropped without sending anything
+pub struct Receiver<'a, T> {
+    shared: &'a OneshotShared<T>,
+}
+
+impl<'a, T> Receiver<'a, T> {
+    fn take(&self, state: u8) -> Result<T, RecvError> {
+        if state & VALUE == 0 {
+            return Err(RecvError);
+        }
+
+        self.shared.value.with_mut(|v| {
+            // Safety: The Sender does not access the Value anymore once `COMPLETE` is set
+            let v_ref = unsafe { &mut *v };
+            v_ref.take().ok_or(RecvError)
+        })
+    }
+}
+
+impl<'a, T> Future for Receiver<'a, T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Self::Output> {
+        let mut state = self.shared.state.load(atomic::Ordering::SeqCst);
+        loop {
+            if state & COMPLETE != 0 {
+                return core::task::Poll::Ready(self.take(state));
+            }
+            if state & WAKER_LOCK != 0 {
+                // Only the Sender takes the lock, while it completes
+                atomic::spin_loop();
+                state = self.shared.state.load(atomic::Ordering::SeqCst);
+                continue;
+            }
+
+            match self.shared.state.compare_exchange(
+                state,
+                state | WAKER_LOCK,
+                atomic::Ordering::SeqCst,
+                atomic::Ordering::SeqCst,
+            ) {
+                Ok(_) => break,
+                Err(current) => state = current,
+            };
+        }
+
+        self.shared.waker.with_mut(|w| {
+            // Safety: We hold the `WAKER_LOCK`
+            let w_ref = unsafe { &mut *w };
+            *w_ref = Some(cx.waker().clone());
+        });
+
+        let previous = self
+            .shared
+            .state
+            .fetch_and(!WAKER_LOCK, atomic::Ordering::SeqCst);
+        if previous & COMPLETE != 0 {
+            // The Sender completed while we were holding the lock, so it could not wake us
+            return core::task::Poll::Ready(self.take(previous));
+        }
+
+        core::task::Poll::Pending
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yield_n_zero() {
+        let (waker, count) = futures_test::task::new_count_waker();
+        let mut ctx = core::task::Context::from_waker(&waker);
+
+        let mut fut = yield_n(0);
+        assert!(core::pin::Pin::new(&mut fut).poll(&mut ctx).is_ready());
+        assert_eq!(0, count.get());
+    }
+
+    #[test]
+    fn yield_n_multiple() {
+        let (waker, count) = futures_test::task::new_count_waker();
+        let mut ctx = core::task::Context::from_waker(&waker);
+
+        let mut fut = yield_n(3);
+        for i in 0..3 {
+            assert!(core::pin::Pin::new(&mut fut).poll(&mut ctx).is_pending());
+            assert_eq!(i + 1, count.get());
+        }
+
+        assert!(core::pin::Pin::new(&mut fut).poll(&mut ctx).is_ready());
+        assert_eq!(3, count.get());
+    }
+
+    #[test]
+    fn oneshot_send_while_pending() {
+        static SHARED: OneshotShared<u32> = OneshotShared::new();
+
+        let (sender, mut receiver) = oneshot(&SHARED);
+
+        let waker = futures_test::task::noop_waker();
+        let mut ctx = core::task::Context::from_waker(&waker);
+        assert!(core::pin::Pin::new(&mut receiver)
+            .poll(&mut ctx)
+            .is_pending());
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.spawn(async move {
+            yield_now().await;
+            sender.send(13);
+        });
+
+        assert_eq!(Ok(13), rt.block_on(receiver));
+    }
+
+    #[test]
+    fn oneshot_dropped_sender() {
+        static SHARED: OneshotShared<u32> = OneshotShared::new();
+
+        let (sender, receiver) = oneshot(&SHARED);
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.spawn(async move {
+            yield_now().await;
+            drop(sender);
+        });
+
+        assert_eq!(Err(RecvError), rt.block_on(receiver));
+    }
+
+    #[test]
+    #[should_panic]
+    fn oneshot_reuse_shared() {
+        let shared = OneshotShared::<u32>::new();
+
+        let _first = oneshot(&shared);
+        let _second = oneshot(&shared);
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::*;
+
+    /// Polls the Receiver while the other thread completes the Sender, making sure that the
+    /// Receiver is either already done or gets woken afterwards
+    fn receive_concurrent<F>(complete: F) -> Result<u32, RecvError>
+    where
+        F: FnOnce(Sender<'static, u32>) + Send + 'static,
+    {
+        // The Shared State needs to outlive the Sender, which is moved into another thread
+        let shared: &'static OneshotShared<u32> = Box::leak(Box::new(OneshotShared::new()));
+        let (sender, mut receiver) = oneshot(shared);
+
+        let completing = loom::thread::spawn(move || complete(sender));
+
+        let (waker, count) = futures_test::task::new_count_waker();
+        let mut ctx = core::task::Context::from_waker(&waker);
+
+        let first = core::pin::Pin::new(&mut receiver).poll(&mut ctx);
+        completing.join().unwrap();
+
+        match first {
+            core::task::Poll::Ready(result) => result,
+            core::task::Poll::Pending => {
+                assert_eq!(1, count.get());
+                match core::pin::Pin::new(&mut receiver).poll(&mut ctx) {
+                    core::task::Poll::Ready(result) => result,
+                    core::task::Poll::Pending => panic!("Woken, but still pending"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn oneshot_send_concurrent_poll() {
+        loom::model(|| {
+            assert_eq!(Ok(13), receive_concurrent(|sender| sender.send(13)));
+        });
+    }
+
+    #[test]
+    fn oneshot_drop_concurrent_poll() {
+        loom::model(|| {
+            assert_eq!(Err(RecvError), receive_concurrent(drop));
+        });
+    }
+}
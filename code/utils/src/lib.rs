@@ -2,6 +2,7 @@
 #![feature(allocator_api)]
 #![feature(ptr_sub_ptr)]
 #![feature(nonnull_slice_from_raw_parts)]
+#![feature(generic_const_exprs)]
 
 #[cfg(feature = "stm32l432")]
 mod stm32l432;
@@ -12,12 +13,31 @@ pub mod queue;
 
 pub mod allocator;
 
+pub mod bitset;
+
+pub mod collections;
+
+mod ct;
+pub use ct::ct_eq;
+
+pub mod fmt;
+
 pub mod futures;
 
+pub mod logging;
+
+pub mod pool;
+
+pub mod serial;
+
 pub mod timer;
 
+pub mod waker;
+
 pub(crate) mod atomic;
 
+pub(crate) mod state;
+
 #[cfg(not(loom))]
 mod unsafecell {
     pub(crate) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
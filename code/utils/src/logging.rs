@@ -0,0 +1,317 @@
+use core::future::Future;
+
+use general::AsyncSerial;
+
+use crate::{
+    atomic::{self, AtomicU32},
+    fmt::write_u64_ascii,
+    queue::unbounded::mpsc::{QueueRx, QueueTx},
+    timer::fixed_size::{LevelOneWheel, TimerWheel, Timescale},
+};
+
+/// The Interval in which the Backend flushes the Messages it collected to the Serial, so multiple
+/// Messages share a single Frame instead of each being written on their own
+pub const FLUSH_INTERVAL_MS: usize = 50;
+
+pub struct SerialLoggerFrontend<T> {
+    id: AtomicU32,
+    tx: T,
+}
+
+impl<T> tracing_core::Subscriber for SerialLoggerFrontend<T>
+where
+    T: QueueTx<Message> + 'static,
+{
+    fn enabled(&self, _metadata: &tracing_core::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn enter(&self, span: &tracing_core::span::Id) {
+        let _ = self.tx.try_enqueue(Message::Enter(span.clone()));
+    }
+
+    fn event(&self, _event: &tracing_core::Event<'_>) {
+        let _ = self.tx.try_enqueue(Message::Event);
+    }
+
+    fn exit(&self, span: &tracing_core::span::Id) {
+        let _ = self.tx.try_enqueue(Message::Exit(span.clone()));
+    }
+
+    fn new_span(&self, _span: &tracing_core::span::Attributes<'_>) -> tracing_core::span::Id {
+        let raw_id = self.id.fetch_add(1, atomic::Ordering::SeqCst);
+
+        let n_id = tracing_core::span::Id::from_u64(raw_id as u64);
+
+        let _ = self.tx.try_enqueue(Message::NewSpan(n_id.clone()));
+
+        n_id
+    }
+
+    fn record(&self, span: &tracing_core::span::Id, _values: &tracing_core::span::Record<'_>) {
+        let _ = self.tx.try_enqueue(Message::Record(span.clone()));
+    }
+
+    fn record_follows_from(
+        &self,
+        _span: &tracing_core::span::Id,
+        _follows: &tracing_core::span::Id,
+    ) {
+        // todo!()
+    }
+
+    fn try_close(&self, _id: tracing_core::span::Id) -> bool {
+        // todo!()
+        false
+    }
+
+    fn clone_span(&self, id: &tracing_core::span::Id) -> tracing_core::span::Id {
+        // todo!()
+        id.clone()
+    }
+}
+
+pub enum Message {
+    NewSpan(tracing_core::span::Id),
+    Enter(tracing_core::span::Id),
+    Exit(tracing_core::span::Id),
+    Record(tracing_core::span::Id),
+    Event,
+}
+
+impl Message {
+    /// The longest Line of a Message, which is the longest Label followed by the longest Span-ID
+    const MAX_LINE: usize = 32;
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::NewSpan(_) => "New-Span",
+            Self::Enter(_) => "Enter",
+            Self::Exit(_) => "Exit",
+            Self::Record(_) => "Record",
+            Self::Event => "Event",
+        }
+    }
+
+    fn span_id(&self) -> Option<&tracing_core::span::Id> {
+        match self {
+            Self::NewSpan(id) | Self::Enter(id) | Self::Exit(id) | Self::Record(id) => Some(id),
+            Self::Event => None,
+        }
+    }
+
+    /// Writes the Message as a single Line into the Buffer and returns the number of Bytes
+    /// written, or None if it does not fit, in which case nothing is written
+    fn write_line(&self, dst: &mut [u8]) -> Option<usize> {
+        let mut line = [0; Self::MAX_LINE];
+
+        let label = self.label().as_bytes();
+        line[..label.len()].copy_from_slice(label);
+        let mut len = label.len();
+
+        if let Some(id) = self.span_id() {
+            line[len] = b' ';
+            len += 1;
+            len += write_u64_ascii(id.into_u64(), &mut line[len..])?;
+        }
+
+        line[len] = b'\n';
+        len += 1;
+
+        dst.get_mut(..len)?.copy_from_slice(&line[..len]);
+        Some(len)
+    }
+}
+
+/// Collects the Lines of multiple Messages into a single Frame
+struct Batch {
+    buffer: [u8; 256],
+    len: usize,
+}
+
+impl Batch {
+    fn new() -> Self {
+        Self {
+            buffer: [0; 256],
+            len: 0,
+        }
+    }
+
+    /// Adds the Message to the Batch, which first flushes the Batch if the Message does not fit
+    /// into the remaining space of the Frame anymore
+    async fn push<S>(&mut self, msg: &Message, serial: &mut S)
+    where
+        S: AsyncSerial<256>,
+    {
+        if let Some(written) = msg.write_line(&mut self.buffer[self.len..]) {
+            self.len += written;
+            return;
+        }
+
+        self.flush(serial).await;
+        if let Some(written) = msg.write_line(&mut self.buffer) {
+            self.len = written;
+        }
+    }
+
+    /// Writes the collected Lines as a single Frame, if there are any
+    async fn flush<S>(&mut self, serial: &mut S)
+    where
+        S: AsyncSerial<256>,
+    {
+        if self.len == 0 {
+            return;
+        }
+
+        serial.write(self.buffer).await;
+
+        self.buffer = [0; 256];
+        self.len = 0;
+    }
+}
+
+/// Creates the Frontend, which is used as the Subscriber for tracing, and the Backend, which
+/// needs to be run as its own Task and writes the logged Messages to the Serial.
+///
+/// The Backend parks its Task until a Message arrives, then waits [`FLUSH_INTERVAL_MS`] for more
+/// Messages and writes all of them at once, so it never keeps the core from idling and only
+/// writes to the Serial once per Interval.
+pub fn logger<'t, S, R, T, SCALE>(
+    serial: S,
+    rx: R,
+    tx: T,
+    timer: &'t TimerWheel<LevelOneWheel, SCALE>,
+) -> (SerialLoggerFrontend<T>, impl Future<Output = ()> + 't)
+where
+    S: AsyncSerial<256> + 't,
+    R: QueueRx<Message> + 't,
+    T: QueueTx<Message> + 'static,
+    SCALE: Timescale,
+{
+    (
+        SerialLoggerFrontend {
+            id: AtomicU32::new(1),
+            tx,
+        },
+        run_backend(rx, serial, timer),
+    )
+}
+
+async fn run_backend<R, S, SCALE>(
+    mut rx: R,
+    mut serial: S,
+    timer: &TimerWheel<LevelOneWheel, SCALE>,
+) where
+    R: QueueRx<Message>,
+    S: AsyncSerial<256>,
+    SCALE: Timescale,
+{
+    let initial = "Starting Logging";
+    let mut buffer = [0; 256];
+    buffer[0..initial.len()].copy_from_slice(initial.as_bytes());
+    serial.write(buffer).await;
+
+    let mut batch = Batch::new();
+    loop {
+        let msg = rx.recv().await;
+        batch.push(&msg, &mut serial).await;
+
+        // If no Timer is available, we simply flush right away
+        let _ = timer.sleep_ms(FLUSH_INTERVAL_MS).await;
+
+        while let Ok(msg) = rx.try_dequeue() {
+            batch.push(&msg, &mut serial).await;
+        }
+        batch.flush(&mut serial).await;
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    use core::{future::Future, task::Context};
+
+    use general::mocks::loopback;
+
+    use crate::{queue::unbounded::mpsc::queue, timer::fixed_size::Scale10Ms};
+
+    #[test]
+    fn batch_flushes_when_full() {
+        let (mut serial, mut other) = loopback::<256>();
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let mut batch = Batch::new();
+        rt.block_on(async {
+            // Every Line takes 6 Bytes, so 42 of them fit into a single Frame
+            for _ in 0..43 {
+                batch.push(&Message::Event, &mut serial).await;
+            }
+        });
+        assert_eq!(6, batch.len);
+
+        let frame = rt.block_on(other.read());
+        assert_eq!(b"Event\n", &frame[246..252]);
+        assert_eq!([0; 4], frame[252..]);
+    }
+
+    #[test]
+    fn write_line_span_id() {
+        let mut dst = [b'x'; 32];
+
+        let msg = Message::NewSpan(tracing_core::span::Id::from_u64(u64::MAX));
+        assert_eq!(Some(30), msg.write_line(&mut dst));
+        assert_eq!(b"New-Span 18446744073709551615\n", &dst[..30]);
+
+        // Too small for the whole Span-ID
+        let mut dst = [b'x'; 12];
+        assert_eq!(None, msg.write_line(&mut dst));
+        assert_eq!([b'x'; 12], dst);
+    }
+
+    #[test]
+    fn backend_parks_until_enqueue() {
+        static TIMER: TimerWheel<LevelOneWheel, Scale10Ms> =
+            TimerWheel::<LevelOneWheel, Scale10Ms>::new();
+
+        let (tx, rx) = queue(&std::alloc::System);
+        let (serial, mut other) = loopback::<256>();
+
+        let mut backend = core::pin::pin!(run_backend(rx, serial, &TIMER));
+
+        let (waker, count) = futures_test::task::new_count_waker();
+        let mut ctx = Context::from_waker(&waker);
+
+        // Nothing was logged, so the Backend parks instead of waking itself
+        assert!(backend.as_mut().poll(&mut ctx).is_pending());
+        assert!(backend.as_mut().poll(&mut ctx).is_pending());
+        assert_eq!(0, count.get());
+
+        tx.try_enqueue(Message::Event);
+        assert_eq!(1, count.get());
+
+        // The Backend now waits for the next Flush and collects everything logged until then
+        assert!(backend.as_mut().poll(&mut ctx).is_pending());
+        tx.try_enqueue(Message::Enter(tracing_core::span::Id::from_u64(1)));
+
+        TIMER.tick_n(5);
+        assert_eq!(2, count.get());
+        assert!(backend.as_mut().poll(&mut ctx).is_pending());
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let initial = rt.block_on(other.read());
+        assert!(initial.starts_with(b"Starting Logging"));
+
+        let frame = rt.block_on(other.read());
+        assert!(frame.starts_with(b"Event\nEnter 1\n"));
+        assert_eq!([0; 256 - 14], frame[14..]);
+    }
+}
@@ -0,0 +1,158 @@
+//! A statically allocated Pool of Items, which can be handed out exactly once each.
+//!
+//! This is mostly useful for Buffers that need to live for `'static`, like the DMA-Frames for
+//! serial communication, as it allows you to place all of them in a single static variable and
+//! then take them out as you need them, instead of needing a seperate static for each of them.
+
+use crate::{
+    atomic::{self, AtomicUsize},
+    UnsafeCell,
+};
+
+/// A Pool of `K` Items, that are stored inline and can each be taken out exactly once.
+///
+/// # Capacity
+/// The taken Items are tracked using a single atomic bitmap, so `K` can be at most the number of
+/// bits in a `usize`.
+///
+/// # Example
+/// ```rust
+/// # use utils::pool::StaticPool;
+/// static POOL: StaticPool<[u8; 16], 2> = StaticPool::new([[0; 16]; 2]);
+///
+/// let first: &'static mut [u8; 16] = POOL.take().unwrap();
+/// let second: &'static mut [u8; 16] = POOL.take().unwrap();
+/// assert!(POOL.take().is_none());
+/// ```
+pub struct StaticPool<T, const K: usize> {
+    items: UnsafeCell<[T; K]>,
+    taken: AtomicUsize,
+}
+
+unsafe impl<T, const K: usize> Sync for StaticPool<T, K> where T: Send {}
+
+impl<T, const K: usize> StaticPool<T, K> {
+    /// Creates a new Pool containing the given Items
+    #[cfg(not(loom))]
+    pub const fn new(items: [T; K]) -> Self {
+        assert!(
+            K <= usize::BITS as usize,
+            "A StaticPool can hold at most usize::BITS Items"
+        );
+
+        Self {
+            items: UnsafeCell::new(items),
+            taken: AtomicUsize::new(0),
+        }
+    }
+    /// Creates a new Pool containing the given Items
+    #[cfg(loom)]
+    pub fn new(items: [T; K]) -> Self {
+        assert!(
+            K <= usize::BITS as usize,
+            "A StaticPool can hold at most usize::BITS Items"
+        );
+
+        Self {
+            items: UnsafeCell::new(items),
+            taken: AtomicUsize::new(0),
+        }
+    }
+
+    /// The total number of Items in the Pool, including the already taken ones
+    pub const fn capacity(&self) -> usize {
+        K
+    }
+
+    /// The number of Items that can still be taken from the Pool
+    pub fn available(&self) -> usize {
+        K - self.taken.load(atomic::Ordering::SeqCst).count_ones() as usize
+    }
+
+    /// Attempts to take a not yet taken Item out of the Pool, returns None if all the Items have
+    /// already been taken
+    #[allow(clippy::mut_from_ref)]
+    pub fn take(&'static self) -> Option<&'static mut T> {
+        loop {
+            let taken = self.taken.load(atomic::Ordering::SeqCst);
+
+            let index = (!taken).trailing_zeros() as usize;
+            if index >= K {
+                return None;
+            }
+
+            if self
+                .taken
+                .compare_exchange(
+                    taken,
+                    taken | (1 << index),
+                    atomic::Ordering::SeqCst,
+                    atomic::Ordering::SeqCst,
+                )
+                .is_err()
+            {
+                continue;
+            }
+
+            // Safety
+            // We just set the bit for this index, so nobody else can get a reference to it
+            return Some(
+                self.items
+                    .with_mut(|items| unsafe { &mut *core::ptr::addr_of_mut!((*items)[index]) }),
+            );
+        }
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_all() {
+        static POOL: StaticPool<u32, 3> = StaticPool::new([0, 1, 2]);
+
+        assert_eq!(3, POOL.available());
+
+        let first = POOL.take().unwrap();
+        let second = POOL.take().unwrap();
+        let third = POOL.take().unwrap();
+
+        assert_eq!((0, 1, 2), (*first, *second, *third));
+        assert_eq!(0, POOL.available());
+    }
+
+    #[test]
+    fn take_exhausted() {
+        static POOL: StaticPool<u32, 1> = StaticPool::new([0]);
+
+        assert!(POOL.take().is_some());
+        assert!(POOL.take().is_none());
+        assert!(POOL.take().is_none());
+    }
+
+    #[test]
+    fn take_distinct() {
+        static POOL: StaticPool<u32, 2> = StaticPool::new([0, 0]);
+
+        let first = POOL.take().unwrap();
+        let second = POOL.take().unwrap();
+
+        *first = 13;
+        *second = 42;
+
+        assert_eq!(13, *first);
+        assert_eq!(42, *second);
+    }
+
+    #[test]
+    fn full_bitmap() {
+        static POOL: StaticPool<u8, { usize::BITS as usize }> =
+            StaticPool::new([0; usize::BITS as usize]);
+
+        for _ in 0..usize::BITS {
+            assert!(POOL.take().is_some());
+        }
+        assert!(POOL.take().is_none());
+    }
+}
@@ -10,11 +10,24 @@ pub mod unbounded {
         //!
         //! # Memory Usage
         //! This can easily leak memory, if the Receiver is dropped before all the Senders are dropped.
-
-        use core::{alloc::Layout, ptr::NonNull};
+        //!
+        //! # Reference Counting
+        //! Every Sender holds exactly one reference on the Buffer it currently uses as its tail, so
+        //! the `ref_count` of a Buffer is the number of Senders currently pointing at it. A Buffer
+        //! is only freed by the Receiver, once it has been fully consumed and its `ref_count` is
+        //! zero, meaning that no Sender can still be writing to it.
+
+        use core::{
+            alloc::Layout,
+            marker::PhantomData,
+            ptr::NonNull,
+            task::{Context, Poll},
+        };
 
         use crate::{
-            atomic::{self, AtomicIsize, AtomicPtr, AtomicU8, AtomicUsize},
+            atomic::{self, AtomicIsize, AtomicPtr, AtomicUsize},
+            state::{AtomicSlotState, SlotState},
+            waker::AtomicWakerCell,
             UnsafeCell,
         };
 
@@ -23,6 +36,43 @@ pub mod unbounded {
         pub trait QueueRx<T> {
             type ReceiveError;
             fn try_dequeue(&mut self) -> Result<T, Self::ReceiveError>;
+
+            /// Attempts to dequeue an Item and otherwise registers the Waker of the Context,
+            /// which is woken once the next Item was enqueued
+            fn poll_dequeue(&mut self, cx: &mut Context<'_>) -> Poll<T>;
+
+            /// Returns a Future, that resolves to the next Item in the Queue and parks the
+            /// current Task until an Item is available, instead of polling
+            /// [`try_dequeue`](QueueRx::try_dequeue) in a loop
+            fn recv(&mut self) -> Recv<'_, Self, T>
+            where
+                Self: Sized,
+            {
+                Recv {
+                    rx: self,
+                    _marker: PhantomData,
+                }
+            }
+        }
+
+        /// The Future returned by [`QueueRx::recv`]
+        pub struct Recv<'r, R, T> {
+            rx: &'r mut R,
+            _marker: PhantomData<fn() -> T>,
+        }
+
+        impl<'r, R, T> core::future::Future for Recv<'r, R, T>
+        where
+            R: QueueRx<T>,
+        {
+            type Output = T;
+
+            fn poll(
+                mut self: core::pin::Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Self::Output> {
+                self.rx.poll_dequeue(cx)
+            }
         }
 
         pub trait QueueTx<T> {
@@ -32,13 +82,13 @@ pub mod unbounded {
 
         struct Entry<T> {
             data: UnsafeCell<Option<T>>,
-            state: AtomicU8,
+            state: AtomicSlotState,
         }
 
         impl<T> Default for Entry<T> {
             fn default() -> Self {
                 Self {
-                    state: AtomicU8::new(0),
+                    state: AtomicSlotState::new(SlotState::Free),
                     data: UnsafeCell::new(None),
                 }
             }
@@ -51,23 +101,74 @@ pub mod unbounded {
             next: AtomicPtr<Self>,
         }
 
-        pub struct Tx<'a, T, A>
+        /// The State shared between all the Senders and the Receiver of a Queue, which is freed
+        /// by whoever drops the last handle on it
+        struct Shared {
+            handles: AtomicUsize,
+            waker: AtomicWakerCell,
+        }
+
+        pub struct Tx<'a, T, A, const N: usize = 4>
         where
             A: Allocator,
         {
             allocator: &'a A,
-            tail: AtomicPtr<Buffer<T, 4>>,
+            shared: NonNull<Shared>,
+            tail: AtomicPtr<Buffer<T, N>>,
         }
-        pub struct Rx<'a, T, A>
+        pub struct Rx<'a, T, A, const N: usize = 4>
         where
             A: Allocator,
         {
             allocator: &'a A,
-            head: *const Buffer<T, 4>,
+            shared: NonNull<Shared>,
+            head: *const Buffer<T, N>,
             pos: usize,
         }
 
+        // Safety:
+        // The Buffers and the Shared State are only accessed through atomics, apart from the
+        // Items, which are handed over to the Receiver, and the Waker, which is stored in an
+        // `AtomicWakerCell`.
+        unsafe impl<'a, T, A, const N: usize> Send for Tx<'a, T, A, N>
+        where
+            T: Send,
+            A: Allocator + Sync,
+        {
+        }
+        unsafe impl<'a, T, A, const N: usize> Sync for Tx<'a, T, A, N>
+        where
+            T: Send,
+            A: Allocator + Sync,
+        {
+        }
+
+        /// Creates a new Queue, whose Buffers hold 4 Items each, see [`queue_sized`]
         pub fn queue<T, A>(allocator: &A) -> (Tx<'_, T, A>, Rx<'_, T, A>)
+        where
+            A: Allocator,
+        {
+            queue_sized::<T, 4, A>(allocator)
+        }
+
+        /// Creates a new Queue, whose Buffers hold `N` Items each.
+        ///
+        /// A new Buffer is allocated whenever the Items pile up faster than they are consumed and
+        /// the current Buffer is full, so a larger `N` trades more memory for fewer allocations,
+        /// like for bursty Logging.
+        ///
+        /// # Example
+        /// ```rust
+        /// # #![feature(allocator_api)]
+        /// # use utils::queue::unbounded::mpsc::queue_sized;
+        /// let (tx, mut rx) = queue_sized::<u8, 16, _>(&std::alloc::System);
+        ///
+        /// tx.try_enqueue(13);
+        /// assert_eq!(Ok(13), rx.try_dequeue());
+        /// ```
+        pub fn queue_sized<T, const N: usize, A>(
+            allocator: &A,
+        ) -> (Tx<'_, T, A, N>, Rx<'_, T, A, N>)
         where
             A: Allocator,
         {
@@ -76,13 +177,17 @@ pub mod unbounded {
                 .ref_count
                 .fetch_add(1, atomic::Ordering::SeqCst);
 
+            let shared = Shared::allocate(allocator);
+
             (
                 Tx {
                     allocator,
+                    shared,
                     tail: AtomicPtr::new(buffer),
                 },
                 Rx {
                     allocator,
+                    shared,
                     head: buffer,
                     pos: 0,
                 },
@@ -90,10 +195,15 @@ pub mod unbounded {
         }
 
         impl<T, const N: usize> Buffer<T, N> {
+            const NOT_EMPTY: () = assert!(N > 0, "A Buffer needs to hold at least a single Item");
+
             pub fn allocate<A>(allocator: &A) -> *mut Self
             where
                 A: Allocator,
             {
+                #[allow(clippy::let_unit_value)]
+                let _ = Self::NOT_EMPTY;
+
                 let buffer: NonNull<Buffer<T, N>> = NonNull::new(
                     allocator
                         .allocate(Layout::new::<Buffer<T, N>>())
@@ -116,6 +226,12 @@ pub mod unbounded {
                 buffer.as_ptr()
             }
 
+            /// Releases a single reference on the Buffer
+            fn release(&self) {
+                let previous = self.ref_count.fetch_sub(1, atomic::Ordering::SeqCst);
+                debug_assert!(previous > 0, "Buffer ref_count underflow");
+            }
+
             pub fn try_enqueue(&self, data: T) -> Result<(), T> {
                 let insert_pos = self.pos.fetch_add(1, atomic::Ordering::SeqCst);
                 if insert_pos >= N {
@@ -124,24 +240,69 @@ pub mod unbounded {
 
                 let entry = &self.entries[insert_pos];
 
-                entry.state.store(1, atomic::Ordering::SeqCst);
+                entry.state.set(SlotState::Reserving);
 
                 entry.data.with_mut(|data_ptr| unsafe {
                     core::ptr::write(data_ptr, Some(data));
                 });
 
-                entry.state.store(2, atomic::Ordering::SeqCst);
+                entry.state.set(SlotState::Ready);
 
                 Ok(())
             }
         }
 
+        impl Shared {
+            /// Allocates the Shared State with the initial handles of a single Sender and the
+            /// Receiver
+            fn allocate<A>(allocator: &A) -> NonNull<Self>
+            where
+                A: Allocator,
+            {
+                let shared: NonNull<Self> =
+                    allocator.allocate(Layout::new::<Self>()).unwrap().cast();
+                unsafe {
+                    core::ptr::write(
+                        shared.as_ptr(),
+                        Self {
+                            handles: AtomicUsize::new(2),
+                            waker: AtomicWakerCell::new(),
+                        },
+                    );
+                }
+
+                shared
+            }
+
+            /// Releases a single handle on the Shared State and frees it, if it was the last one
+            ///
+            /// # Safety
+            /// The caller needs to own a handle on the Shared State, which was allocated using the
+            /// given Allocator, and must not use the Shared State anymore afterwards
+            unsafe fn release<A>(shared: NonNull<Self>, allocator: &A)
+            where
+                A: Allocator,
+            {
+                let previous = unsafe { shared.as_ref() }
+                    .handles
+                    .fetch_sub(1, atomic::Ordering::SeqCst);
+                if previous != 1 {
+                    return;
+                }
+
+                unsafe {
+                    core::ptr::drop_in_place(shared.as_ptr());
+                    allocator.deallocate(shared.cast(), Layout::new::<Self>());
+                }
+            }
+        }
+
         #[derive(Debug, PartialEq, Eq)]
         pub enum DequeueError {
             Empty,
         }
 
-        impl<'a, T, A> Tx<'a, T, A>
+        impl<'a, T, A, const N: usize> Tx<'a, T, A, N>
         where
             A: Allocator,
         {
@@ -151,7 +312,10 @@ pub mod unbounded {
                     let buffer = unsafe { &*tail_ptr };
 
                     match buffer.try_enqueue(data) {
-                        Ok(_) => return,
+                        Ok(_) => {
+                            unsafe { self.shared.as_ref() }.waker.wake();
+                            return;
+                        }
                         Err(d) => {
                             data = d;
 
@@ -166,7 +330,17 @@ pub mod unbounded {
                                         atomic::Ordering::SeqCst,
                                     ) {
                                         Ok(_) => new_buffer,
-                                        Err(new_buffer) => new_buffer,
+                                        Err(other_buffer) => {
+                                            // Someone else already appended a Buffer, so ours
+                                            // was never shared and can be freed again
+                                            unsafe {
+                                                self.allocator.deallocate(
+                                                    NonNull::new(new_buffer as *mut u8).unwrap(),
+                                                    Layout::new::<Buffer<T, N>>(),
+                                                );
+                                            }
+                                            other_buffer
+                                        }
                                     }
                                 }
                                 ptr => ptr,
@@ -181,33 +355,93 @@ pub mod unbounded {
                                 atomic::Ordering::SeqCst,
                                 atomic::Ordering::SeqCst,
                             ) {
-                                Ok(_) => {}
+                                Ok(_) => {
+                                    // We moved our tail to the next Buffer, so we give up our
+                                    // reference on the old Buffer
+                                    buffer.release();
+                                }
                                 Err(_) => {
-                                    next.ref_count.fetch_sub(1, atomic::Ordering::SeqCst);
+                                    // Someone else already moved the tail and therefore also
+                                    // already released the reference on the old Buffer
+                                    next.release();
                                 }
                             };
-
-                            buffer.ref_count.fetch_sub(1, atomic::Ordering::SeqCst);
                         }
                     };
                 }
             }
         }
 
-        impl<'a, T, A> Drop for Tx<'a, T, A>
+        impl<'a, T, A, const N: usize> Drop for Tx<'a, T, A, N>
         where
             A: Allocator,
         {
             fn drop(&mut self) {
                 let buffer = unsafe { &*self.tail.load(atomic::Ordering::SeqCst) };
-                buffer.ref_count.fetch_sub(1, atomic::Ordering::SeqCst);
+                buffer.release();
+
+                unsafe { Shared::release(self.shared, self.allocator) };
             }
         }
 
-        impl<'a, T, A> Rx<'a, T, A>
+        impl<'a, T, A, const N: usize> Clone for Tx<'a, T, A, N>
         where
             A: Allocator,
         {
+            fn clone(&self) -> Self {
+                // The reference is taken while we still hold our own reference on the Buffer, so
+                // it can't be freed in between
+                let tail_ptr = self.tail.load(atomic::Ordering::SeqCst);
+                unsafe { &*tail_ptr }
+                    .ref_count
+                    .fetch_add(1, atomic::Ordering::SeqCst);
+
+                unsafe { self.shared.as_ref() }
+                    .handles
+                    .fetch_add(1, atomic::Ordering::SeqCst);
+
+                Self {
+                    allocator: self.allocator,
+                    shared: self.shared,
+                    tail: AtomicPtr::new(tail_ptr),
+                }
+            }
+        }
+
+        impl<'a, T, A, const N: usize> Rx<'a, T, A, N>
+        where
+            A: Allocator,
+        {
+            /// The number of Buffers currently allocated for the Queue, which are all the Buffers
+            /// reachable from the head of the Receiver
+            #[cfg(all(test, not(loom)))]
+            fn buffer_count(&self) -> usize {
+                let mut count = 0;
+                let mut current = self.head;
+                while !current.is_null() {
+                    count += 1;
+                    current = unsafe { &*current }.next.load(atomic::Ordering::SeqCst);
+                }
+                count
+            }
+
+            /// Attempts to dequeue an Item and otherwise registers the Waker of the Context, which
+            /// is woken once the next Item was enqueued
+            pub fn poll_dequeue(&mut self, cx: &mut Context<'_>) -> Poll<T> {
+                if let Ok(data) = self.try_dequeue() {
+                    return Poll::Ready(data);
+                }
+
+                unsafe { self.shared.as_ref() }.waker.register(cx.waker());
+
+                // An Item might have been enqueued before our Waker was registered, in which case
+                // no one is going to wake us for it
+                match self.try_dequeue() {
+                    Ok(data) => Poll::Ready(data),
+                    Err(_) => Poll::Pending,
+                }
+            }
+
             pub fn try_dequeue(&mut self) -> Result<T, DequeueError> {
                 let mut buf_ptr = self.head;
                 let mut buffer = unsafe { &*buf_ptr };
@@ -215,10 +449,9 @@ pub mod unbounded {
                 let mut initial = true;
                 let mut possible_entries = &buffer.entries[self.pos..];
                 loop {
-                    let mut set_entries = possible_entries
-                        .iter()
-                        .enumerate()
-                        .filter(|(_, e)| e.state.load(atomic::Ordering::SeqCst) == 2);
+                    let mut set_entries = possible_entries.iter().enumerate().filter(|(_, e)| {
+                        e.state.load(atomic::Ordering::SeqCst) == SlotState::Ready
+                    });
 
                     match set_entries.next() {
                         Some((index, entry)) => {
@@ -226,7 +459,7 @@ pub mod unbounded {
                                 .data
                                 .with_mut(|data_ptr| unsafe { (*data_ptr).take() }.unwrap());
 
-                            entry.state.store(3, atomic::Ordering::SeqCst);
+                            entry.state.set(SlotState::Taken);
 
                             if initial && self.pos == index {
                                 self.pos += 1;
@@ -238,25 +471,24 @@ pub mod unbounded {
                             match buffer.next.load(atomic::Ordering::SeqCst) {
                                 ptr if ptr.is_null() => return Err(DequeueError::Empty),
                                 ptr => {
-                                    if buffer
-                                        .entries
-                                        .iter()
-                                        .all(|e| e.state.load(atomic::Ordering::SeqCst) == 3)
-                                    {
-                                        if buffer.ref_count.load(atomic::Ordering::SeqCst) == 0 {
-                                            if self.head == buf_ptr && initial {
-                                                self.head = ptr;
-                                                self.pos = 0;
-
-                                                unsafe {
-                                                    self.allocator.deallocate(
-                                                        NonNull::new(buf_ptr as *mut u8).unwrap(),
-                                                        Layout::new::<Buffer<T, 4>>(),
-                                                    );
-                                                }
+                                    if buffer.entries.iter().all(|e| {
+                                        e.state.load(atomic::Ordering::SeqCst) == SlotState::Taken
+                                    }) {
+                                        // If a Sender still references the Buffer, we can't
+                                        // free it yet and will try again on a later dequeue
+                                        if buffer.ref_count.load(atomic::Ordering::SeqCst) == 0
+                                            && self.head == buf_ptr
+                                            && initial
+                                        {
+                                            self.head = ptr;
+                                            self.pos = 0;
+
+                                            unsafe {
+                                                self.allocator.deallocate(
+                                                    NonNull::new(buf_ptr as *mut u8).unwrap(),
+                                                    Layout::new::<Buffer<T, N>>(),
+                                                );
                                             }
-                                        } else {
-                                            todo!("Cant Free")
                                         }
                                     }
 
@@ -273,11 +505,13 @@ pub mod unbounded {
             }
         }
 
-        impl<'a, T, A> Drop for Rx<'a, T, A>
+        impl<'a, T, A, const N: usize> Drop for Rx<'a, T, A, N>
         where
             A: Allocator,
         {
             fn drop(&mut self) {
+                unsafe { Shared::release(self.shared, self.allocator) };
+
                 while !self.head.is_null() {
                     let buffer = unsafe { &*self.head };
                     if buffer.ref_count.load(atomic::Ordering::SeqCst) != 0 {
@@ -287,7 +521,7 @@ pub mod unbounded {
                     for entry in buffer
                         .entries
                         .iter()
-                        .filter(|e| e.state.load(atomic::Ordering::SeqCst) == 2)
+                        .filter(|e| e.state.load(atomic::Ordering::SeqCst) == SlotState::Ready)
                     {
                         let data = entry.data.with_mut(|data| unsafe { (*data).take() });
                         drop(data);
@@ -300,14 +534,14 @@ pub mod unbounded {
                     unsafe {
                         self.allocator.deallocate(
                             NonNull::new(current as *mut u8).unwrap(),
-                            Layout::new::<Buffer<T, 4>>(),
+                            Layout::new::<Buffer<T, N>>(),
                         );
                     }
                 }
             }
         }
 
-        impl<'a, T, A> QueueTx<T> for Tx<'a, T, A>
+        impl<'a, T, A, const N: usize> QueueTx<T> for Tx<'a, T, A, N>
         where
             A: Allocator,
         {
@@ -318,7 +552,7 @@ pub mod unbounded {
                 Ok(())
             }
         }
-        impl<'a, T, A> QueueRx<T> for Rx<'a, T, A>
+        impl<'a, T, A, const N: usize> QueueRx<T> for Rx<'a, T, A, N>
         where
             A: Allocator,
         {
@@ -327,12 +561,18 @@ pub mod unbounded {
             fn try_dequeue(&mut self) -> Result<T, Self::ReceiveError> {
                 Rx::try_dequeue(self)
             }
+
+            fn poll_dequeue(&mut self, cx: &mut Context<'_>) -> Poll<T> {
+                Rx::poll_dequeue(self, cx)
+            }
         }
 
         #[cfg(all(test, not(loom)))]
         mod tests {
             use super::*;
 
+            use crate::allocator::CountingAlloc;
+
             #[test]
             fn create_queue() {
                 let (tx, rx) = queue::<u8, _>(&std::alloc::System);
@@ -401,6 +641,122 @@ pub mod unbounded {
                 drop(tx);
                 drop(rx);
             }
+
+            #[test]
+            fn drained_queue_frees_buffers() {
+                let allocator = CountingAlloc::new(&std::alloc::System);
+
+                let (tx, mut rx) = queue(&allocator);
+
+                for i in 0..100 {
+                    tx.try_enqueue(i);
+                }
+                assert_eq!(25, rx.buffer_count());
+
+                for i in 0..100 {
+                    assert_eq!(Ok(i), rx.try_dequeue());
+                }
+                // The Buffers still in use and the Shared State
+                assert_eq!(rx.buffer_count() + 1, allocator.outstanding());
+
+                drop(tx);
+                drop(rx);
+
+                assert_eq!(0, allocator.outstanding());
+            }
+
+            #[test]
+            fn sized_buffers_fewer_allocations() {
+                fn allocations<const N: usize>() -> usize {
+                    let allocator = CountingAlloc::new(&std::alloc::System);
+
+                    let (tx, mut rx) = queue_sized::<usize, N, _>(&allocator);
+                    for i in 0..50 {
+                        tx.try_enqueue(i);
+                    }
+                    for i in 0..50 {
+                        assert_eq!(Ok(i), rx.try_dequeue());
+                    }
+
+                    drop(tx);
+                    drop(rx);
+                    assert_eq!(0, allocator.outstanding());
+
+                    allocator.allocations()
+                }
+
+                // The Buffers and the Shared State
+                assert_eq!(14, allocations::<4>());
+                assert_eq!(5, allocations::<16>());
+            }
+
+            #[test]
+            fn poll_dequeue_wakes_on_enqueue() {
+                let (tx, mut rx) = queue(&std::alloc::System);
+
+                let (waker, count) = futures_test::task::new_count_waker();
+                let mut ctx = Context::from_waker(&waker);
+
+                assert_eq!(Poll::Pending, rx.poll_dequeue(&mut ctx));
+                assert_eq!(0, count.get());
+
+                tx.try_enqueue(13);
+                assert_eq!(1, count.get());
+
+                assert_eq!(Poll::Ready(13), rx.poll_dequeue(&mut ctx));
+            }
+
+            #[test]
+            fn recv_from_other_task() {
+                let (tx, mut rx) = queue(&std::alloc::System);
+
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap();
+
+                rt.spawn(async move {
+                    crate::futures::yield_now().await;
+                    tx.try_enqueue(13);
+                });
+
+                assert_eq!(13, rt.block_on(rx.recv()));
+            }
+
+            #[test]
+            fn cloned_tx_ref_counts() {
+                let (tx1, mut rx) = queue(&std::alloc::System);
+                let tx2 = tx1.clone();
+
+                let first = unsafe { &*rx.head };
+                assert_eq!(2, first.ref_count.load(atomic::Ordering::SeqCst));
+
+                for i in 0..6 {
+                    tx1.try_enqueue(i);
+                }
+
+                // tx1 moved on to the second Buffer, while tx2 still points at the first one
+                let second = unsafe { &*first.next.load(atomic::Ordering::SeqCst) };
+                assert_eq!(1, first.ref_count.load(atomic::Ordering::SeqCst));
+                assert_eq!(1, second.ref_count.load(atomic::Ordering::SeqCst));
+
+                tx2.try_enqueue(6);
+
+                assert_eq!(0, first.ref_count.load(atomic::Ordering::SeqCst));
+                assert_eq!(2, second.ref_count.load(atomic::Ordering::SeqCst));
+
+                for i in 0..7 {
+                    assert_eq!(Ok(i), rx.try_dequeue());
+                }
+
+                drop(tx1);
+                drop(tx2);
+
+                let head = unsafe { &*rx.head };
+                assert_eq!(0, head.ref_count.load(atomic::Ordering::SeqCst));
+
+                drop(rx);
+            }
         }
 
         #[cfg(all(test, loom))]
@@ -409,10 +765,52 @@ pub mod unbounded {
 
             use loom::sync::Arc;
 
+            use crate::allocator::CountingAlloc;
+
+            #[test]
+            fn cloned_tx_across_buffers() {
+                loom::model(|| {
+                    let allocator: &'static CountingAlloc<'static, std::alloc::System> =
+                        Box::leak(Box::new(CountingAlloc::new(&std::alloc::System)));
+
+                    let (tx1, mut rx) = queue::<u8, _>(allocator);
+                    let tx2 = tx1.clone();
+
+                    let t1 = loom::thread::spawn(move || {
+                        for i in 0..3 {
+                            tx1.try_enqueue(i);
+                        }
+                    });
+                    let t2 = loom::thread::spawn(move || {
+                        for i in 0..3 {
+                            tx2.try_enqueue(i);
+                        }
+                    });
+
+                    t1.join().unwrap();
+                    t2.join().unwrap();
+
+                    // All the Senders have been dropped, so no Buffer should be referenced
+                    let mut buffer_ptr = rx.head;
+                    while !buffer_ptr.is_null() {
+                        let buffer = unsafe { &*buffer_ptr };
+                        assert_eq!(0, buffer.ref_count.load(atomic::Ordering::SeqCst));
+                        buffer_ptr = buffer.next.load(atomic::Ordering::SeqCst);
+                    }
+
+                    for _ in 0..6 {
+                        assert!(rx.try_dequeue().is_ok());
+                    }
+                    drop(rx);
+
+                    assert_eq!(0, allocator.outstanding());
+                });
+            }
+
             #[test]
             fn two_enqueue_one_dequeue() {
                 let mut model = loom::model::Builder::new();
-                model.max_branches = 1000;
+                model.max_branches = 2000;
 
                 model.check(|| {
                     let (rtx, mut rx) = queue::<u8, _>(&std::alloc::System);
@@ -0,0 +1,1139 @@
+//! Async Serial implementations
+//!
+//! # Generic
+//! The [`NbAsyncSerial`] works with any serial implementing the `nb` serial traits from
+//! embedded-hal, but is not very efficient as it basically busy-waits on the serial interface.
+//!
+//! # stm32l432
+//! With the `stm32l432` feature enabled, this also contains the DMA based Serial implementation
+//! for the stm32l432.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{self, AtomicBool},
+    task::{Poll, Waker},
+};
+
+use general::AsyncSerial;
+
+use crate::waker::AtomicWakerCell;
+
+#[cfg(feature = "stm32l432")]
+pub use crate::stm32l432::serial::*;
+
+/// The Error returned when a DMA transfer failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferError;
+
+/// The Status of a single DMA transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferStatus {
+    /// The transfer has not finished yet
+    Running,
+    /// The transfer completed successfully
+    Complete,
+    /// The transfer failed
+    Failed,
+}
+
+/// Tracks the Status of a DMA transfer, which is updated from the interrupt handler and checked
+/// by the Future waiting for the transfer.
+pub struct TransferFlags {
+    complete: AtomicBool,
+    error: AtomicBool,
+}
+
+impl TransferFlags {
+    pub const fn new() -> Self {
+        Self {
+            complete: AtomicBool::new(false),
+            error: AtomicBool::new(false),
+        }
+    }
+
+    /// Resets the Flags for a new transfer
+    pub fn start(&self) {
+        self.error.store(false, atomic::Ordering::SeqCst);
+        self.complete.store(false, atomic::Ordering::SeqCst);
+    }
+
+    /// Marks the current transfer as completed
+    pub fn complete(&self) {
+        self.complete.store(true, atomic::Ordering::SeqCst);
+    }
+
+    /// Marks the current transfer as failed
+    pub fn fail(&self) {
+        self.error.store(true, atomic::Ordering::SeqCst);
+    }
+
+    /// The Status of the current transfer, a failed transfer is reported as failed even if it
+    /// was also marked as completed
+    pub fn status(&self) -> TransferStatus {
+        if self.error.load(atomic::Ordering::SeqCst) {
+            TransferStatus::Failed
+        } else if self.complete.load(atomic::Ordering::SeqCst) {
+            TransferStatus::Complete
+        } else {
+            TransferStatus::Running
+        }
+    }
+}
+
+impl Default for TransferFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Connects a DMA transfer with the Future waiting for it, by storing the Status of the transfer
+/// and the Waker of the Future. The interrupt handler reports the outcome of the transfer, which
+/// wakes the Future.
+pub struct TransferNotifier {
+    waker: AtomicWakerCell,
+    flags: TransferFlags,
+}
+
+impl TransferNotifier {
+    /// Creates a new Notifier, which can be used in a `static`
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        Self {
+            waker: AtomicWakerCell::new(),
+            flags: TransferFlags::new(),
+        }
+    }
+    /// Creates a new Notifier
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        Self {
+            waker: AtomicWakerCell::new(),
+            flags: TransferFlags::new(),
+        }
+    }
+
+    /// Registers the Waker, that is woken once the current transfer is done
+    pub fn register(&self, waker: &Waker) {
+        self.waker.register(waker);
+    }
+
+    /// Resets the Status for a new transfer
+    pub fn start_transfer(&self) {
+        self.flags.start();
+    }
+
+    /// The Status of the current transfer
+    pub fn status(&self) -> TransferStatus {
+        self.flags.status()
+    }
+
+    /// Marks the current transfer as completed and wakes the waiting Future
+    pub fn complete(&self) {
+        self.flags.complete();
+        self.waker.wake();
+    }
+
+    /// Marks the current transfer as failed and wakes the waiting Future
+    pub fn fail(&self) {
+        self.flags.fail();
+        self.waker.wake();
+    }
+}
+
+impl Default for TransferNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The Phases of sending a single Frame using DMA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TxPhase {
+    /// The Buffer is still used by a previous transfer
+    WaitingForBuffer,
+    /// The Buffer has been filled, but the transfer was not started yet
+    Initial,
+    /// The transfer was started and we are waiting for it to complete
+    SendAndWaiting,
+    /// The transfer is done
+    Done,
+}
+
+impl TxPhase {
+    /// Whether or not a poll in this Phase waits for the transfer-complete interrupt, which means
+    /// that the Waker needs to be registered and the interrupt unmasked.
+    ///
+    /// While waiting for the Buffer, this is the interrupt of the previous transfer, which still
+    /// uses the Buffer.
+    pub(crate) fn waits_for_interrupt(self) -> bool {
+        !matches!(self, Self::Done)
+    }
+}
+
+/// The Error returned when a new transfer can't be started, because the Buffer is still in use
+/// by a previous transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferBusy;
+
+/// Holds the Buffer for DMA transfers, while it is not owned by a running transfer.
+///
+/// A transfer takes the Buffer out of the Slot and is expected to put it back once it is done.
+/// However the Future driving the transfer might be dropped before that happens, so when the
+/// Slot is empty [`BufferSlot::take_or_reclaim`] tries to get the Buffer back from the previous
+/// transfer instead of assuming that it is lost.
+pub struct BufferSlot<B> {
+    buffer: Option<B>,
+}
+
+impl<B> BufferSlot<B> {
+    /// Creates a new Slot holding the given Buffer
+    pub const fn new(buffer: B) -> Self {
+        Self {
+            buffer: Some(buffer),
+        }
+    }
+
+    /// Whether or not the Buffer is currently stored in the Slot
+    pub fn is_available(&self) -> bool {
+        self.buffer.is_some()
+    }
+
+    /// Takes the Buffer out of the Slot or, if it is not stored in the Slot, attempts to reclaim
+    /// it using the given closure, which should return the Buffer of a previous transfer if that
+    /// one has finished
+    pub fn take_or_reclaim<F>(&mut self, reclaim: F) -> Result<B, BufferBusy>
+    where
+        F: FnOnce() -> Option<B>,
+    {
+        match self.buffer.take() {
+            Some(buffer) => Ok(buffer),
+            None => reclaim().ok_or(BufferBusy),
+        }
+    }
+
+    /// Puts the Buffer back into the Slot
+    pub fn restore(&mut self, buffer: B) {
+        debug_assert!(self.buffer.is_none(), "Restored a Buffer into a full Slot");
+
+        self.buffer = Some(buffer);
+    }
+}
+
+/// The DMA-Channel and Notifier used by a [`TxTransfer`], which allows the same transfer logic to
+/// be driven by the DMA of the HAL and by a Mock in the Tests
+pub trait TxChannel {
+    /// The Buffer the DMA sends from
+    type Buffer;
+
+    /// Copies the Data into the Buffer and commits it, so it is send by the next transfer
+    fn fill(buffer: Self::Buffer, src: &[u8; 256]) -> Self::Buffer;
+
+    /// Starts sending the Buffer, which is handed back if the transfer could not be started
+    fn send(&mut self, buffer: Self::Buffer) -> Result<(), Self::Buffer>;
+
+    /// Returns the Buffer of the last transfer, once it has finished
+    fn transfer_complete(&mut self) -> Option<Self::Buffer>;
+
+    /// Registers the Waker, that is woken by the next transfer interrupt
+    fn register(&self, waker: &Waker);
+
+    /// Resets the Status of the Channel for a new transfer
+    fn start_transfer(&self);
+
+    /// The Status of the current transfer
+    fn status(&self) -> TransferStatus;
+
+    /// Unmasks the transfer interrupt, which masks itself again after firing
+    fn unmask(&self);
+}
+
+enum TxState<B> {
+    WaitingForBuffer,
+    Initial { data: B },
+    SendAndWaiting,
+    Done,
+}
+
+impl<B> TxState<B> {
+    fn phase(&self) -> TxPhase {
+        match self {
+            Self::WaitingForBuffer => TxPhase::WaitingForBuffer,
+            Self::Initial { .. } => TxPhase::Initial,
+            Self::SendAndWaiting => TxPhase::SendAndWaiting,
+            Self::Done => TxPhase::Done,
+        }
+    }
+}
+
+/// Sends a single Frame using DMA.
+///
+/// The Buffer is taken out of the [`BufferSlot`] for the transfer and put back once the transfer
+/// is done. If the Buffer is still used by a previous transfer, whose Future was dropped, this
+/// waits for the interrupt of that transfer before starting its own.
+pub struct TxTransfer<'t, C>
+where
+    C: TxChannel,
+{
+    channel: &'t mut C,
+    slot: &'t mut BufferSlot<C::Buffer>,
+    /// The Data to send, which is kept around to send it again if the transfer failed
+    src: [u8; 256],
+    state: TxState<C::Buffer>,
+}
+
+impl<'t, C> TxTransfer<'t, C>
+where
+    C: TxChannel,
+{
+    /// Prepares the transfer of the given Data, which is started once the Future is polled
+    pub fn new(channel: &'t mut C, slot: &'t mut BufferSlot<C::Buffer>, src: &[u8; 256]) -> Self {
+        let state = match slot.take_or_reclaim(|| channel.transfer_complete()) {
+            Ok(buffer) => TxState::Initial {
+                data: C::fill(buffer, src),
+            },
+            Err(BufferBusy) => TxState::WaitingForBuffer,
+        };
+
+        Self {
+            channel,
+            slot,
+            src: *src,
+            state,
+        }
+    }
+
+    /// Drives the transfer. If the transfer failed, the Data is send again the next time this is
+    /// polled
+    fn poll_transfer(
+        &mut self,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Result<(), TransferError>> {
+        // The Waker needs to be in place before the transfer is started, so the interrupt handler
+        // always has something to wake
+        if self.state.phase().waits_for_interrupt() {
+            self.channel.register(cx.waker());
+        }
+
+        match core::mem::replace(&mut self.state, TxState::Done) {
+            TxState::WaitingForBuffer => {
+                let channel = &mut *self.channel;
+                match self.slot.take_or_reclaim(|| channel.transfer_complete()) {
+                    Ok(buffer) => {
+                        self.state = TxState::Initial {
+                            data: C::fill(buffer, &self.src),
+                        };
+
+                        cx.waker().wake_by_ref();
+                    }
+                    Err(BufferBusy) => {
+                        // The previous transfer is still running and wakes us through its
+                        // interrupt, once it is done
+                        self.state = TxState::WaitingForBuffer;
+                        self.channel.unmask();
+                    }
+                };
+
+                Poll::Pending
+            }
+            TxState::Initial { data } => {
+                // The Flags are reset before the transfer is started and the interrupt is only
+                // unmasked afterwards, so the interrupt can only ever report on this transfer.
+                // If the transfer completes before the interrupt is unmasked, the interrupt stays
+                // pending and fires right after unmasking it
+                self.channel.start_transfer();
+                match self.channel.send(data) {
+                    Ok(_) => {
+                        self.state = TxState::SendAndWaiting;
+                        self.channel.unmask();
+                    }
+                    Err(data) => {
+                        self.state = TxState::Initial { data };
+
+                        cx.waker().wake_by_ref();
+                    }
+                };
+
+                Poll::Pending
+            }
+            TxState::SendAndWaiting if self.channel.status() == TransferStatus::Failed => {
+                // Try to get the Buffer back, so it can be used for the next transfer
+                if let Some(buffer) = self.channel.transfer_complete() {
+                    self.slot.restore(buffer);
+                }
+                self.state = TxState::WaitingForBuffer;
+
+                Poll::Ready(Err(TransferError))
+            }
+            TxState::SendAndWaiting => match self.channel.transfer_complete() {
+                Some(buffer) => {
+                    self.slot.restore(buffer);
+
+                    Poll::Ready(Ok(()))
+                }
+                None => {
+                    self.state = TxState::SendAndWaiting;
+                    // The interrupt masks itself after firing, so it needs to be unmasked again
+                    // for as long as we are waiting
+                    self.channel.unmask();
+
+                    Poll::Pending
+                }
+            },
+            TxState::Done => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl<'t, C> Future for TxTransfer<'t, C>
+where
+    C: TxChannel,
+    C::Buffer: Unpin,
+{
+    type Output = Result<(), TransferError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        self.poll_transfer(cx)
+    }
+}
+
+/// Makes sure the Buffer is not lost, if the Future is dropped before the transfer finished.
+/// If the transfer is still running, the Buffer is reclaimed by the next transfer instead
+impl<'t, C> Drop for TxTransfer<'t, C>
+where
+    C: TxChannel,
+{
+    fn drop(&mut self) {
+        match core::mem::replace(&mut self.state, TxState::Done) {
+            TxState::Initial { data } => {
+                self.slot.restore(data);
+            }
+            TxState::SendAndWaiting => {
+                if let Some(buffer) = self.channel.transfer_complete() {
+                    self.slot.restore(buffer);
+                }
+            }
+            TxState::WaitingForBuffer | TxState::Done => {}
+        }
+    }
+}
+
+/// Adapts a fallible transfer Future to the [`AsyncSerial`] trait, which has no way of reporting
+/// Errors, by retrying the transfer until it succeeds, as a failed transfer is usually caused by
+/// a recoverable Bus-Error.
+pub struct RetryTransfer<F> {
+    pub(crate) inner: F,
+}
+
+impl<'t, C> Future for RetryTransfer<TxTransfer<'t, C>>
+where
+    C: TxChannel,
+    C::Buffer: Unpin,
+{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        match self.inner.poll_transfer(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(()),
+            Poll::Ready(Err(TransferError)) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The Error returned when more Bytes should be committed to a Frame, than it can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitTooLong {
+    /// The number of Bytes, that should have been committed
+    pub len: usize,
+    /// The number of Bytes the Frame can hold
+    pub capacity: usize,
+}
+
+/// A Frame, whose written Bytes need to be committed before they can be transferred, like the
+/// `DMAFrame` of the HAL
+pub trait CommitFrame {
+    /// The number of Bytes the Frame can hold
+    fn capacity(&self) -> usize;
+
+    /// Commits the first `len` Bytes of the Frame, without checking `len`.
+    ///
+    /// The HAL does not validate the length, so committing more than the capacity results in
+    /// the DMA reading past the Frame, use [`checked_commit`] instead.
+    fn commit_unchecked(&mut self, len: usize);
+}
+
+/// Commits the first `len` Bytes of the Frame, but rejects any `len` exceeding the capacity of
+/// the Frame, which might have been derived from untrusted Input, instead of passing it on to
+/// the HAL
+pub fn checked_commit<F>(frame: &mut F, len: usize) -> Result<(), CommitTooLong>
+where
+    F: CommitFrame,
+{
+    let capacity = frame.capacity();
+    if len > capacity {
+        return Err(CommitTooLong { len, capacity });
+    }
+
+    frame.commit_unchecked(len);
+    Ok(())
+}
+
+/// An [`AsyncSerial`] implementation for any blocking `nb` serial.
+///
+/// # Performance
+/// This works by trying to read/write the serial whenever the Future gets polled and if the
+/// serial would block, the Future immediately wakes itself again and yields. This is basically a
+/// cooperative busy-wait, so it will keep the executor busy, but it works for every serial.
+///
+/// # Errors
+/// Use [`try_read`](NbAsyncSerial::try_read) and [`try_write`](NbAsyncSerial::try_write) to
+/// handle errors returned by the underlying serial. As [`AsyncSerial`] has no way to report
+/// errors, its implementation retries the failed Byte instead, like after an Overrun.
+pub struct NbAsyncSerial<S> {
+    serial: S,
+}
+
+impl<S> NbAsyncSerial<S> {
+    /// Wraps the given serial
+    pub fn new(serial: S) -> Self {
+        Self { serial }
+    }
+
+    /// Returns the underlying serial
+    pub fn into_inner(self) -> S {
+        self.serial
+    }
+
+    /// Reads a single Frame, like [`AsyncSerial::read`], but resolves to the Error of the
+    /// underlying serial if it fails
+    pub fn try_read<const N: usize>(&mut self) -> NbReadFuture<'_, S, N> {
+        NbReadFuture {
+            serial: &mut self.serial,
+            buffer: [0; N],
+            pos: 0,
+        }
+    }
+
+    /// Writes a single Frame, like [`AsyncSerial::write`], but resolves to the Error of the
+    /// underlying serial if it fails
+    pub fn try_write<const N: usize>(&mut self, buffer: [u8; N]) -> NbWriteFuture<'_, S, N> {
+        NbWriteFuture {
+            serial: &mut self.serial,
+            buffer,
+            pos: 0,
+        }
+    }
+}
+
+impl<S, const N: usize> AsyncSerial<N> for NbAsyncSerial<S>
+where
+    S: embedded_hal::serial::nb::Read<u8> + embedded_hal::serial::nb::Write<u8> + Unpin,
+{
+    type ReceiveFuture<'f> = RetrySerial<NbReadFuture<'f, S, N>> where Self: 'f;
+    type WriteFuture<'f> = RetrySerial<NbWriteFuture<'f, S, N>> where Self: 'f;
+
+    fn read<'s, 'f>(&'s mut self) -> Self::ReceiveFuture<'f>
+    where
+        's: 'f,
+    {
+        RetrySerial {
+            inner: self.try_read(),
+        }
+    }
+
+    fn write<'s, 'f>(&'s mut self, buffer: [u8; N]) -> Self::WriteFuture<'f>
+    where
+        's: 'f,
+    {
+        RetrySerial {
+            inner: self.try_write(buffer),
+        }
+    }
+}
+
+/// Adapts a fallible [`NbAsyncSerial`] Future to the [`AsyncSerial`] trait, which has no way of
+/// reporting Errors, by retrying the failed Byte until the underlying serial recovers.
+pub struct RetrySerial<F> {
+    inner: F,
+}
+
+impl<'s, S, const N: usize> Future for RetrySerial<NbReadFuture<'s, S, N>>
+where
+    S: embedded_hal::serial::nb::Read<u8> + Unpin,
+{
+    type Output = [u8; N];
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        match self.inner.poll_frame(cx) {
+            Poll::Ready(Ok(frame)) => Poll::Ready(frame),
+            Poll::Ready(Err(_)) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<'s, S, const N: usize> Future for RetrySerial<NbWriteFuture<'s, S, N>>
+where
+    S: embedded_hal::serial::nb::Write<u8> + Unpin,
+{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        match self.inner.poll_frame(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(()),
+            Poll::Ready(Err(_)) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The Future for reading a single Frame from a [`NbAsyncSerial`]
+pub struct NbReadFuture<'s, S, const N: usize> {
+    serial: &'s mut S,
+    buffer: [u8; N],
+    pos: usize,
+}
+
+impl<'s, S, const N: usize> NbReadFuture<'s, S, N>
+where
+    S: embedded_hal::serial::nb::Read<u8>,
+{
+    /// Reads the remaining Bytes of the Frame. If the serial fails, the failed Byte is read
+    /// again the next time this is polled
+    fn poll_frame(&mut self, cx: &mut core::task::Context<'_>) -> Poll<Result<[u8; N], S::Error>> {
+        while self.pos < N {
+            match self.serial.read() {
+                Ok(byte) => {
+                    let pos = self.pos;
+                    self.buffer[pos] = byte;
+                    self.pos += 1;
+                }
+                Err(nb::Error::WouldBlock) => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Err(nb::Error::Other(e)) => return Poll::Ready(Err(e)),
+            };
+        }
+
+        Poll::Ready(Ok(self.buffer))
+    }
+}
+
+impl<'s, S, const N: usize> Future for NbReadFuture<'s, S, N>
+where
+    S: embedded_hal::serial::nb::Read<u8> + Unpin,
+{
+    type Output = Result<[u8; N], S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        self.poll_frame(cx)
+    }
+}
+
+/// The Future for writing a single Frame to a [`NbAsyncSerial`]
+pub struct NbWriteFuture<'s, S, const N: usize> {
+    serial: &'s mut S,
+    buffer: [u8; N],
+    pos: usize,
+}
+
+impl<'s, S, const N: usize> NbWriteFuture<'s, S, N>
+where
+    S: embedded_hal::serial::nb::Write<u8>,
+{
+    /// Writes the remaining Bytes of the Frame and flushes the serial. If the serial fails, the
+    /// failed Byte is written again the next time this is polled
+    fn poll_frame(&mut self, cx: &mut core::task::Context<'_>) -> Poll<Result<(), S::Error>> {
+        while self.pos < N {
+            let byte = self.buffer[self.pos];
+            match self.serial.write(byte) {
+                Ok(_) => {
+                    self.pos += 1;
+                }
+                Err(nb::Error::WouldBlock) => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Err(nb::Error::Other(e)) => return Poll::Ready(Err(e)),
+            };
+        }
+
+        match self.serial.flush() {
+            Ok(_) => Poll::Ready(Ok(())),
+            Err(nb::Error::WouldBlock) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+impl<'s, S, const N: usize> Future for NbWriteFuture<'s, S, N>
+where
+    S: embedded_hal::serial::nb::Write<u8> + Unpin,
+{
+    type Output = Result<(), S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        self.poll_frame(cx)
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    use embedded_hal_mock::serial::{Mock as SerialMock, Transaction as SerialTransaction};
+
+    /// Records the Length committed to it, like the DMAFrame of the HAL would
+    struct MockFrame {
+        committed: Option<usize>,
+    }
+    impl CommitFrame for MockFrame {
+        fn capacity(&self) -> usize {
+            256
+        }
+
+        fn commit_unchecked(&mut self, len: usize) {
+            self.committed = Some(len);
+        }
+    }
+
+    #[test]
+    fn checked_commit_within_capacity() {
+        let mut frame = MockFrame { committed: None };
+
+        assert_eq!(Ok(()), checked_commit(&mut frame, 13));
+        assert_eq!(Some(13), frame.committed);
+
+        assert_eq!(Ok(()), checked_commit(&mut frame, 256));
+        assert_eq!(Some(256), frame.committed);
+    }
+
+    #[test]
+    fn checked_commit_too_long() {
+        let mut frame = MockFrame { committed: None };
+
+        assert_eq!(
+            Err(CommitTooLong {
+                len: 257,
+                capacity: 256
+            }),
+            checked_commit(&mut frame, 257)
+        );
+        assert_eq!(None, frame.committed);
+    }
+
+    #[test]
+    fn transfer_flags_complete() {
+        let flags = TransferFlags::new();
+
+        flags.start();
+        assert_eq!(TransferStatus::Running, flags.status());
+
+        flags.complete();
+        assert_eq!(TransferStatus::Complete, flags.status());
+    }
+
+    #[test]
+    fn transfer_flags_error() {
+        let flags = TransferFlags::new();
+
+        flags.start();
+        flags.fail();
+        assert_eq!(TransferStatus::Failed, flags.status());
+
+        // The DMA also signals completion after an error, which should not hide the error
+        flags.complete();
+        assert_eq!(TransferStatus::Failed, flags.status());
+
+        flags.start();
+        assert_eq!(TransferStatus::Running, flags.status());
+    }
+
+    /// A DMA-Channel, whose transfers only finish when told to
+    #[derive(Default)]
+    struct MockChannel {
+        flags: TransferFlags,
+        /// The Buffer currently owned by the DMA
+        dma: Option<[u8; 256]>,
+        finished: bool,
+        sent: Vec<u8>,
+        registered: core::cell::Cell<usize>,
+        unmasked: core::cell::Cell<usize>,
+    }
+
+    impl MockChannel {
+        /// Finishes the running transfer, like the interrupt would
+        fn finish(&mut self) {
+            self.finished = true;
+            self.flags.complete();
+        }
+    }
+
+    impl TxChannel for MockChannel {
+        type Buffer = [u8; 256];
+
+        fn fill(_: Self::Buffer, src: &[u8; 256]) -> Self::Buffer {
+            *src
+        }
+
+        fn send(&mut self, buffer: Self::Buffer) -> Result<(), Self::Buffer> {
+            self.sent.push(buffer[0]);
+            self.dma = Some(buffer);
+            self.finished = false;
+            Ok(())
+        }
+
+        fn transfer_complete(&mut self) -> Option<Self::Buffer> {
+            if self.finished {
+                self.dma.take()
+            } else {
+                None
+            }
+        }
+
+        fn register(&self, _: &Waker) {
+            self.registered.set(self.registered.get() + 1);
+        }
+
+        fn start_transfer(&self) {
+            self.flags.start();
+        }
+
+        fn status(&self) -> TransferStatus {
+            self.flags.status()
+        }
+
+        fn unmask(&self) {
+            self.unmasked.set(self.unmasked.get() + 1);
+        }
+    }
+
+    #[test]
+    fn transfer_notifier_const_construction() {
+        static NOTIFIER: TransferNotifier = TransferNotifier::new();
+
+        assert_eq!(TransferStatus::Running, NOTIFIER.status());
+    }
+
+    #[test]
+    fn transfer_notifier_wakes() {
+        let (waker, count) = futures_test::task::new_count_waker();
+        let notifier = TransferNotifier::new();
+
+        notifier.start_transfer();
+        notifier.register(&waker);
+        notifier.complete();
+        assert_eq!(1, count.get());
+        assert_eq!(TransferStatus::Complete, notifier.status());
+
+        // A new transfer resets the Status and the Waker needs to be registered again
+        notifier.start_transfer();
+        assert_eq!(TransferStatus::Running, notifier.status());
+        notifier.fail();
+        assert_eq!(1, count.get());
+        assert_eq!(TransferStatus::Failed, notifier.status());
+
+        notifier.register(&waker);
+        notifier.fail();
+        assert_eq!(2, count.get());
+    }
+
+    #[test]
+    fn tx_phase_interrupt() {
+        let (waker, _) = futures_test::task::new_count_waker();
+        let mut cx = core::task::Context::from_waker(&waker);
+
+        let mut channel = MockChannel::default();
+        let mut slot = BufferSlot::new([0; 256]);
+
+        // Leaves the Buffer with the DMA, so the next transfer has to wait for it
+        let mut previous = TxTransfer::new(&mut channel, &mut slot, &[1; 256]);
+        assert_eq!(Poll::Pending, previous.poll_transfer(&mut cx));
+        drop(previous);
+
+        let mut transfer = TxTransfer::new(&mut channel, &mut slot, &[2; 256]);
+        let mut phases = Vec::new();
+        loop {
+            let phase = transfer.state.phase();
+            let registered = transfer.channel.registered.get();
+
+            let result = transfer.poll_transfer(&mut cx);
+            phases.push((phase, transfer.channel.registered.get() > registered));
+            if result.is_ready() {
+                break;
+            }
+
+            // The interrupt fires once for the previous and once for our own transfer
+            if phases.len() == 1 || phases.len() == 4 {
+                transfer.channel.finish();
+            }
+        }
+        drop(transfer);
+
+        assert_eq!(
+            vec![
+                (TxPhase::WaitingForBuffer, true),
+                (TxPhase::WaitingForBuffer, true),
+                (TxPhase::Initial, true),
+                (TxPhase::SendAndWaiting, true),
+                (TxPhase::SendAndWaiting, true),
+            ],
+            phases
+        );
+
+        // Once the transfer is done, the interrupt is left alone
+        let mut transfer = TxTransfer::new(&mut channel, &mut slot, &[3; 256]);
+        transfer.state = TxState::Done;
+        let registered = transfer.channel.registered.get();
+        assert_eq!(Poll::Ready(Ok(())), transfer.poll_transfer(&mut cx));
+        assert_eq!(registered, transfer.channel.registered.get());
+    }
+
+    #[test]
+    fn tx_transfer_send() {
+        let (waker, count) = futures_test::task::new_count_waker();
+        let mut cx = core::task::Context::from_waker(&waker);
+
+        let mut channel = MockChannel::default();
+        let mut slot = BufferSlot::new([0; 256]);
+
+        let mut transfer = TxTransfer::new(&mut channel, &mut slot, &[1; 256]);
+        assert_eq!(Poll::Pending, transfer.poll_transfer(&mut cx));
+        assert_eq!(Poll::Pending, transfer.poll_transfer(&mut cx));
+
+        transfer.channel.finish();
+        assert_eq!(Poll::Ready(Ok(())), transfer.poll_transfer(&mut cx));
+        drop(transfer);
+
+        assert_eq!(0, count.get());
+        assert_eq!(vec![1], channel.sent);
+        assert!(slot.is_available());
+    }
+
+    #[test]
+    fn tx_transfer_dropped() {
+        let (waker, count) = futures_test::task::new_count_waker();
+        let mut cx = core::task::Context::from_waker(&waker);
+
+        let mut channel = MockChannel::default();
+        let mut slot = BufferSlot::new([0; 256]);
+
+        // Dropped before it was even started
+        let transfer = TxTransfer::new(&mut channel, &mut slot, &[1; 256]);
+        drop(transfer);
+        assert!(slot.is_available());
+
+        // Dropped while the DMA still owns the Buffer
+        let mut transfer = TxTransfer::new(&mut channel, &mut slot, &[2; 256]);
+        assert_eq!(Poll::Pending, transfer.poll_transfer(&mut cx));
+        drop(transfer);
+        assert!(!slot.is_available());
+
+        // The next transfer has to wait for the interrupt of the previous one, instead of
+        // spinning on its own Waker
+        let mut transfer = TxTransfer::new(&mut channel, &mut slot, &[3; 256]);
+        let registered = transfer.channel.registered.get();
+        let unmasked = transfer.channel.unmasked.get();
+        assert_eq!(Poll::Pending, transfer.poll_transfer(&mut cx));
+        assert_eq!(Poll::Pending, transfer.poll_transfer(&mut cx));
+        assert_eq!(0, count.get());
+        assert_eq!(registered + 2, transfer.channel.registered.get());
+        assert_eq!(unmasked + 2, transfer.channel.unmasked.get());
+
+        // The interrupt of the previous transfer hands the Buffer back
+        transfer.channel.finish();
+        assert_eq!(Poll::Pending, transfer.poll_transfer(&mut cx));
+        assert_eq!(Poll::Pending, transfer.poll_transfer(&mut cx));
+        transfer.channel.finish();
+        assert_eq!(Poll::Ready(Ok(())), transfer.poll_transfer(&mut cx));
+        drop(transfer);
+
+        assert_eq!(vec![2, 3], channel.sent);
+        assert!(slot.is_available());
+    }
+
+    #[test]
+    fn tx_transfer_retry() {
+        let (waker, _) = futures_test::task::new_count_waker();
+        let mut cx = core::task::Context::from_waker(&waker);
+
+        let mut channel = MockChannel::default();
+        let mut slot = BufferSlot::new([0; 256]);
+
+        let mut transfer = RetryTransfer {
+            inner: TxTransfer::new(&mut channel, &mut slot, &[1; 256]),
+        };
+        assert_eq!(Poll::Pending, Pin::new(&mut transfer).poll(&mut cx));
+
+        // The transfer failed, but the DMA still handed the Buffer back
+        transfer.inner.channel.finished = true;
+        transfer.inner.channel.flags.fail();
+        assert_eq!(Poll::Pending, Pin::new(&mut transfer).poll(&mut cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut transfer).poll(&mut cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut transfer).poll(&mut cx));
+
+        transfer.inner.channel.finish();
+        assert_eq!(Poll::Ready(()), Pin::new(&mut transfer).poll(&mut cx));
+        drop(transfer);
+
+        assert_eq!(vec![1, 1], channel.sent);
+        assert!(slot.is_available());
+    }
+
+    #[test]
+    fn buffer_slot_reclaim() {
+        let mut slot = BufferSlot::new([1, 2, 3, 4]);
+
+        let buffer = slot
+            .take_or_reclaim(|| None)
+            .expect("Buffer is in the Slot");
+        assert!(!slot.is_available());
+
+        // The previous transfer is still running
+        assert_eq!(Err(BufferBusy), slot.take_or_reclaim(|| None));
+
+        // The previous transfer finished, but its Future never put the Buffer back
+        assert_eq!(Ok(buffer), slot.take_or_reclaim(|| Some(buffer)));
+        assert!(!slot.is_available());
+    }
+
+    #[test]
+    fn read_write_frame() {
+        let mut expectations = vec![];
+        expectations.extend([1, 2, 3, 4].into_iter().map(SerialTransaction::read));
+        expectations.extend([4, 3, 2, 1].into_iter().map(SerialTransaction::write));
+        expectations.push(SerialTransaction::flush());
+        let serial = SerialMock::new(&expectations);
+
+        let mut async_serial = NbAsyncSerial::new(serial);
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            let mut frame: [u8; 4] = async_serial.read().await;
+            assert_eq!([1, 2, 3, 4], frame);
+
+            frame.reverse();
+            async_serial.write(frame).await;
+        });
+
+        async_serial.into_inner().done();
+    }
+
+    /// A Serial that fails every operation
+    struct FailingSerial;
+    impl embedded_hal::serial::ErrorType for FailingSerial {
+        type Error = embedded_hal::serial::ErrorKind;
+    }
+    impl embedded_hal::serial::nb::Read<u8> for FailingSerial {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            Err(nb::Error::Other(embedded_hal::serial::ErrorKind::Overrun))
+        }
+    }
+    impl embedded_hal::serial::nb::Write<u8> for FailingSerial {
+        fn write(&mut self, _: u8) -> nb::Result<(), Self::Error> {
+            Err(nb::Error::Other(embedded_hal::serial::ErrorKind::Noise))
+        }
+        fn flush(&mut self) -> nb::Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn read_write_error() {
+        let mut async_serial = NbAsyncSerial::new(FailingSerial);
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            assert_eq!(
+                Err(embedded_hal::serial::ErrorKind::Overrun),
+                async_serial.try_read::<4>().await
+            );
+            assert_eq!(
+                Err(embedded_hal::serial::ErrorKind::Noise),
+                async_serial.try_write([1, 2, 3, 4]).await
+            );
+        });
+    }
+
+    /// A Serial that fails every second operation, like a UART with occasional Overruns
+    struct FlakySerial {
+        failed: bool,
+        read: std::vec::IntoIter<u8>,
+        written: Vec<u8>,
+    }
+    impl FlakySerial {
+        /// Alternates between failing and succeeding, starting with a failure
+        fn fail(&mut self) -> bool {
+            self.failed = !self.failed;
+            self.failed
+        }
+    }
+    impl embedded_hal::serial::ErrorType for FlakySerial {
+        type Error = embedded_hal::serial::ErrorKind;
+    }
+    impl embedded_hal::serial::nb::Read<u8> for FlakySerial {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            if self.fail() {
+                return Err(nb::Error::Other(embedded_hal::serial::ErrorKind::Overrun));
+            }
+            Ok(self.read.next().expect("Only 4 Bytes are read"))
+        }
+    }
+    impl embedded_hal::serial::nb::Write<u8> for FlakySerial {
+        fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
+            if self.fail() {
+                return Err(nb::Error::Other(embedded_hal::serial::ErrorKind::Noise));
+            }
+            self.written.push(byte);
+            Ok(())
+        }
+        fn flush(&mut self) -> nb::Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn read_write_recover() {
+        let mut async_serial = NbAsyncSerial::new(FlakySerial {
+            failed: false,
+            read: vec![1, 2, 3, 4].into_iter(),
+            written: Vec::new(),
+        });
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            let frame: [u8; 4] = async_serial.read().await;
+            assert_eq!([1, 2, 3, 4], frame);
+
+            async_serial.write([4, 3, 2, 1]).await;
+        });
+
+        assert_eq!(vec![4, 3, 2, 1], async_serial.into_inner().written);
+    }
+}
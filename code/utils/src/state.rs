@@ -0,0 +1,190 @@
+//! The State-Machine shared by the Slots in the Timer and the Entries in the Queues.
+//!
+//! # Transitions
+//! ```text
+//! Free -> Reserving -> Ready -> Taken
+//! ```
+//! This is the path of an Entry in the Queues, which are never reused. The Slots of the Timer
+//! additionally use `Reserving` to get exclusive access to the Slot, while the Waker is taken
+//! out of it or the Slot is released, which allows these Transitions:
+//! * `Ready -> Reserving` and `Taken -> Reserving`: Claims exclusive access to the Slot
+//! * `Reserving -> Ready` and `Reserving -> Taken`: Gives up the exclusive access again, like
+//!   after taking out the Waker of a fired Timer
+//! * `Reserving -> Free`: The Slot is released, like when the Timer is dropped or all the Slots
+//!   are cleared at once
+
+use crate::atomic::{self, AtomicU8};
+
+/// The State of a single Slot/Entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum SlotState {
+    /// The Slot is not used and can be reserved
+    Free = 0,
+    /// The Slot has been reserved and its Data is currently being accessed exclusively
+    Reserving = 1,
+    /// The Data has been written and is ready to be taken
+    Ready = 2,
+    /// The Data has been taken out of the Slot
+    Taken = 3,
+}
+
+impl SlotState {
+    const fn from_raw(raw: u8) -> Self {
+        match raw {
+            0 => Self::Free,
+            1 => Self::Reserving,
+            2 => Self::Ready,
+            _ => Self::Taken,
+        }
+    }
+
+    /// Checks if the Transition from `self` to `to` is a valid one
+    pub(crate) const fn can_transition(self, to: Self) -> bool {
+        matches!(
+            (self, to),
+            (Self::Free, Self::Reserving)
+                | (Self::Reserving, Self::Ready)
+                | (Self::Reserving, Self::Taken)
+                | (Self::Reserving, Self::Free)
+                | (Self::Ready, Self::Reserving)
+                | (Self::Ready, Self::Taken)
+                | (Self::Taken, Self::Reserving)
+        )
+    }
+}
+
+/// An atomic [`SlotState`]
+pub(crate) struct AtomicSlotState {
+    state: AtomicU8,
+}
+
+impl AtomicSlotState {
+    #[cfg(not(loom))]
+    pub(crate) const fn new(state: SlotState) -> Self {
+        Self {
+            state: AtomicU8::new(state as u8),
+        }
+    }
+    #[cfg(loom)]
+    pub(crate) fn new(state: SlotState) -> Self {
+        Self {
+            state: AtomicU8::new(state as u8),
+        }
+    }
+
+    pub(crate) fn load(&self, order: atomic::Ordering) -> SlotState {
+        SlotState::from_raw(self.state.load(order))
+    }
+
+    /// Attempts to transition from the `from` state to the `to` state, this fails if the current
+    /// state is not `from` or if the transition itself is not valid
+    pub(crate) fn try_transition(&self, from: SlotState, to: SlotState) -> bool {
+        if !from.can_transition(to) {
+            return false;
+        }
+
+        self.state
+            .compare_exchange(
+                from as u8,
+                to as u8,
+                atomic::Ordering::SeqCst,
+                atomic::Ordering::SeqCst,
+            )
+            .is_ok()
+    }
+
+    /// Unconditionally sets the state, this should only be used by the current owner of the Slot.
+    ///
+    /// # Debug
+    /// In debug builds this checks that the transition is valid
+    pub(crate) fn set(&self, to: SlotState) {
+        let previous = SlotState::from_raw(self.state.swap(to as u8, atomic::Ordering::SeqCst));
+        debug_assert!(
+            previous.can_transition(to),
+            "Invalid Transition from {:?} to {:?}",
+            previous,
+            to
+        );
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_transitions() {
+        let state = AtomicSlotState::new(SlotState::Free);
+
+        assert!(state.try_transition(SlotState::Free, SlotState::Reserving));
+        assert!(state.try_transition(SlotState::Reserving, SlotState::Ready));
+        assert!(state.try_transition(SlotState::Ready, SlotState::Taken));
+        assert!(state.try_transition(SlotState::Taken, SlotState::Reserving));
+        assert!(state.try_transition(SlotState::Reserving, SlotState::Free));
+
+        assert_eq!(SlotState::Free, state.load(atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn release_needs_exclusive_access() {
+        let state = AtomicSlotState::new(SlotState::Ready);
+
+        // A Slot can only be released while holding exclusive access to it
+        assert!(!state.try_transition(SlotState::Ready, SlotState::Free));
+        assert_eq!(SlotState::Ready, state.load(atomic::Ordering::SeqCst));
+
+        state.set(SlotState::Taken);
+        assert!(!state.try_transition(SlotState::Taken, SlotState::Free));
+        assert_eq!(SlotState::Taken, state.load(atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn take_exclusive() {
+        let state = AtomicSlotState::new(SlotState::Ready);
+
+        assert!(state.try_transition(SlotState::Ready, SlotState::Reserving));
+        // The Slot can't be freed while the Data is being taken
+        assert!(!state.try_transition(SlotState::Ready, SlotState::Free));
+        assert!(!state.try_transition(SlotState::Taken, SlotState::Free));
+        assert!(state.try_transition(SlotState::Reserving, SlotState::Taken));
+
+        assert_eq!(SlotState::Taken, state.load(atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn reuse_taken() {
+        let state = AtomicSlotState::new(SlotState::Taken);
+
+        assert!(state.try_transition(SlotState::Taken, SlotState::Reserving));
+        assert!(state.try_transition(SlotState::Reserving, SlotState::Ready));
+
+        assert_eq!(SlotState::Ready, state.load(atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn illegal_transition_rejected() {
+        let state = AtomicSlotState::new(SlotState::Free);
+
+        assert!(!state.try_transition(SlotState::Free, SlotState::Taken));
+        assert!(!state.try_transition(SlotState::Free, SlotState::Ready));
+        assert_eq!(SlotState::Free, state.load(atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn wrong_current_state_rejected() {
+        let state = AtomicSlotState::new(SlotState::Reserving);
+
+        assert!(!state.try_transition(SlotState::Free, SlotState::Reserving));
+        assert_eq!(SlotState::Reserving, state.load(atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn illegal_set_panics_in_debug() {
+        let state = AtomicSlotState::new(SlotState::Free);
+
+        state.set(SlotState::Taken);
+    }
+}
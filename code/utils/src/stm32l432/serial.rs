@@ -9,23 +9,47 @@
 //! You need two seperate notifiers, one for receiving and one for transmitting. The respective
 //! notifier should be called from the appropriate interrupt handler, signaling that the
 //! transaction might be completed.
-//! In the interrupt handlers, you need to to call [`SerialNotifier::transfer_complete`]
+//! In the interrupt handlers, you need to to call [`SerialNotifier::transfer_complete`] or
+//! [`SerialNotifier::transfer_error`] if the transfer failed.
+//!
+//! The [`serial_interrupts`](crate::serial_interrupts) macro generates both the notifiers and
+//! the interrupt handlers for a given USART, so they can't be accidentally mixed up.
+//!
+//! ## The Frames
+//! The DMA transfers need buffers that live for `'static`, which can be obtained from a
+//! [`FramePool`] placed in a static variable.
+//!
+//! ## Supported USARTs
+//! The STM32L432 only has `USART1` and `USART2` (besides the `LPUART1`), which are both
+//! supported using the following DMA1-Channels (RM0394, Table 41):
+//!
+//! | USART    | TX            | RX            |
+//! |----------|---------------|---------------|
+//! | `USART1` | Channel 4     | Channel 5     |
+//! | `USART2` | Channel 7     | Channel 6     |
+//!
+//! `USART3` only exists on the larger parts of the STM32L4 Family, like the STM32L433, where it
+//! would use Channel 2 for TX and Channel 3 for RX. As it is not available on this chip, the HAL
+//! provides no Type for it and there is no `USART3` Key.
 
-use core::{
-    future::Future, marker::PhantomData, sync::atomic, sync::atomic::AtomicBool, task::Waker,
-};
+use core::{future::Future, marker::PhantomData, task::Waker};
 
-use cortex_m::interrupt::InterruptNumber;
 use general::AsyncSerial;
 use stm32l4xx_hal::{self as hal};
 
-use super::NoInterruptMutex;
+use crate::{
+    pool::StaticPool,
+    serial::{
+        checked_commit, BufferSlot, CommitFrame, RetryTransfer, TransferError, TransferNotifier,
+        TransferStatus, TxChannel, TxTransfer,
+    },
+};
 
 mod keys {
     use stm32l4xx_hal::{self as hal};
 
     macro_rules! key {
-        ($name:ident, $interrupt_type:ty, $interrupt:expr) => {
+        ($name:ident, $interrupt_type:ty, $interrupt:expr, $teif:ident, $cteif:ident) => {
             pub struct $name;
 
             impl crate::sealed::Sealed for $name {}
@@ -33,8 +57,19 @@ mod keys {
             impl NotifierKey for $name {
                 type Interrupt = $interrupt_type;
 
-                fn interrupt() -> Self::Interrupt {
-                    $interrupt
+                const INTERRUPT: Self::Interrupt = $interrupt;
+
+                fn take_transfer_error() -> bool {
+                    // Safety
+                    // Reading the status and clearing our own channels error flag does not
+                    // interfere with any of the other channels
+                    let dma = unsafe { &*hal::stm32::DMA1::ptr() };
+                    if dma.isr.read().$teif().bit_is_clear() {
+                        return false;
+                    }
+
+                    dma.ifcr.write(|w| w.$cteif().set_bit());
+                    true
                 }
             }
         };
@@ -45,28 +80,44 @@ mod keys {
     pub trait NotifierKey: crate::sealed::Sealed {
         type Interrupt: cortex_m::interrupt::InterruptNumber;
 
-        fn interrupt() -> Self::Interrupt;
+        /// The Interrupt of the DMA-Channel belonging to this Key
+        const INTERRUPT: Self::Interrupt;
+
+        fn interrupt() -> Self::Interrupt {
+            Self::INTERRUPT
+        }
+
+        /// Checks and clears the transfer-error flag of the DMA-Channel belonging to this Key
+        fn take_transfer_error() -> bool;
     }
 
     key!(
         Tx1Key,
         hal::stm32::Interrupt,
-        hal::stm32::Interrupt::DMA1_CH4
+        hal::stm32::Interrupt::DMA1_CH4,
+        teif4,
+        cteif4
     );
     key!(
         Rx1Key,
         hal::stm32::Interrupt,
-        hal::stm32::Interrupt::DMA1_CH5
+        hal::stm32::Interrupt::DMA1_CH5,
+        teif5,
+        cteif5
     );
     key!(
         Tx2Key,
         hal::stm32::Interrupt,
-        hal::stm32::Interrupt::DMA1_CH7
+        hal::stm32::Interrupt::DMA1_CH7,
+        teif7,
+        cteif7
     );
     key!(
         Rx2Key,
         hal::stm32::Interrupt,
-        hal::stm32::Interrupt::DMA1_CH6
+        hal::stm32::Interrupt::DMA1_CH6,
+        teif6,
+        cteif6
     );
 }
 pub use keys::*;
@@ -74,62 +125,178 @@ pub use keys::*;
 mod notifier {
     use super::*;
 
-    /// This Notifier is needed to get the async part working.
+    /// This Notifier is needed to get the async part working. It only adds the masking of the
+    /// interrupt of its [`NotifierKey`] to the [`TransferNotifier`].
     pub struct SerialNotifier<KEY> {
-        waker: NoInterruptMutex<Option<Waker>>,
-        complete: AtomicBool,
+        inner: TransferNotifier,
         _key: PhantomData<KEY>,
     }
 
-    macro_rules! notifier {
-        ($key:ty) => {
-            impl SerialNotifier<$key> {
-                pub const fn new() -> Self {
-                    Self {
-                        waker: NoInterruptMutex::new(None),
-                        complete: AtomicBool::new(false),
-                        _key: PhantomData {},
-                    }
-                }
+    impl<KEY> SerialNotifier<KEY>
+    where
+        KEY: NotifierKey,
+    {
+        /// Creates a new Notifier, which can be used in a `static` for any [`NotifierKey`]
+        pub const fn new() -> Self {
+            Self {
+                inner: TransferNotifier::new(),
+                _key: PhantomData {},
             }
-        };
+        }
     }
 
-    notifier!(Tx1Key);
-    notifier!(Rx1Key);
-    notifier!(Tx2Key);
-    notifier!(Rx2Key);
-
     impl<KEY> SerialNotifier<KEY>
     where
         KEY: NotifierKey,
     {
-        pub(crate) fn set_waker(&self, waker: Waker) {
-            self.waker.with_lock(|mut w| {
-                *w = Some(waker);
-            });
+        pub(crate) fn set_waker(&self, waker: &Waker) {
+            self.inner.register(waker);
         }
 
         pub(crate) fn start_transfer(&self) {
-            self.complete.store(false, atomic::Ordering::SeqCst);
+            self.inner.start_transfer();
+        }
+
+        pub(crate) fn status(&self) -> TransferStatus {
+            self.inner.status()
         }
 
         pub fn transfer_complete(&self) {
-            self.complete.store(true, atomic::Ordering::SeqCst);
+            self.inner.complete();
+            cortex_m::peripheral::NVIC::mask(KEY::interrupt());
+        }
 
-            self.waker.with_lock(|waker| {
-                if let Some(waker) = waker.as_ref() {
-                    waker.wake_by_ref();
+        /// Marks the current transfer as failed, which makes the waiting Future resolve to an
+        /// Error
+        pub fn transfer_error(&self) {
+            self.inner.fail();
+            cortex_m::peripheral::NVIC::mask(KEY::interrupt());
+        }
+    }
+}
+pub use notifier::*;
+
+#[doc(hidden)]
+pub use hal::stm32::interrupt;
+
+/// Generates the Notifiers and the matching interrupt handlers for the given USART.
+///
+/// The Notifiers are placed in a module named after the USART, as `TX` and `RX`, and can then be
+/// passed to [`Serial::new`]. The given Interrupts are checked at compile time against the
+/// DMA-Channels of the USART, so swapping the tx and rx Interrupts or using the Interrupts of a
+/// different USART fails to compile.
+///
+/// # Example
+/// ```rust,ignore
+/// utils::serial_interrupts!(USART2 => { tx: DMA1_CH7, rx: DMA1_CH6 });
+///
+/// let serial = utils::serial::Serial::<utils::serial::USART2>::new(
+///     tx,
+///     rx,
+///     (channels.7, channels.6),
+///     (tx_frame, rx_frame),
+///     (&USART2::TX, &USART2::RX),
+/// );
+/// ```
+///
+/// Crossing the channels is rejected
+/// ```rust,ignore
+/// utils::serial_interrupts!(USART2 => { tx: DMA1_CH6, rx: DMA1_CH7 });
+/// ```
+#[macro_export]
+macro_rules! serial_interrupts {
+    ($usart:ident => { tx: $tx_int:ident, rx: $rx_int:ident }) => {
+        #[allow(non_snake_case)]
+        pub mod $usart {
+            use $crate::serial::interrupt;
+
+            type TxKey = <<$crate::serial::$usart as $crate::serial::SerialKey>::Tx as $crate::serial::DmaTx>::Key;
+            type RxKey = <<$crate::serial::$usart as $crate::serial::SerialKey>::Rx as $crate::serial::DmaRx>::Key;
+
+            const _: () = assert!(
+                <TxKey as $crate::serial::NotifierKey>::INTERRUPT as u16
+                    == interrupt::$tx_int as u16,
+                concat!(
+                    stringify!($tx_int),
+                    " is not the tx interrupt of ",
+                    stringify!($usart)
+                )
+            );
+            const _: () = assert!(
+                <RxKey as $crate::serial::NotifierKey>::INTERRUPT as u16
+                    == interrupt::$rx_int as u16,
+                concat!(
+                    stringify!($rx_int),
+                    " is not the rx interrupt of ",
+                    stringify!($usart)
+                )
+            );
+
+            pub static TX: $crate::serial::SerialNotifier<TxKey> =
+                $crate::serial::SerialNotifier::<TxKey>::new();
+            pub static RX: $crate::serial::SerialNotifier<RxKey> =
+                $crate::serial::SerialNotifier::<RxKey>::new();
+
+            #[interrupt]
+            fn $tx_int() {
+                if <TxKey as $crate::serial::NotifierKey>::take_transfer_error() {
+                    TX.transfer_error();
+                } else {
+                    TX.transfer_complete();
+                }
+            }
+            #[interrupt]
+            fn $rx_int() {
+                if <RxKey as $crate::serial::NotifierKey>::take_transfer_error() {
+                    RX.transfer_error();
                 } else {
-                    // panic!("WHAT")
+                    RX.transfer_complete();
                 }
-            });
+            }
+        }
+    };
+}
 
-            cortex_m::peripheral::NVIC::mask(KEY::interrupt());
+/// A statically allocated Pool of `K` DMA-Frames of size `N`, which hands out every Frame once.
+///
+/// # Example
+/// ```rust,ignore
+/// static FRAMES: FramePool<256, 2> = FramePool::new();
+///
+/// let tx_frame = FRAMES.take().unwrap();
+/// let rx_frame = FRAMES.take().unwrap();
+/// ```
+pub struct FramePool<const N: usize, const K: usize> {
+    frames: StaticPool<hal::dma::DMAFrame<N>, K>,
+}
+
+impl<const N: usize, const K: usize> FramePool<N, K> {
+    const FRAME: hal::dma::DMAFrame<N> = hal::dma::DMAFrame::new();
+
+    /// Creates a new Pool with `K` empty Frames
+    pub const fn new() -> Self {
+        Self {
+            frames: StaticPool::new([Self::FRAME; K]),
         }
     }
+
+    /// Takes a Frame, that was not handed out before, from the Pool
+    #[allow(clippy::mut_from_ref)]
+    pub fn take(&'static self) -> Option<&'static mut hal::dma::DMAFrame<N>> {
+        self.frames.take()
+    }
+
+    /// The number of Frames that can still be taken from the Pool
+    pub fn available(&self) -> usize {
+        self.frames.available()
+    }
+}
+
+impl<const N: usize, const K: usize> Default for FramePool<N, K> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
-pub use notifier::*;
 
 /// Defines some general functions needed for every DMA-Channel. This trait adds nothing new
 /// directly, but allows for more generic code.
@@ -298,13 +465,8 @@ struct SerialTx<TARGET>
 where
     TARGET: DmaTx,
 {
-    tx: hal::dma::FrameSender<
-        &'static mut hal::dma::DMAFrame<256>,
-        hal::dma::TxDma<TARGET, TARGET::Channel>,
-        256,
-    >,
-    tx_buffer: Option<&'static mut hal::dma::DMAFrame<256>>,
-    notifier: &'static SerialNotifier<TARGET::Key>,
+    channel: TxDmaChannel<TARGET>,
+    tx_buffer: BufferSlot<&'static mut hal::dma::DMAFrame<256>>,
 }
 
 struct SerialRx<TARGET>
@@ -331,28 +493,30 @@ where
         tx.channel.listen(hal::dma::Event::TransferComplete);
 
         Self {
-            tx: TARGET::frame_tx(tx),
-            tx_buffer: Some(tx_buffer),
-            notifier,
+            channel: TxDmaChannel {
+                tx: TARGET::frame_tx(tx),
+                notifier,
+            },
+            tx_buffer: BufferSlot::new(tx_buffer),
         }
     }
 
-    pub fn write(&mut self, src: &[u8; 256]) -> TxFuture<'_, TARGET, hal::stm32::Interrupt> {
-        // Prepare the Buffer
-        let buffer = self.tx_buffer.take().expect("");
-        {
-            let target = buffer.write();
-            target.copy_from_slice(src);
-            buffer.commit(256);
-        }
+    /// Starts writing the given Data.
+    ///
+    /// If the Buffer is still used by a previous transfer, the returned Future waits for that
+    /// transfer to finish before starting the new one
+    pub fn write(&mut self, src: &[u8; 256]) -> TxFuture<'_, TARGET> {
+        TxTransfer::new(&mut self.channel, &mut self.tx_buffer, src)
+    }
+}
 
-        TxFuture {
-            tx: &mut self.tx,
-            target_buffer: &mut self.tx_buffer,
-            notifier: self.notifier,
-            interrupt: Tx2Key::interrupt(),
-            state: TxState::Initial { data: buffer },
-        }
+impl<const N: usize> CommitFrame for hal::dma::DMAFrame<N> {
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn commit_unchecked(&mut self, len: usize) {
+        self.commit(len);
     }
 }
 
@@ -382,93 +546,63 @@ where
     }
 }
 
-/// The Future is used to send a full buffer of data over the serial interface
-pub struct TxFuture<'t, Tx, IT>
+/// The DMA-Channel used to send Frames, together with the Notifier of its interrupt
+pub struct TxDmaChannel<Tx>
 where
     Tx: DmaTx + 'static,
 {
-    notifier: &'static SerialNotifier<Tx::Key>,
-    target_buffer: &'t mut Option<&'static mut hal::dma::DMAFrame<256>>,
-    tx: &'t mut hal::dma::FrameSender<
+    tx: hal::dma::FrameSender<
         &'static mut hal::dma::DMAFrame<256>,
         hal::dma::TxDma<Tx, Tx::Channel>,
         256,
     >,
-    interrupt: IT,
-    state: TxState,
-}
-enum TxState {
-    Initial {
-        data: &'static mut hal::dma::DMAFrame<256>,
-    },
-    SendAndWaiting,
-    Done,
+    notifier: &'static SerialNotifier<Tx::Key>,
 }
 
-impl<'t, Tx, IT> Future for TxFuture<'t, Tx, IT>
+impl<Tx> TxChannel for TxDmaChannel<Tx>
 where
     Tx: DmaTx,
-    hal::dma::TxDma<Tx, Tx::Channel>: hal::dma::TransferPayload,
-    IT: InterruptNumber + Unpin,
 {
-    type Output = ();
-
-    fn poll(
-        mut self: core::pin::Pin<&mut Self>,
-        cx: &mut core::task::Context<'_>,
-    ) -> core::task::Poll<Self::Output> {
-        self.notifier.set_waker(cx.waker().clone());
+    type Buffer = &'static mut hal::dma::DMAFrame<256>;
 
-        unsafe {
-            cortex_m::peripheral::NVIC::unmask(self.interrupt);
-        }
-
-        match core::mem::replace(&mut self.state, TxState::Done) {
-            TxState::Initial { data } => {
-                self.notifier.start_transfer();
-                match Tx::send_buffer(self.tx, data) {
-                    Ok(_) => {
-                        self.state = TxState::SendAndWaiting;
-
-                        // cx.waker().wake_by_ref();
-
-                        core::task::Poll::Pending
-                    }
-                    Err(data) => {
-                        self.state = TxState::Initial { data };
+    fn fill(buffer: Self::Buffer, src: &[u8; 256]) -> Self::Buffer {
+        let target = buffer.write();
+        target.copy_from_slice(src);
+        checked_commit(buffer, src.len()).expect("The Source has the same Size as the Frame");
 
-                        cx.waker().wake_by_ref();
+        buffer
+    }
 
-                        core::task::Poll::Pending
-                    }
-                }
-            }
-            TxState::SendAndWaiting => match Tx::transfer_complete(self.tx) {
-                Some(buffer) => {
-                    *self.target_buffer = Some(buffer);
+    fn send(&mut self, buffer: Self::Buffer) -> Result<(), Self::Buffer> {
+        Tx::send_buffer(&mut self.tx, buffer)
+    }
 
-                    self.state = TxState::Done;
+    fn transfer_complete(&mut self) -> Option<Self::Buffer> {
+        Tx::transfer_complete(&mut self.tx)
+    }
 
-                    // assert!(self.notifier.complete.load(atomic::Ordering::SeqCst));
+    fn register(&self, waker: &Waker) {
+        self.notifier.set_waker(waker);
+    }
 
-                    core::task::Poll::Ready(())
-                }
-                None => {
-                    self.state = TxState::SendAndWaiting;
+    fn start_transfer(&self) {
+        self.notifier.start_transfer();
+    }
 
-                    cx.waker().wake_by_ref();
+    fn status(&self) -> TransferStatus {
+        self.notifier.status()
+    }
 
-                    core::task::Poll::Pending
-                }
-            },
-            TxState::Done => {
-                self.state = TxState::Done;
-                core::task::Poll::Ready(())
-            }
+    fn unmask(&self) {
+        unsafe {
+            cortex_m::peripheral::NVIC::unmask(Tx::Key::interrupt());
         }
     }
 }
 
+/// The Future is used to send a full buffer of data over the serial interface
+pub type TxFuture<'t, Tx> = TxTransfer<'t, TxDmaChannel<Tx>>;
+
 /// The Future is used to receive a full buffer of data over the serial interface
 pub struct RxFuture<'t, Rx, IT>
 where
@@ -483,7 +617,42 @@ where
     interrupt: IT,
 }
 
+impl<'t, Rx, IT> RxFuture<'t, Rx, IT>
+where
+    Rx: DmaRx + 'static,
+    IT: cortex_m::interrupt::InterruptNumber,
+{
+    /// Drives the transfer. If the transfer failed, the Error is cleared, so the next time this
+    /// is polled waits for the next Frame
+    fn poll_transfer(
+        &mut self,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<[u8; 256], TransferError>> {
+        if self.notifier.status() == TransferStatus::Failed {
+            self.notifier.start_transfer();
+            return core::task::Poll::Ready(Err(TransferError));
+        }
+
+        todo!()
+    }
+}
+
 impl<'t, Rx, IT> Future for RxFuture<'t, Rx, IT>
+where
+    Rx: DmaRx + 'static,
+    IT: cortex_m::interrupt::InterruptNumber + Unpin,
+{
+    type Output = Result<[u8; 256], TransferError>;
+
+    fn poll(
+        mut self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Self::Output> {
+        self.poll_transfer(cx)
+    }
+}
+
+impl<'t, Rx, IT> Future for RetryTransfer<RxFuture<'t, Rx, IT>>
 where
     Rx: DmaRx + 'static,
     IT: cortex_m::interrupt::InterruptNumber + Unpin,
@@ -494,7 +663,14 @@ where
         mut self: core::pin::Pin<&mut Self>,
         cx: &mut core::task::Context<'_>,
     ) -> core::task::Poll<Self::Output> {
-        todo!()
+        match self.inner.poll_transfer(cx) {
+            core::task::Poll::Ready(Ok(frame)) => core::task::Poll::Ready(frame),
+            core::task::Poll::Ready(Err(TransferError)) => {
+                cx.waker().wake_by_ref();
+                core::task::Poll::Pending
+            }
+            core::task::Poll::Pending => core::task::Poll::Pending,
+        }
     }
 }
 
@@ -563,6 +739,99 @@ where
 
         Self { tx, rx }
     }
+
+    /// Reads a single Frame, resolving to an Error if the DMA transfer failed
+    pub fn try_read(&mut self) -> RxFuture<'_, SK::Rx, hal::stm32::Interrupt> {
+        self.rx.read()
+    }
+
+    /// Writes a single Frame, resolving to an Error if the DMA transfer failed
+    pub fn try_write(&mut self, buffer: [u8; 256]) -> TxFuture<'_, SK::Tx> {
+        self.tx.write(&buffer)
+    }
+
+    /// Splits the Serial into its transmitting and receiving Half, which can be used
+    /// independently from each other, like by a logging Task and a protocol Task.
+    ///
+    /// This is sound, because both Halves use their own DMA-Channel, Buffer and Notifier.
+    pub fn split(self) -> (SerialTxHandle<SK::Tx>, SerialRxHandle<SK::Rx>) {
+        (
+            SerialTxHandle { tx: self.tx },
+            SerialRxHandle { rx: self.rx },
+        )
+    }
+}
+
+/// Only needs to compile, to make sure that the Halves can be owned by separate Tasks. This is
+/// not a Test, as this Module is only built for the Hardware, so it is checked by every build
+/// with the `stm32l432` Feature instead
+#[allow(dead_code)]
+fn split_into_tasks(serial: Serial<USART2>) {
+    fn task<F>(_: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+    }
+
+    let (mut tx, mut rx) = serial.split();
+
+    task(async move {
+        tx.write([0; 256]).await;
+    });
+    task(async move {
+        let _ = rx.read().await;
+    });
+}
+
+/// The transmitting Half of a [`Serial`], see [`Serial::split`]
+pub struct SerialTxHandle<TX>
+where
+    TX: DmaTx,
+{
+    tx: SerialTx<TX>,
+}
+
+impl<TX> SerialTxHandle<TX>
+where
+    TX: DmaTx + 'static,
+    hal::dma::TxDma<TX, TX::Channel>: hal::dma::TransferPayload,
+{
+    /// Writes a single Frame, resolving to an Error if the DMA transfer failed
+    pub fn try_write(&mut self, buffer: [u8; 256]) -> TxFuture<'_, TX> {
+        self.tx.write(&buffer)
+    }
+
+    /// Writes a single Frame, like [`AsyncSerial::write`], which retries failed transfers
+    pub fn write(&mut self, buffer: [u8; 256]) -> RetryTransfer<TxFuture<'_, TX>> {
+        RetryTransfer {
+            inner: self.tx.write(&buffer),
+        }
+    }
+}
+
+/// The receiving Half of a [`Serial`], see [`Serial::split`]
+pub struct SerialRxHandle<RX>
+where
+    RX: DmaRx,
+{
+    rx: SerialRx<RX>,
+}
+
+impl<RX> SerialRxHandle<RX>
+where
+    RX: DmaRx + 'static,
+{
+    /// Reads a single Frame, resolving to an Error if the DMA transfer failed
+    pub fn try_read(&mut self) -> RxFuture<'_, RX, hal::stm32::Interrupt> {
+        self.rx.read()
+    }
+
+    /// Reads a single Frame, like [`AsyncSerial::read`], which retries failed transfers
+    pub fn read(&mut self) -> RetryTransfer<RxFuture<'_, RX, hal::stm32::Interrupt>> {
+        RetryTransfer {
+            inner: self.rx.read(),
+        }
+    }
 }
 
 impl<SK> AsyncSerial<256> for Serial<SK>
@@ -570,20 +839,43 @@ where
     SK: 'static + SerialKey,
     hal::dma::TxDma<SK::Tx, <SK::Tx as DmaTx>::Channel>: hal::dma::TransferPayload,
 {
-    type ReceiveFuture<'t> = RxFuture<'t, SK::Rx, hal::stm32::Interrupt>;
-    type WriteFuture<'t> = TxFuture<'t, SK::Tx, hal::stm32::Interrupt>;
+    type ReceiveFuture<'t> = RetryTransfer<RxFuture<'t, SK::Rx, hal::stm32::Interrupt>>;
+    type WriteFuture<'t> = RetryTransfer<TxFuture<'t, SK::Tx>>;
 
     fn read<'s, 'f>(&'s mut self) -> Self::ReceiveFuture<'f>
     where
         's: 'f,
     {
-        self.rx.read()
+        RetryTransfer {
+            inner: self.rx.read(),
+        }
     }
 
     fn write<'s, 'f>(&'s mut self, buffer: [u8; 256]) -> Self::WriteFuture<'f>
     where
         's: 'f,
     {
-        self.tx.write(&buffer)
+        RetryTransfer {
+            inner: self.tx.write(&buffer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_pool_take() {
+        static FRAMES: FramePool<16, 2> = FramePool::new();
+
+        assert_eq!(2, FRAMES.available());
+
+        let first = FRAMES.take().unwrap();
+        let second = FRAMES.take().unwrap();
+        assert!(!core::ptr::eq(first, second));
+
+        assert_eq!(0, FRAMES.available());
+        assert!(FRAMES.take().is_none());
     }
 }
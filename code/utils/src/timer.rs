@@ -40,16 +40,22 @@ pub mod fixed_size {
     use core::{future::Future, marker::PhantomData, num::NonZeroUsize, task::Waker};
 
     use crate::{
-        atomic::{self, AtomicBool, AtomicIsize, AtomicU8, AtomicUsize},
-        unsafecell::UnsafeCell,
+        atomic::{self, AtomicBool, AtomicIsize, AtomicU32, AtomicUsize},
+        state::{AtomicSlotState, SlotState},
+        UnsafeCell,
     };
 
     /// This is used to configure the Timescale of the Timer and also determines the resolution of
     /// the timers as well as the frequency at which the Timer needs to be updated ([`TimerWheel::tick`]).
     pub trait Timescale {
+        /// The Duration of a single Tick in ms
+        const STEP_MS: usize;
+
         fn scale_ms(time: usize) -> usize;
 
-        fn step_ms() -> usize;
+        fn step_ms() -> usize {
+            Self::STEP_MS
+        }
     }
 
     /// A general Timescale implementation that can be used to easily adjust the Timescale to
@@ -57,6 +63,8 @@ pub mod fixed_size {
     pub struct ScaleGeneral<const N: usize> {}
 
     impl<const N: usize> Timescale for ScaleGeneral<N> {
+        const STEP_MS: usize = N;
+
         fn scale_ms(time: usize) -> usize {
             if time % N == 0 {
                 time / N
@@ -64,10 +72,6 @@ pub mod fixed_size {
                 (time / N) + 1
             }
         }
-
-        fn step_ms() -> usize {
-            N
-        }
     }
 
     pub type Scale1Ms = ScaleGeneral<1>;
@@ -75,22 +79,61 @@ pub mod fixed_size {
     pub type Scale100Ms = ScaleGeneral<100>;
 
     /// The internal Slot used by the TimerWheels
+    ///
+    /// # States
+    /// * `Free`: The Slot is not used by any Timer
+    /// * `Reserving`: The Slot is being claimed for a new Timer and its Waker is being stored
+    /// * `Ready`: The Slot contains the Waker for a Timer, that has not fired yet
+    /// * `Taken`: The Timer fired and the Waker was taken out of the Slot, but the Slot is still
+    ///   owned by the [`TimerHandle`]
+    ///
+    /// The `Reserving` State is also used by the Wheel while it takes the Waker out of the Slot
+    /// to fire the Timer, to make sure the Slot is not released and reused at the same time.
     pub struct Slot {
-        state: AtomicU8,
+        state: AtomicSlotState,
         waker: UnsafeCell<Option<Waker>>,
         fired: AtomicBool,
+        /// The Tick of the Wheel at which the Timer should fire, used to detect stale entries in
+        /// the Wheel, after the Timer was cancelled and the Slot reused
+        deadline: AtomicUsize,
+        /// Incremented every time the Slot is cleared, so the [`TimerHandle`] of a cleared Timer
+        /// can tell that the Slot no longer belongs to it
+        generation: AtomicUsize,
     }
 
     impl Slot {
+        #[cfg(not(loom))]
         const fn new() -> Self {
             Self {
-                state: AtomicU8::new(0),
+                state: AtomicSlotState::new(SlotState::Free),
+                waker: UnsafeCell::new(None),
+                fired: AtomicBool::new(false),
+                deadline: AtomicUsize::new(0),
+                generation: AtomicUsize::new(0),
+            }
+        }
+        #[cfg(loom)]
+        fn new() -> Self {
+            Self {
+                state: AtomicSlotState::new(SlotState::Free),
                 waker: UnsafeCell::new(None),
                 fired: AtomicBool::new(false),
+                deadline: AtomicUsize::new(0),
+                generation: AtomicUsize::new(0),
             }
         }
     }
 
+    // Safety:
+    // The Waker in the Slot is only ever accessed by whoever moved the `state` into `Reserving`,
+    // either when storing a new Waker or when taking it out of the Slot to fire the Timer. As
+    // every Transition is performed using SeqCst Atomics, the accesses by different threads are
+    // always ordered and there is never more than one thread accessing the Waker at a time.
+    // This relies on the `Waker` itself being `Send`, which is guaranteed by its contract, so a
+    // Waker from any executor can be stored in the Slot and be woken from another thread or an
+    // interrupt-handler.
+    unsafe impl Sync for Slot {}
+
     /// A Storage wrapper for a list of Slots, allowing us to easily interact with it for the
     /// TimerWheels
     pub struct SlotStorage<const N: usize> {
@@ -99,7 +142,8 @@ pub mod fixed_size {
     }
 
     impl<const N: usize> SlotStorage<N> {
-        const fn new() -> Self {
+        #[cfg(not(loom))]
+        pub const fn new() -> Self {
             #[allow(clippy::declare_interior_mutable_const)]
             const SLOT: Slot = Slot::new();
 
@@ -108,8 +152,27 @@ pub mod fixed_size {
                 used_slots: AtomicUsize::new(0),
             }
         }
+        #[cfg(loom)]
+        pub fn new() -> Self {
+            Self {
+                wakers: core::array::from_fn(|_| Slot::new()),
+                used_slots: AtomicUsize::new(0),
+            }
+        }
 
+        #[cfg(test)]
         fn add_waker(&self, waker: Waker) -> Result<usize, ()> {
+            let index = self.reserve_waker(waker)?;
+            self.wakers[index].state.set(SlotState::Ready);
+
+            Ok(index)
+        }
+
+        /// Stores the Waker in a free Slot and returns its Index.
+        ///
+        /// Unlike [`add_waker`](Self::add_waker) the Slot is left in the `Reserving` State, so the
+        /// Wheel can set up the Deadline before the Slot is marked as `Ready`.
+        fn reserve_waker(&self, waker: Waker) -> Result<usize, ()> {
             let usage = self.used_slots.fetch_add(1, atomic::Ordering::SeqCst);
             if usage >= N {
                 self.used_slots.fetch_sub(1, atomic::Ordering::SeqCst);
@@ -118,14 +181,13 @@ pub mod fixed_size {
 
             loop {
                 for (index, slot) in self.wakers.iter().enumerate() {
-                    if slot.state.load(atomic::Ordering::Relaxed) != 0 {
+                    if slot.state.load(atomic::Ordering::Relaxed) != SlotState::Free {
                         continue;
                     }
 
-                    if slot
+                    if !slot
                         .state
-                        .compare_exchange(0, 1, atomic::Ordering::SeqCst, atomic::Ordering::SeqCst)
-                        .is_err()
+                        .try_transition(SlotState::Free, SlotState::Reserving)
                     {
                         continue;
                     }
@@ -137,32 +199,126 @@ pub mod fixed_size {
                         *w_ref = Some(waker);
                     });
 
-                    slot.state.store(2, atomic::Ordering::SeqCst);
-
                     return Ok(index);
                 }
             }
         }
 
-        fn take_slot(&self, index: usize) -> Option<(Waker, &AtomicBool)> {
+        /// Creates the Handle for the reserved Slot with the given Index
+        fn handle(&self, index: usize) -> TimerHandle<'_> {
+            let slot = &self.wakers[index];
+
+            TimerHandle::Registered {
+                slot,
+                index,
+                generation: slot.generation.load(atomic::Ordering::SeqCst),
+                used_slots: &self.used_slots,
+            }
+        }
+
+        #[cfg(test)]
+        fn take_slot(&self, index: usize) -> Option<Waker> {
+            self.take_slot_if(index, |_| true)
+        }
+
+        /// Takes the Waker out of the Slot and marks the Timer as fired, if the Slot is `Ready`
+        /// and `check` accepts it.
+        ///
+        /// This all happens while we have exclusive access to the Slot, so the Slot can't be
+        /// released and reused by a different Timer in the mean time.
+        fn take_slot_if<F>(&self, index: usize, check: F) -> Option<Waker>
+        where
+            F: FnOnce(&Slot) -> bool,
+        {
             let slot = self.wakers.get(index)?;
 
-            if slot
+            // Claim exclusive access to the Slot, so the Timer can't be dropped and its Slot be
+            // reused while we take the Waker
+            if !slot
                 .state
-                .compare_exchange(2, 1, atomic::Ordering::SeqCst, atomic::Ordering::SeqCst)
-                .is_err()
+                .try_transition(SlotState::Ready, SlotState::Reserving)
             {
                 return None;
             }
 
+            if !check(slot) {
+                slot.state.set(SlotState::Ready);
+                return None;
+            }
+
             let data = slot.waker.with_mut(|raw_w| {
                 let w_ref = unsafe { &mut *raw_w };
                 w_ref.take()
-            })?;
+            });
+
+            slot.fired.store(true, atomic::Ordering::SeqCst);
+            slot.state.set(SlotState::Taken);
+
+            data
+        }
+
+        /// Frees every used Slot and wakes the Timers, that have not fired yet, so their Futures
+        /// notice that they were cleared
+        fn clear_slots(&self) {
+            for slot in self.wakers.iter() {
+                // Claim exclusive access to the Slot, waiting for anyone currently accessing it
+                loop {
+                    if slot
+                        .state
+                        .try_transition(SlotState::Ready, SlotState::Reserving)
+                        || slot
+                            .state
+                            .try_transition(SlotState::Taken, SlotState::Reserving)
+                    {
+                        break;
+                    }
+                    if slot.state.load(atomic::Ordering::SeqCst) == SlotState::Free {
+                        break;
+                    }
+
+                    atomic::spin_loop();
+                }
+                if slot.state.load(atomic::Ordering::SeqCst) == SlotState::Free {
+                    continue;
+                }
+
+                // The Generation needs to change before the Slot is Free, so the old Handle never
+                // mistakes a new Timer in the Slot for its own
+                slot.generation.fetch_add(1, atomic::Ordering::SeqCst);
+                slot.fired.store(false, atomic::Ordering::SeqCst);
+                let waker = slot.waker.with_mut(|raw_w| {
+                    let w_ref = unsafe { &mut *raw_w };
+                    w_ref.take()
+                });
+                slot.state.set(SlotState::Free);
+
+                self.used_slots.fetch_sub(1, atomic::Ordering::SeqCst);
+
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+        }
 
-            let fired_ref = &slot.fired;
+        /// The number of Slots that are currently used by Timers
+        pub fn used_count(&self) -> usize {
+            self.used_slots.load(atomic::Ordering::SeqCst)
+        }
 
-            Some((data, fired_ref))
+        /// The number of Slots whose Timer already fired, but that have not been released yet.
+        ///
+        /// # Diagnostics
+        /// A Slot is only released once its Timer/Future is dropped, so if this number keeps
+        /// growing, some Timers are likely never polled again after firing and slowly exhaust the
+        /// available Slots.
+        pub fn fired_count(&self) -> usize {
+            self.wakers
+                .iter()
+                .filter(|slot| {
+                    slot.fired.load(atomic::Ordering::SeqCst)
+                        && slot.state.load(atomic::Ordering::SeqCst) == SlotState::Taken
+                })
+                .count()
         }
     }
     impl<const N: usize> AsRef<[Slot]> for SlotStorage<N> {
@@ -170,6 +326,27 @@ pub mod fixed_size {
             &self.wakers
         }
     }
+    impl<const N: usize> Default for SlotStorage<N> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// A Storage for the Wakers of the Timers in a [`Wheel`]
+    pub trait TimerStorage: AsRef<[Slot]> {
+        /// The number of Slots in the Storage
+        const CAPACITY: usize;
+
+        /// Frees all the Slots in the Storage, see [`TimerWheel::clear`]
+        fn clear(&self);
+    }
+    impl<const N: usize> TimerStorage for SlotStorage<N> {
+        const CAPACITY: usize = N;
+
+        fn clear(&self) {
+            self.clear_slots();
+        }
+    }
 
     /// A one level TimerWheel-Storage
     pub struct LevelOneWheel {
@@ -189,6 +366,16 @@ pub mod fixed_size {
 
     /// The general TimerWheel that you, as the consumer of the api, will interact with the most
     /// and perform all your actions through this.
+    ///
+    /// # Wakers
+    /// The TimerWheel accepts any [`Waker`], not just the ones from this crates executor, so it
+    /// can also be used from a different async runtime. The Wakers are woken from whatever
+    /// context calls [`TimerWheel::tick`], usually an interrupt-handler, so waking needs to be
+    /// cheap and must not block.
+    ///
+    /// # Sync
+    /// The TimerWheel is `Sync` as long as its Wheel and Storage are, which is the case for all
+    /// the Wheels and the [`SlotStorage`] provided here.
     pub struct TimerWheel<WHEEL, SCALE>
     where
         WHEEL: Wheel,
@@ -196,84 +383,207 @@ pub mod fixed_size {
     {
         wheel: WHEEL,
         waker: WHEEL::Storage,
+        /// The hardware counter value up to which the Wheel was advanced by
+        /// [`TimerWheel::advance_to`]
+        hw_last: AtomicU32,
         _marker: PhantomData<SCALE>,
     }
 
-    unsafe impl<WHEEL, SCALE> core::marker::Sync for TimerWheel<WHEEL, SCALE>
-    where
-        WHEEL: Wheel,
-        SCALE: Timescale,
-    {
-    }
-
     impl LevelOneWheel {
+        #[cfg(not(loom))]
         const fn new() -> Self {
+            Self::starting_at(0)
+        }
+        #[cfg(loom)]
+        fn new() -> Self {
+            Self::starting_at(0)
+        }
+
+        /// Creates a new Wheel, whose current Tick starts at the given value instead of 0.
+        ///
+        /// The current Tick wraps around once it reaches `usize::MAX`, which is handled
+        /// transparently, so this is mostly useful for testing that wrap-around.
+        #[cfg(not(loom))]
+        pub const fn starting_at(tick: usize) -> Self {
             #[allow(clippy::declare_interior_mutable_const)]
             const SLOT: AtomicIsize = AtomicIsize::new(-1);
 
             Self {
-                current: AtomicUsize::new(0),
+                current: AtomicUsize::new(tick),
                 slots: [SLOT; 32],
             }
         }
+        /// Creates a new Wheel, whose current Tick starts at the given value instead of 0.
+        ///
+        /// The current Tick wraps around once it reaches `usize::MAX`, which is handled
+        /// transparently, so this is mostly useful for testing that wrap-around.
+        #[cfg(loom)]
+        pub fn starting_at(tick: usize) -> Self {
+            Self {
+                current: AtomicUsize::new(tick),
+                slots: core::array::from_fn(|_| AtomicIsize::new(-1)),
+            }
+        }
     }
     impl LevelTwoWheel {
+        #[cfg(not(loom))]
         const fn new() -> Self {
             #[allow(clippy::declare_interior_mutable_const)]
             const WHEEL: LevelOneWheel = LevelOneWheel::new();
 
             Self { slots: [WHEEL; 32] }
         }
+        #[cfg(loom)]
+        fn new() -> Self {
+            Self {
+                slots: core::array::from_fn(|_| LevelOneWheel::new()),
+            }
+        }
     }
     impl LevelThreeWheel {
+        #[cfg(not(loom))]
         const fn new() -> Self {
             #[allow(clippy::declare_interior_mutable_const)]
             const WHEEL: LevelTwoWheel = LevelTwoWheel::new();
 
             Self { slots: [WHEEL; 32] }
         }
+        #[cfg(loom)]
+        fn new() -> Self {
+            Self {
+                slots: core::array::from_fn(|_| LevelTwoWheel::new()),
+            }
+        }
     }
 
     impl<SCALE> TimerWheel<LevelOneWheel, SCALE>
     where
         SCALE: Timescale,
     {
+        #[cfg(not(loom))]
         pub const fn new() -> Self {
-            Self {
-                wheel: LevelOneWheel::new(),
-                waker: SlotStorage::new(),
-                _marker: PhantomData {},
-            }
+            Self::from_parts(LevelOneWheel::new(), SlotStorage::new())
+        }
+        #[cfg(loom)]
+        pub fn new() -> Self {
+            Self::from_parts(LevelOneWheel::new(), SlotStorage::new())
         }
     }
     impl<SCALE> TimerWheel<LevelTwoWheel, SCALE>
     where
         SCALE: Timescale,
     {
+        #[cfg(not(loom))]
         pub const fn new() -> Self {
-            Self {
-                wheel: LevelTwoWheel::new(),
-                waker: SlotStorage::new(),
-                _marker: PhantomData {},
-            }
+            Self::from_parts(LevelTwoWheel::new(), SlotStorage::new())
+        }
+        #[cfg(loom)]
+        pub fn new() -> Self {
+            Self::from_parts(LevelTwoWheel::new(), SlotStorage::new())
         }
     }
 
     pub enum TimerHandle<'t> {
         Registered {
             slot: &'t Slot,
+            /// The Index of the Slot in the Storage
+            index: usize,
+            /// The Generation of the Slot, when the Timer was registered
+            generation: usize,
             used_slots: &'t AtomicUsize,
         },
         Fired,
     }
 
+    impl<'t> TimerHandle<'t> {
+        /// Atomically checks if the Timer fired and clears the fired state, so the Timer can be
+        /// re-armed using [`TimerWheel::rearm_ms`] and the next firing can be observed.
+        ///
+        /// A Handle for a Timer, that fired immediately, has no Slot and therefore always
+        /// reports that it fired.
+        ///
+        /// A Timer, that was cleared using [`TimerWheel::clear`], never reports that it fired.
+        pub fn take_fired(&self) -> bool {
+            match self {
+                Self::Registered { slot, .. } => {
+                    if self.is_cleared() {
+                        return false;
+                    }
+
+                    let fired = slot.fired.swap(false, atomic::Ordering::SeqCst);
+                    // The Slot might have been cleared and reused in the mean time, in which case
+                    // the fired state belongs to the new Timer
+                    if self.is_cleared() {
+                        if fired {
+                            slot.fired.store(true, atomic::Ordering::SeqCst);
+                        }
+                        return false;
+                    }
+
+                    fired
+                }
+                Self::Fired => true,
+            }
+        }
+
+        /// Checks if the Timer was removed using [`TimerWheel::clear`], after which its Slot no
+        /// longer belongs to this Handle
+        pub fn is_cleared(&self) -> bool {
+            match self {
+                Self::Registered {
+                    slot, generation, ..
+                } => slot.generation.load(atomic::Ordering::SeqCst) != *generation,
+                Self::Fired => false,
+            }
+        }
+
+        /// Cancels the Timer, so it will not fire anymore and its Slot can be reused by another
+        /// Timer. This is the same as dropping the Handle, but makes the intent more explicit.
+        pub fn cancel(self) {
+            drop(self);
+        }
+    }
+
     impl<'t> Drop for TimerHandle<'t> {
         fn drop(&mut self) {
             match self {
-                Self::Registered { slot, used_slots } => {
-                    slot.state.store(0, atomic::Ordering::SeqCst);
-                    slot.fired.store(false, atomic::Ordering::SeqCst);
+                Self::Registered {
+                    slot,
+                    used_slots,
+                    generation,
+                    ..
+                } => {
+                    // The Wheel might be taking the Waker out of the Slot right now, in which
+                    // case we need to wait for it to finish before the Slot can be released
+                    let previous = loop {
+                        // A cleared Slot was already released and might be used by another Timer
+                        if slot.generation.load(atomic::Ordering::SeqCst) != *generation {
+                            return;
+                        }
+
+                        if slot
+                            .state
+                            .try_transition(SlotState::Ready, SlotState::Reserving)
+                        {
+                            break SlotState::Ready;
+                        }
+                        if slot
+                            .state
+                            .try_transition(SlotState::Taken, SlotState::Reserving)
+                        {
+                            break SlotState::Taken;
+                        }
 
+                        atomic::spin_loop();
+                    };
+
+                    // The Slot was cleared and reused, before we got exclusive access to it
+                    if slot.generation.load(atomic::Ordering::SeqCst) != *generation {
+                        slot.state.set(previous);
+                        return;
+                    }
+
+                    slot.state.set(SlotState::Free);
                     used_slots.fetch_sub(1, atomic::Ordering::SeqCst);
                 }
                 Self::Fired => {}
@@ -294,7 +604,13 @@ pub mod fixed_size {
 
     /// A generic Trait allowing you to implement your own Wheel-Backend for the Timer
     pub trait Wheel {
-        type Storage: AsRef<[Slot]>;
+        /// The number of Slots in the Wheel, the Storage needs to have the same capacity
+        const SLOTS: usize;
+
+        /// The most Ticks a Timer can be registered in the future, see [`max_delay_ms`]
+        const MAX_STEPS: usize = Self::SLOTS - 1;
+
+        type Storage: TimerStorage;
 
         /// Move the current Slot in the Wheel along by one step
         fn tick(&self, storage: &Self::Storage);
@@ -306,37 +622,102 @@ pub mod fixed_size {
             waker: Waker,
             storage: &'t Self::Storage,
         ) -> Result<TimerHandle<'t>, WheelAddError>;
+
+        /// Inserts the already filled Slot with the given Index in the Storage `step` slots after
+        /// the current slot again, used to re-arm a Timer without allocating a new Slot.
+        ///
+        /// The Slot is `Reserving` when this is called and the Wheel is responsible for marking
+        /// it as `Ready`, once it is safe to fire the Timer.
+        fn rearm_step(
+            &self,
+            steps: NonZeroUsize,
+            index: usize,
+            storage: &Self::Storage,
+        ) -> Result<(), WheelAddError> {
+            let _ = (steps, index, storage);
+            Err(WheelAddError::Other(
+                "Re-arming is not supported by this Wheel",
+            ))
+        }
+
+        /// Removes all the Timers from the Wheel, see [`TimerWheel::clear`].
+        ///
+        /// Wheels that keep track of their Timers need to override this, the default does
+        /// nothing.
+        fn clear(&self) {}
+    }
+
+    /// The longest Sleep in ms, that can be registered on a [`TimerWheel`] with the given Wheel
+    /// and Timescale. Longer Sleeps fail with [`WheelAddError::OutOfRange`].
+    ///
+    /// This allows for checking at compile time, that the longest Timer fits into the chosen
+    /// Configuration. The [`LevelTwoWheel`] can't hold any Timers yet, so the Wheel is not
+    /// picked automatically and the check is a plain const assert on the [`LevelOneWheel`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use utils::timer::fixed_size::*;
+    /// // A Timeout of 300ms fits into a single Level at a 10ms Timescale
+    /// const _: () = assert!(300 <= max_delay_ms::<LevelOneWheel, Scale10Ms>());
+    /// ```
+    pub const fn max_delay_ms<WHEEL, SCALE>() -> usize
+    where
+        WHEEL: Wheel,
+        SCALE: Timescale,
+    {
+        WHEEL::MAX_STEPS * SCALE::STEP_MS
     }
 
     impl Wheel for LevelOneWheel {
+        const SLOTS: usize = 32;
+
         type Storage = SlotStorage<32>;
 
         fn tick(&self, storage: &Self::Storage) {
-            let index = (self.current.fetch_add(1, atomic::Ordering::SeqCst) + 1) % 32;
+            // # Orderings
+            // This runs in an interrupt-handler on every Tick, so only the fence below is SeqCst.
+            // The Tick and the Slot are a Store-Buffer pattern with `rearm_step`, which only
+            // needs the fences on both sides, so the accesses themselves can be Relaxed.
+            let now = self
+                .current
+                .fetch_add(1, atomic::Ordering::Relaxed)
+                .wrapping_add(1);
+            let index = now % 32;
+
+            // Pairs with the fence in `rearm_step`, so that either we see the newly inserted
+            // Timer or the inserting thread sees our new Tick and fires the Timer itself
+            atomic::fence(atomic::Ordering::SeqCst);
 
             let slot = &self.slots[index];
 
-            let waker_index = match slot.load(atomic::Ordering::SeqCst) {
+            // This is only a hint, the Slot is actually claimed by the compare_exchange
+            let waker_index = match slot.load(atomic::Ordering::Relaxed) {
                 id if id < 0 => return,
                 id => id as usize,
             };
 
+            // Acquires the Deadline, that was stored before the Timer was inserted
             if slot
                 .compare_exchange(
                     waker_index as isize,
                     -1,
-                    atomic::Ordering::SeqCst,
-                    atomic::Ordering::SeqCst,
+                    atomic::Ordering::AcqRel,
+                    atomic::Ordering::Relaxed,
                 )
                 .is_err()
             {
                 return;
             }
 
-            let (waker, fired) = storage.take_slot(waker_index).unwrap();
-            fired.store(true, atomic::Ordering::SeqCst);
-
-            waker.wake();
+            // The Timer might have been dropped in the mean time, in which case there is nothing
+            // left to wake, or its Slot might have been reused by a Timer with a different
+            // deadline, which will be fired by its own entry in the Wheel
+            let expired = storage.take_slot_if(waker_index, |slot| {
+                slot.deadline.load(atomic::Ordering::SeqCst) == now
+            });
+            if let Some(waker) = expired {
+                waker.wake();
+            }
         }
         fn add_step<'t>(
             &self,
@@ -348,36 +729,105 @@ pub mod fixed_size {
                 return Err(WheelAddError::OutOfRange);
             }
 
-            let waker_index = storage.add_waker(waker).map_err(|_| WheelAddError::Full)? as isize;
+            let waker_index = storage
+                .reserve_waker(waker)
+                .map_err(|_| WheelAddError::Full)?;
+            let handle = storage.handle(waker_index);
+
+            // If the Timer could not be inserted, the Handle is dropped and releases the Slot again
+            self.rearm_step(time, waker_index, storage).map(|()| handle)
+        }
+
+        fn rearm_step(
+            &self,
+            time: NonZeroUsize,
+            index: usize,
+            storage: &Self::Storage,
+        ) -> Result<(), WheelAddError> {
+            if time.get() >= 32 {
+                return Err(WheelAddError::OutOfRange);
+            }
 
-            for i in 0..31 {
-                let slot_index =
-                    (self.current.load(atomic::Ordering::SeqCst) + time.get() + i) % 32;
+            // All the Deadlines are relative to a single snapshot of the current Tick, so a
+            // concurrent tick can't shift the Timer by more than the Tick it already counts for.
+            // A stale snapshot is caught by the check after inserting the Timer, like any other
+            // concurrent tick, so this can be Relaxed.
+            let current = self.current.load(atomic::Ordering::Relaxed);
+
+            // The Deadline needs to be set before the Slot is Ready, otherwise a stale entry for
+            // the Slot, from a cancelled Timer, could fire it with the old Deadline. It is
+            // published by marking the Slot as Ready.
+            let waker_slot = &storage.wakers[index];
+            waker_slot
+                .deadline
+                .store(current.wrapping_add(time.get()), atomic::Ordering::Relaxed);
+            waker_slot.state.set(SlotState::Ready);
+
+            let waker_index = index as isize;
+            // The Timer needs to stay within a single rotation of the Wheel, otherwise it would
+            // be found by a tick with a different Deadline and never fire
+            for steps in time.get()..32 {
+                let deadline = current.wrapping_add(steps);
+                let slot_index = deadline % 32;
+
+                waker_slot
+                    .deadline
+                    .store(deadline, atomic::Ordering::Relaxed);
 
                 let slot = &self.slots[slot_index];
 
+                // Releases the Deadline to the tick, that claims the Timer
                 if slot
                     .compare_exchange(
                         -1,
                         waker_index,
-                        atomic::Ordering::SeqCst,
-                        atomic::Ordering::SeqCst,
+                        atomic::Ordering::Release,
+                        atomic::Ordering::Relaxed,
                     )
-                    .is_ok()
+                    .is_err()
                 {
-                    return Ok(TimerHandle::Registered {
-                        slot: &storage.wakers[waker_index as usize],
-                        used_slots: &storage.used_slots,
+                    continue;
+                }
+
+                // The Wheel might have already moved past the Deadline, while we were inserting
+                // the Timer, in which case it would only be found again after a full rotation
+                // and we need to fire it ourselves
+                atomic::fence(atomic::Ordering::SeqCst);
+                let now = self.current.load(atomic::Ordering::Relaxed);
+                if now.wrapping_sub(current) >= steps {
+                    // Whoever takes the Slot first fires the Timer, which is decided by the
+                    // Slot-State, so this only needs to remove the entry
+                    let _ = slot.compare_exchange(
+                        waker_index,
+                        -1,
+                        atomic::Ordering::Relaxed,
+                        atomic::Ordering::Relaxed,
+                    );
+
+                    let expired = storage.take_slot_if(index, |slot| {
+                        slot.deadline.load(atomic::Ordering::SeqCst) == deadline
                     });
+                    if let Some(waker) = expired {
+                        waker.wake();
+                    }
                 }
+
+                return Ok(());
             }
 
             Err(WheelAddError::Full)
         }
+
+        fn clear(&self) {
+            for slot in self.slots.iter() {
+                slot.store(-1, atomic::Ordering::SeqCst);
+            }
+        }
     }
 
     impl Wheel for LevelTwoWheel {
-        // 32 * 32 = 1024
+        const SLOTS: usize = 32 * 32;
+
         type Storage = SlotStorage<1024>;
 
         fn tick(&self, storage: &Self::Storage) {
@@ -391,6 +841,12 @@ pub mod fixed_size {
         ) -> Result<TimerHandle<'t>, WheelAddError> {
             todo!("Add")
         }
+
+        fn clear(&self) {
+            for wheel in self.slots.iter() {
+                wheel.clear();
+            }
+        }
     }
 
     impl<WHEEL, SCALE> TimerWheel<WHEEL, SCALE>
@@ -398,10 +854,157 @@ pub mod fixed_size {
         WHEEL: Wheel,
         SCALE: Timescale,
     {
+        const STORAGE_CHECK: () = assert!(
+            WHEEL::SLOTS == <WHEEL::Storage as TimerStorage>::CAPACITY,
+            "The Storage needs to have exactly as many Slots as the Wheel"
+        );
+
+        /// Creates a new TimerWheel from the Wheel and its Storage.
+        ///
+        /// # Capacity
+        /// The capacity of the Storage needs to match the number of Slots in the Wheel, otherwise
+        /// this will fail to compile.
+        /// ```rust,compile_fail
+        /// # use core::{num::NonZeroUsize, task::Waker};
+        /// # use utils::timer::fixed_size::*;
+        /// struct SmallWheel;
+        /// impl Wheel for SmallWheel {
+        ///     const SLOTS: usize = 32;
+        ///     type Storage = SlotStorage<16>;
+        ///
+        ///     fn tick(&self, _: &Self::Storage) {}
+        ///     fn add_step<'t>(
+        ///         &self,
+        ///         _: NonZeroUsize,
+        ///         _: Waker,
+        ///         _: &'t Self::Storage,
+        ///     ) -> Result<TimerHandle<'t>, WheelAddError> {
+        ///         Err(WheelAddError::Full)
+        ///     }
+        /// }
+        ///
+        /// static TIMER: TimerWheel<SmallWheel, Scale1Ms> =
+        ///     TimerWheel::from_parts(SmallWheel, SlotStorage::new());
+        /// ```
+        #[cfg(not(loom))]
+        pub const fn from_parts(wheel: WHEEL, storage: WHEEL::Storage) -> Self {
+            #[allow(clippy::let_unit_value)]
+            let _ = Self::STORAGE_CHECK;
+
+            Self {
+                wheel,
+                waker: storage,
+                hw_last: AtomicU32::new(0),
+                _marker: PhantomData {},
+            }
+        }
+        /// Creates a new TimerWheel from the Wheel and its Storage.
+        #[cfg(loom)]
+        pub fn from_parts(wheel: WHEEL, storage: WHEEL::Storage) -> Self {
+            #[allow(clippy::let_unit_value)]
+            let _ = Self::STORAGE_CHECK;
+
+            Self {
+                wheel,
+                waker: storage,
+                hw_last: AtomicU32::new(0),
+                _marker: PhantomData {},
+            }
+        }
+
         pub fn tick(&self) {
             self.wheel.tick(&self.waker);
         }
 
+        /// Performs `n` Ticks at once, like calling [`TimerWheel::tick`] `n` times
+        pub fn tick_n(&self, n: usize) {
+            for _ in 0..n {
+                self.tick();
+            }
+        }
+
+        /// Advances the Wheel to the given value of a free-running hardware counter, which counts
+        /// in ms and starts at 0, by performing as many Ticks as full Timescale steps elapsed
+        /// since the last call.
+        ///
+        /// This keeps the Wheel in sync with the hardware, even if some interrupts were missed or
+        /// coalesced, instead of falling behind by one Tick for every missed interrupt. Any
+        /// remaining partial step is carried over to the next call.
+        ///
+        /// # Wrap-Around
+        /// The counter is allowed to wrap around, as long as this is called at least once every
+        /// `u32::MAX / 2` ms. A value that is behind the last seen one, like one read by a racing
+        /// caller before the Wheel was advanced, saturates to 0 Ticks.
+        pub fn advance_to(&self, hw_now: u32) {
+            let step = SCALE::step_ms() as u32;
+
+            let mut last = self.hw_last.load(atomic::Ordering::SeqCst);
+            loop {
+                let elapsed = hw_now.wrapping_sub(last);
+                if elapsed > u32::MAX / 2 {
+                    return;
+                }
+
+                let ticks = elapsed / step;
+                if ticks == 0 {
+                    return;
+                }
+
+                // Only the caller that moves the last seen value forward performs the Ticks, so
+                // concurrent callers never tick for the same steps twice
+                match self.hw_last.compare_exchange(
+                    last,
+                    last.wrapping_add(ticks * step),
+                    atomic::Ordering::SeqCst,
+                    atomic::Ordering::SeqCst,
+                ) {
+                    Ok(_) => {
+                        self.tick_n(ticks as usize);
+                        return;
+                    }
+                    Err(current) => last = current,
+                }
+            }
+        }
+
+        /// Returns a future that will resolve after around `time` milliseconds, like `sleep_ms`,
+        /// but if the Wheel is full, it keeps retrying to register its Timer on every poll
+        /// instead of failing.
+        ///
+        /// # Busy-Waiting
+        /// While the Wheel is full, the future wakes itself right away to retry on the next poll,
+        /// so under sustained pressure on the Timers this busy-spins, just like
+        /// [`yield_now`](crate::futures::yield_now) in a loop.
+        pub fn sleep_ms_retry(&self, time: usize) -> SleepMs<'_, WHEEL, SCALE> {
+            SleepMs {
+                timer: self,
+                handle: None,
+                time: SCALE::scale_ms(time),
+                retry: true,
+            }
+        }
+
+        /// Removes all the Timers and frees their Slots, so the TimerWheel can be reused cleanly,
+        /// like after tearing down a subsystem whose Tasks will never poll their Timers again.
+        ///
+        /// # Outstanding Timers
+        /// The Futures and Handles of the cleared Timers can still be used safely, but no longer
+        /// own a Slot. A pending [`SleepMs`] is woken and resolves to an Error, while a
+        /// [`TimerHandle`] never reports that it fired and can't be re-armed, see
+        /// [`TimerHandle::is_cleared`].
+        ///
+        /// Timers that are registered while the TimerWheel is being cleared may or may not be
+        /// cleared as well.
+        pub fn clear(&self) {
+            self.wheel.clear();
+            self.waker.clear();
+        }
+
+        /// The Storage for the Wakers of the Timers, mostly useful for diagnostics
+        pub fn storage(&self) -> &WHEEL::Storage {
+            &self.waker
+        }
+
         /// Adds the Waker to be woken in the given time in ms.
         ///
         /// # Special Case
@@ -416,6 +1019,59 @@ pub mod fixed_size {
             }
         }
 
+        /// Re-arms the Timer of the given Handle, which needs to belong to this TimerWheel, to
+        /// fire again in `time` ms, reusing its Slot instead of allocating a new one.
+        ///
+        /// This only works once the Timer has fired, a Timer that is still pending can't be
+        /// re-armed.
+        pub fn rearm_ms(
+            &self,
+            handle: &TimerHandle<'_>,
+            time: usize,
+            waker: Waker,
+        ) -> Result<(), WheelAddError> {
+            let (slot, index) = match handle {
+                TimerHandle::Registered { slot, index, .. } => (*slot, *index),
+                TimerHandle::Fired => return Err(WheelAddError::Other("The Handle has no Slot")),
+            };
+
+            if !slot
+                .state
+                .try_transition(SlotState::Taken, SlotState::Reserving)
+            {
+                return Err(WheelAddError::Other("The Timer has not fired yet"));
+            }
+            if handle.is_cleared() {
+                slot.state.set(SlotState::Taken);
+                return Err(WheelAddError::Other("The Timer was cleared"));
+            }
+
+            let time = match NonZeroUsize::new(time) {
+                Some(time) => time,
+                None => {
+                    slot.state.set(SlotState::Taken);
+                    slot.fired.store(true, atomic::Ordering::SeqCst);
+                    waker.wake();
+                    return Ok(());
+                }
+            };
+
+            slot.fired.store(false, atomic::Ordering::SeqCst);
+            slot.waker.with_mut(|w| {
+                let w_ref = unsafe { &mut *w };
+                *w_ref = Some(waker);
+            });
+
+            let result = self.wheel.rearm_step(time, index, &self.waker);
+            if result.is_err() {
+                // The Wheel might have failed before marking the Slot as Ready, which would
+                // otherwise prevent the Handle from ever releasing the Slot
+                slot.state
+                    .try_transition(SlotState::Reserving, SlotState::Taken);
+            }
+            result
+        }
+
         #[cfg(feature = "stm32l432")]
         pub fn configure_tim3(
             &self,
@@ -487,6 +1143,40 @@ pub mod fixed_size {
                 timer: self,
                 handle: None,
                 time: SCALE::scale_ms(time),
+                retry: false,
+            }
+        }
+
+        /// Returns a future that will resolve after around `time` milliseconds, unless it is
+        /// reset using [`ResettableSleep::reset_ms`]
+        pub fn resettable_sleep_ms(
+            &self,
+            time: usize,
+        ) -> ResettableSleep<'_, LevelOneWheel, SCALE> {
+            ResettableSleep {
+                inner: self.sleep_ms(time),
+                waker: None,
+            }
+        }
+
+        /// Returns a future that parks the current Task for at least a single Tick of the Timer.
+        ///
+        /// This is meant as an alternative to [`yield_now`](crate::futures::yield_now) for Tasks
+        /// that poll something in a loop. A Task yielding in a loop is ready again right away, so
+        /// the Runtime never has a pass without any ready Task and the core never gets to idle,
+        /// while a Task sleeping in a loop is only woken once per Tick.
+        ///
+        /// If no Timer is available, because the Wheel is full, this falls back to yielding
+        /// once, so it always completes.
+        pub fn cooperative_sleep(&self) -> CooperativeSleep<'_, LevelOneWheel, SCALE> {
+            CooperativeSleep {
+                sleep: SleepMs {
+                    timer: self,
+                    handle: None,
+                    time: 1,
+                    retry: false,
+                },
+                fallback: None,
             }
         }
     }
@@ -500,24 +1190,36 @@ pub mod fixed_size {
         timer: &'t TimerWheel<WHEEL, SCALE>,
         handle: Option<TimerHandle<'t>>,
         time: usize,
+        /// Whether or not to retry registering the Timer, if the Wheel is full
+        retry: bool,
     }
 
-    impl<'t, WHEEL, SCALE> Future for SleepMs<'t, WHEEL, SCALE>
+    impl<'t, WHEEL, SCALE> SleepMs<'t, WHEEL, SCALE>
     where
         WHEEL: Wheel,
         SCALE: Timescale,
     {
-        type Output = Result<(), ()>;
-
-        fn poll(
-            mut self: core::pin::Pin<&mut Self>,
+        /// Polls the Sleep without pinning it, for driving it from a custom poll loop instead of
+        /// an executor. This behaves exactly like polling the Future.
+        ///
+        /// Once the Timer fired, its Slot is released right away, instead of only when the Sleep
+        /// is dropped, and every further poll simply completes again.
+        pub fn poll_sleep(
+            &mut self,
             cx: &mut core::task::Context<'_>,
-        ) -> core::task::Poll<Self::Output> {
+        ) -> core::task::Poll<Result<(), ()>> {
             match &self.handle {
                 Some(handle) => match handle {
                     TimerHandle::Fired => core::task::Poll::Ready(Ok(())),
-                    TimerHandle::Registered { slot, used_slots } => {
-                        if slot.fired.load(atomic::Ordering::SeqCst) {
+                    TimerHandle::Registered { slot, .. } => {
+                        // The fired state only belongs to this Timer, if it was not cleared
+                        // after loading it
+                        let fired = slot.fired.load(atomic::Ordering::SeqCst);
+                        if handle.is_cleared() {
+                            core::task::Poll::Ready(Err(()))
+                        } else if fired {
+                            // Dropping the registered Handle releases the Slot
+                            self.handle = Some(TimerHandle::Fired);
                             core::task::Poll::Ready(Ok(()))
                         } else {
                             core::task::Poll::Pending
@@ -527,6 +1229,10 @@ pub mod fixed_size {
                 None => {
                     let handle = match self.timer.add_ms(self.time, cx.waker().clone()) {
                         Ok(h) => h,
+                        Err(WheelAddError::Full) if self.retry => {
+                            cx.waker().wake_by_ref();
+                            return core::task::Poll::Pending;
+                        }
                         Err(e) => return core::task::Poll::Ready(Err(())),
                     };
                     self.handle = Some(handle);
@@ -537,11 +1243,120 @@ pub mod fixed_size {
         }
     }
 
-    #[cfg(all(test, not(loom)))]
-    mod tests {
-        use super::*;
-
-        #[test]
+    impl<'t, WHEEL, SCALE> Future for SleepMs<'t, WHEEL, SCALE>
+    where
+        WHEEL: Wheel,
+        SCALE: Timescale,
+    {
+        type Output = Result<(), ()>;
+
+        fn poll(
+            mut self: core::pin::Pin<&mut Self>,
+            cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<Self::Output> {
+            self.poll_sleep(cx)
+        }
+    }
+
+    /// The Future returned by [`TimerWheel::cooperative_sleep`]
+    pub struct CooperativeSleep<'t, WHEEL, SCALE>
+    where
+        WHEEL: Wheel,
+        SCALE: Timescale,
+    {
+        sleep: SleepMs<'t, WHEEL, SCALE>,
+        /// Used instead of the Timer, if no Timer could be registered
+        fallback: Option<crate::futures::YieldNow>,
+    }
+
+    impl<'t, WHEEL, SCALE> Future for CooperativeSleep<'t, WHEEL, SCALE>
+    where
+        WHEEL: Wheel,
+        SCALE: Timescale,
+    {
+        type Output = ();
+
+        fn poll(
+            mut self: core::pin::Pin<&mut Self>,
+            cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<Self::Output> {
+            if self.fallback.is_none() {
+                match core::pin::Pin::new(&mut self.sleep).poll(cx) {
+                    core::task::Poll::Ready(Ok(())) => return core::task::Poll::Ready(()),
+                    core::task::Poll::Ready(Err(())) => {
+                        self.fallback = Some(crate::futures::yield_now());
+                    }
+                    core::task::Poll::Pending => return core::task::Poll::Pending,
+                };
+            }
+
+            match self.fallback.as_mut() {
+                Some(fallback) => core::pin::Pin::new(fallback).poll(cx),
+                None => core::task::Poll::Pending,
+            }
+        }
+    }
+
+    /// A sleeping Future, whose deadline can be moved while it is pending, like for debouncing
+    /// where the deadline keeps getting pushed out
+    pub struct ResettableSleep<'t, WHEEL, SCALE>
+    where
+        WHEEL: Wheel,
+        SCALE: Timescale,
+    {
+        inner: SleepMs<'t, WHEEL, SCALE>,
+        /// The Waker from the last poll, used to re-arm the Timer right away when it is reset
+        waker: Option<Waker>,
+    }
+
+    impl<'t, WHEEL, SCALE> ResettableSleep<'t, WHEEL, SCALE>
+    where
+        WHEEL: Wheel,
+        SCALE: Timescale,
+    {
+        /// Cancels the current Timer and sleeps for `time` milliseconds from now on instead.
+        ///
+        /// This can also be used after the Future has already completed, to start sleeping again
+        pub fn reset_ms(&mut self, time: usize) {
+            if let Some(handle) = self.inner.handle.take() {
+                handle.cancel();
+            }
+            self.inner.time = SCALE::scale_ms(time);
+
+            // Re-arm immediately, if the Future was already polled, otherwise it is armed on the
+            // next poll like any other Sleep
+            if let Some(waker) = self.waker.clone() {
+                if let Ok(handle) = self.inner.timer.add_ms(self.inner.time, waker) {
+                    self.inner.handle = Some(handle);
+                }
+            }
+        }
+    }
+
+    impl<'t, WHEEL, SCALE> Future for ResettableSleep<'t, WHEEL, SCALE>
+    where
+        WHEEL: Wheel,
+        SCALE: Timescale,
+    {
+        type Output = Result<(), ()>;
+
+        fn poll(
+            mut self: core::pin::Pin<&mut Self>,
+            cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<Self::Output> {
+            if !matches!(&self.waker, Some(w) if w.will_wake(cx.waker())) {
+                self.waker = Some(cx.waker().clone());
+            }
+
+            core::pin::Pin::new(&mut self.inner).poll(cx)
+        }
+    }
+
+    #[cfg(all(test, not(loom)))]
+    mod tests {
+        use super::*;
+
+        #[test]
         fn scale_1ms() {
             assert_eq!(1, Scale1Ms::scale_ms(1));
             assert_eq!(2, Scale1Ms::scale_ms(2));
@@ -557,6 +1372,54 @@ pub mod fixed_size {
             assert_eq!(2, Scale10Ms::scale_ms(11));
         }
 
+        #[test]
+        fn max_delay() {
+            assert_eq!(310, max_delay_ms::<LevelOneWheel, Scale10Ms>());
+            assert_eq!(31, max_delay_ms::<LevelOneWheel, Scale1Ms>());
+        }
+
+        #[test]
+        fn max_delay_fits() {
+            let timer = TimerWheel::<LevelOneWheel, Scale10Ms>::new();
+            let max = max_delay_ms::<LevelOneWheel, Scale10Ms>();
+
+            let (waker, _) = futures_test::task::new_count_waker();
+            let mut ctx = core::task::Context::from_waker(&waker);
+
+            let mut longest = timer.sleep_ms(max);
+            assert!(longest.poll_sleep(&mut ctx).is_pending());
+
+            let mut too_long = timer.sleep_ms(max + 1);
+            assert_eq!(
+                core::task::Poll::Ready(Err(())),
+                too_long.poll_sleep(&mut ctx)
+            );
+        }
+
+        #[test]
+        fn from_parts_matching_storage() {
+            struct SmallWheel;
+            impl Wheel for SmallWheel {
+                const SLOTS: usize = 2;
+                type Storage = SlotStorage<2>;
+
+                fn tick(&self, _: &Self::Storage) {}
+                fn add_step<'t>(
+                    &self,
+                    _: NonZeroUsize,
+                    _: Waker,
+                    _: &'t Self::Storage,
+                ) -> Result<TimerHandle<'t>, WheelAddError> {
+                    Err(WheelAddError::Full)
+                }
+            }
+
+            static TIMER: TimerWheel<SmallWheel, Scale1Ms> =
+                TimerWheel::from_parts(SmallWheel, SlotStorage::new());
+
+            assert_eq!(0, TIMER.storage().used_count());
+        }
+
         #[test]
         fn storage_add_waker() {
             let storage = SlotStorage::<2>::new();
@@ -584,6 +1447,63 @@ pub mod fixed_size {
             assert_eq!(Ok(1), storage.add_waker(futures_test::task::noop_waker()));
         }
 
+        #[test]
+        fn storage_take_waker_twice() {
+            let storage = SlotStorage::<2>::new();
+
+            assert_eq!(Ok(0), storage.add_waker(futures_test::task::noop_waker()));
+
+            assert!(storage.take_slot(0).is_some());
+            assert!(storage.take_slot(0).is_none());
+        }
+
+        #[test]
+        fn storage_take_free_slot() {
+            let storage = SlotStorage::<2>::new();
+
+            assert!(storage.take_slot(0).is_none());
+            assert_eq!(
+                SlotState::Free,
+                storage.wakers[0].state.load(atomic::Ordering::SeqCst)
+            );
+        }
+
+        #[test]
+        fn timer1_drop_before_tick() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let (waker, count) = futures_test::task::new_count_waker();
+
+            let handle = timer.add_ms(1, waker).unwrap();
+            drop(handle);
+
+            timer.tick();
+
+            assert_eq!(0, count.get());
+        }
+
+        #[test]
+        fn storage_fired_used_count() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let first = timer.add_ms(1, futures_test::task::noop_waker()).unwrap();
+            let second = timer.add_ms(2, futures_test::task::noop_waker()).unwrap();
+
+            assert_eq!(0, timer.storage().fired_count());
+            assert_eq!(2, timer.storage().used_count());
+
+            timer.tick();
+
+            assert_eq!(1, timer.storage().fired_count());
+            assert_eq!(2, timer.storage().used_count());
+
+            drop(first);
+            drop(second);
+
+            assert_eq!(0, timer.storage().fired_count());
+            assert_eq!(0, timer.storage().used_count());
+        }
+
         #[test]
         fn add_0ms_timer1() {
             let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
@@ -622,6 +1542,295 @@ pub mod fixed_size {
             assert_eq!(1, count.get());
         }
 
+        #[test]
+        fn timer1_wrap_around() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::from_parts(
+                LevelOneWheel::starting_at(usize::MAX - 1),
+                SlotStorage::new(),
+            );
+
+            let (waker, count) = futures_test::task::new_count_waker();
+
+            let handle = timer.add_ms(3, waker).unwrap();
+
+            // Moves the current Tick past usize::MAX
+            timer.tick();
+            timer.tick();
+            assert!(!handle.take_fired());
+            assert_eq!(0, count.get());
+
+            timer.tick();
+            assert!(handle.take_fired());
+            assert_eq!(1, count.get());
+        }
+
+        #[test]
+        fn timer1_advance_to() {
+            let timer = TimerWheel::<LevelOneWheel, Scale10Ms>::new();
+
+            let (waker, count) = futures_test::task::new_count_waker();
+
+            let first = timer.add_ms(1, waker.clone()).unwrap();
+            let second = timer.add_ms(3, waker.clone()).unwrap();
+            let third = timer.add_ms(6, waker).unwrap();
+
+            // Less than a full step does not Tick at all
+            timer.advance_to(9);
+            assert_eq!(0, count.get());
+
+            // The partial step from before is carried over
+            timer.advance_to(12);
+            assert!(first.take_fired());
+            assert_eq!(1, count.get());
+
+            // Jumps over multiple missed steps at once
+            timer.advance_to(41);
+            assert!(second.take_fired());
+            assert!(!third.take_fired());
+            assert_eq!(2, count.get());
+
+            // An outdated value does not move the Wheel backwards or Tick
+            timer.advance_to(35);
+            assert_eq!(2, count.get());
+
+            timer.advance_to(59);
+            assert!(!third.take_fired());
+            timer.advance_to(60);
+            assert!(third.take_fired());
+            assert_eq!(3, count.get());
+        }
+
+        /// Drives the Future like the Runtime would, by only polling it in passes in which it
+        /// was woken before, while ticking the Timer every `tick_every` passes. Returns the number
+        /// of idle passes, in which the Future was not ready.
+        fn idle_passes<F>(
+            timer: &TimerWheel<LevelOneWheel, Scale1Ms>,
+            fut: F,
+            passes: usize,
+            tick_every: usize,
+        ) -> usize
+        where
+            F: Future,
+        {
+            let mut fut = core::pin::pin!(fut);
+            let (waker, count) = futures_test::task::new_count_waker();
+            let mut ctx = core::task::Context::from_waker(&waker);
+
+            let mut idle = 0;
+            let mut last_count = None;
+            for pass in 0..passes {
+                if last_count == Some(count.get()) {
+                    idle += 1;
+                } else {
+                    last_count = Some(count.get());
+                    assert!(fut.as_mut().poll(&mut ctx).is_pending());
+                }
+
+                if pass % tick_every == tick_every - 1 {
+                    timer.tick();
+                }
+            }
+
+            idle
+        }
+
+        #[test]
+        fn yield_now_never_idles() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let fut = async {
+                loop {
+                    crate::futures::yield_now().await;
+                }
+            };
+
+            assert_eq!(0, idle_passes(&timer, fut, 20, 4));
+        }
+
+        #[test]
+        fn cooperative_sleep_idles() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let fut = async {
+                loop {
+                    timer.cooperative_sleep().await;
+                }
+            };
+
+            // The Task only runs once per Tick
+            assert_eq!(15, idle_passes(&timer, fut, 20, 4));
+
+            // The Task was dropped while sleeping, which releases its Timer
+            assert_eq!(0, timer.storage().used_count());
+        }
+
+        #[test]
+        fn cooperative_sleep_full_wheel() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let (waker, count) = futures_test::task::new_count_waker();
+            let mut timers = Vec::new();
+            while let Ok(handle) = timer.add_ms(1, waker.clone()) {
+                timers.push(handle);
+            }
+
+            let mut ctx = core::task::Context::from_waker(&waker);
+            let mut sleep = timer.cooperative_sleep();
+
+            // Falls back to yielding once
+            assert!(core::pin::Pin::new(&mut sleep).poll(&mut ctx).is_pending());
+            assert_eq!(1, count.get());
+            assert!(core::pin::Pin::new(&mut sleep).poll(&mut ctx).is_ready());
+        }
+
+        /// A Wheel with only a single Slot, which fires its Timer on the next Tick
+        struct SingleSlotWheel;
+
+        impl Wheel for SingleSlotWheel {
+            const SLOTS: usize = 1;
+
+            type Storage = SlotStorage<1>;
+
+            fn tick(&self, storage: &Self::Storage) {
+                if let Some(waker) = storage.take_slot(0) {
+                    waker.wake();
+                }
+            }
+
+            fn add_step<'t>(
+                &self,
+                _: NonZeroUsize,
+                waker: Waker,
+                storage: &'t Self::Storage,
+            ) -> Result<TimerHandle<'t>, WheelAddError> {
+                let index = storage.add_waker(waker).map_err(|_| WheelAddError::Full)?;
+
+                Ok(storage.handle(index))
+            }
+        }
+
+        #[test]
+        fn sleep_ms_retry_full() {
+            let timer = TimerWheel::<SingleSlotWheel, Scale1Ms>::from_parts(
+                SingleSlotWheel,
+                SlotStorage::new(),
+            );
+
+            let (first_waker, first_count) = futures_test::task::new_count_waker();
+            let mut first_ctx = core::task::Context::from_waker(&first_waker);
+            let (second_waker, second_count) = futures_test::task::new_count_waker();
+            let mut second_ctx = core::task::Context::from_waker(&second_waker);
+
+            let mut first = timer.sleep_ms_retry(1);
+            assert!(core::pin::Pin::new(&mut first)
+                .poll(&mut first_ctx)
+                .is_pending());
+
+            // The only Slot is taken, so the second one keeps waking itself to retry
+            let mut second = timer.sleep_ms_retry(1);
+            assert!(core::pin::Pin::new(&mut second)
+                .poll(&mut second_ctx)
+                .is_pending());
+            assert_eq!(1, second_count.get());
+
+            timer.tick();
+            assert_eq!(1, first_count.get());
+            assert_eq!(
+                core::task::Poll::Ready(Ok(())),
+                core::pin::Pin::new(&mut first).poll(&mut first_ctx)
+            );
+            drop(first);
+
+            // The Slot was released, so the second one can now register its Timer
+            assert!(core::pin::Pin::new(&mut second)
+                .poll(&mut second_ctx)
+                .is_pending());
+            assert_eq!(1, second_count.get());
+
+            timer.tick();
+            assert_eq!(2, second_count.get());
+            assert_eq!(
+                core::task::Poll::Ready(Ok(())),
+                core::pin::Pin::new(&mut second).poll(&mut second_ctx)
+            );
+        }
+
+        #[test]
+        fn sleep_ms_full_fails() {
+            let timer = TimerWheel::<SingleSlotWheel, Scale1Ms>::from_parts(
+                SingleSlotWheel,
+                SlotStorage::new(),
+            );
+
+            let (waker, count) = futures_test::task::new_count_waker();
+            let mut ctx = core::task::Context::from_waker(&waker);
+
+            let _first = timer.add_ms(1, waker.clone()).unwrap();
+
+            let mut second = SleepMs {
+                timer: &timer,
+                handle: None,
+                time: 1,
+                retry: false,
+            };
+            assert_eq!(
+                core::task::Poll::Ready(Err(())),
+                core::pin::Pin::new(&mut second).poll(&mut ctx)
+            );
+            assert_eq!(0, count.get());
+        }
+
+        #[test]
+        fn timer1_collisions_stay_in_rotation() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            // Fills all the Slots of the Wheel from 30 onwards
+            let (waker, _) = futures_test::task::new_count_waker();
+            let _first = timer.add_ms(30, waker.clone()).unwrap();
+            let _second = timer.add_ms(30, waker.clone()).unwrap();
+
+            assert_eq!(Some(WheelAddError::Full), timer.add_ms(30, waker).err());
+        }
+
+        #[test]
+        fn timer1_rearm_same_slot() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let (waker, count) = futures_test::task::new_count_waker();
+
+            let handle = timer.add_ms(1, waker.clone()).unwrap();
+            let first_index = match &handle {
+                TimerHandle::Registered { index, .. } => *index,
+                TimerHandle::Fired => unreachable!(),
+            };
+
+            // Can't re-arm a Timer that is still pending
+            assert!(timer.rearm_ms(&handle, 2, waker.clone()).is_err());
+
+            timer.tick();
+            assert_eq!(1, count.get());
+
+            assert!(handle.take_fired());
+            assert!(!handle.take_fired());
+
+            timer.rearm_ms(&handle, 2, waker).expect("The Timer fired");
+            assert_eq!(1, timer.storage().used_count());
+
+            timer.tick();
+            assert!(!handle.take_fired());
+            assert_eq!(1, count.get());
+
+            timer.tick();
+            assert!(handle.take_fired());
+            assert_eq!(2, count.get());
+
+            assert!(matches!(
+                handle,
+                TimerHandle::Registered { index, .. } if index == first_index
+            ));
+            assert_eq!(1, timer.storage().used_count());
+        }
+
         #[test]
         fn sleep_future_1ms() {
             let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
@@ -648,6 +1857,142 @@ pub mod fixed_size {
             assert_eq!(1, count.get());
         }
 
+        #[test]
+        fn sleep_releases_slot_when_fired() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let mut sleep = timer.sleep_ms(1);
+
+            let (waker, count) = futures_test::task::new_count_waker();
+            let mut ctx = core::task::Context::from_waker(&waker);
+
+            assert!(sleep.poll_sleep(&mut ctx).is_pending());
+            assert_eq!(1, timer.storage().used_count());
+
+            timer.tick();
+            assert_eq!(1, count.get());
+            assert_eq!(1, timer.storage().used_count());
+
+            // The Slot is released as soon as the Sleep notices that it fired
+            assert_eq!(core::task::Poll::Ready(Ok(())), sleep.poll_sleep(&mut ctx));
+            assert_eq!(0, timer.storage().used_count());
+
+            assert_eq!(core::task::Poll::Ready(Ok(())), sleep.poll_sleep(&mut ctx));
+            assert_eq!(0, timer.storage().used_count());
+        }
+
+        #[test]
+        fn resettable_sleep_longer() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let mut sleep_fut = Box::pin(timer.resettable_sleep_ms(2));
+
+            let (waker, count) = futures_test::task::new_count_waker();
+            let mut ctx = core::task::Context::from_waker(&waker);
+
+            assert!(sleep_fut.as_mut().poll(&mut ctx).is_pending());
+
+            timer.tick();
+            assert!(sleep_fut.as_mut().poll(&mut ctx).is_pending());
+
+            sleep_fut.reset_ms(3);
+
+            // The old deadline passes without firing
+            timer.tick();
+            assert!(sleep_fut.as_mut().poll(&mut ctx).is_pending());
+            assert_eq!(0, count.get());
+
+            timer.tick();
+            assert!(sleep_fut.as_mut().poll(&mut ctx).is_pending());
+            assert_eq!(0, count.get());
+
+            timer.tick();
+            assert!(sleep_fut.as_mut().poll(&mut ctx).is_ready());
+            assert_eq!(1, count.get());
+        }
+
+        #[test]
+        fn timer1_cancelled_slot_reused() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let (first_waker, first_count) = futures_test::task::new_count_waker();
+            timer.add_ms(1, first_waker).unwrap().cancel();
+
+            // Reuses the Slot of the cancelled Timer, but with a later deadline
+            let (second_waker, second_count) = futures_test::task::new_count_waker();
+            let _handle = timer.add_ms(2, second_waker).unwrap();
+
+            timer.tick();
+            assert_eq!(0, first_count.get());
+            assert_eq!(0, second_count.get());
+
+            timer.tick();
+            assert_eq!(0, first_count.get());
+            assert_eq!(1, second_count.get());
+        }
+
+        #[test]
+        fn timer1_clear() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let (waker, count) = futures_test::task::new_count_waker();
+            let first = timer.add_ms(1, waker.clone()).unwrap();
+            let second = timer.add_ms(3, waker.clone()).unwrap();
+            let third = timer.add_ms(5, waker.clone()).unwrap();
+            assert_eq!(3, timer.storage().used_count());
+
+            // The pending Timers are woken, so their Futures notice being cleared
+            timer.clear();
+            assert_eq!(0, timer.storage().used_count());
+            assert_eq!(3, count.get());
+            assert!(first.is_cleared());
+            assert!(!first.take_fired());
+
+            // The cleared Timers never fire
+            for _ in 0..5 {
+                timer.tick();
+            }
+            assert!(!second.take_fired());
+
+            let (new_waker, new_count) = futures_test::task::new_count_waker();
+            let handle = timer.add_ms(1, new_waker).unwrap();
+            assert!(!handle.is_cleared());
+            assert_eq!(1, timer.storage().used_count());
+
+            // Dropping the old Handles must not free the Slot of the new Timer
+            drop(first);
+            drop(second);
+            drop(third);
+            assert_eq!(1, timer.storage().used_count());
+            assert_eq!(3, count.get());
+
+            timer.tick();
+            assert_eq!(1, new_count.get());
+            assert!(handle.take_fired());
+        }
+
+        #[test]
+        fn sleep_future_clear() {
+            let timer = TimerWheel::<LevelOneWheel, Scale1Ms>::new();
+
+            let mut sleep_fut = Box::pin(timer.sleep_ms(5));
+
+            let (waker, count) = futures_test::task::new_count_waker();
+            let mut ctx = core::task::Context::from_waker(&waker);
+
+            assert!(sleep_fut.as_mut().poll(&mut ctx).is_pending());
+
+            timer.clear();
+            assert_eq!(1, count.get());
+            assert_eq!(
+                core::task::Poll::Ready(Err(())),
+                sleep_fut.as_mut().poll(&mut ctx)
+            );
+
+            drop(sleep_fut);
+            assert_eq!(0, timer.storage().used_count());
+        }
+
         #[test]
         fn sleep_future_10ms() {
             let timer = TimerWheel::<LevelOneWheel, Scale10Ms>::new();
@@ -676,4 +2021,254 @@ pub mod fixed_size {
             assert_eq!(1, count.get());
         }
     }
+
+    #[cfg(all(test, loom))]
+    mod loom_tests {
+        use super::*;
+
+        use loom::sync::Arc;
+
+        #[test]
+        fn add_waker_take_slot() {
+            loom::model(|| {
+                let storage = Arc::new(SlotStorage::<2>::new());
+                let (waker, count) = futures_test::task::new_count_waker();
+
+                let add_storage = storage.clone();
+                let adder = loom::thread::spawn(move || add_storage.add_waker(waker).unwrap());
+
+                let take_storage = storage.clone();
+                let taker = loom::thread::spawn(move || {
+                    for index in 0..2 {
+                        if let Some(waker) = take_storage.take_slot(index) {
+                            waker.wake();
+                            return true;
+                        }
+                    }
+                    false
+                });
+
+                let index = adder.join().unwrap();
+                if !taker.join().unwrap() {
+                    // The Waker was added after the other thread checked the Slots
+                    let waker = storage.take_slot(index).expect("The Waker was added");
+                    waker.wake();
+                }
+
+                assert_eq!(1, count.get());
+            });
+        }
+
+        #[test]
+        fn add_concurrent_tick_never_lost() {
+            loom::model(|| {
+                let timer = Arc::new(TimerWheel::<LevelOneWheel, Scale1Ms>::new());
+                let (waker, count) = futures_test::task::new_count_waker();
+
+                let add_timer = timer.clone();
+                let adder = loom::thread::spawn(move || {
+                    let handle = add_timer.add_ms(1, waker).unwrap();
+                    core::mem::forget(handle);
+                });
+
+                let tick_timer = timer.clone();
+                let ticker = loom::thread::spawn(move || {
+                    tick_timer.tick();
+                });
+
+                adder.join().unwrap();
+                ticker.join().unwrap();
+
+                // Depending on whether the Timer was added before or after the tick, it fired
+                // already or fires on the next tick, but it is never lost
+                timer.tick();
+                assert_eq!(1, count.get());
+            });
+        }
+
+        #[test]
+        fn clear_concurrent_drop() {
+            loom::model(|| {
+                // The Handle borrows the TimerWheel, so it needs to outlive both threads
+                let timer: &'static _ =
+                    Box::leak(Box::new(TimerWheel::<LevelOneWheel, Scale1Ms>::new()));
+                let (waker, _) = futures_test::task::new_count_waker();
+
+                let handle = timer.add_ms(1, waker).unwrap();
+
+                let dropper = loom::thread::spawn(move || {
+                    drop(handle);
+                });
+                let clearer = loom::thread::spawn(move || {
+                    timer.clear();
+                });
+
+                dropper.join().unwrap();
+                clearer.join().unwrap();
+
+                // The Slot is released exactly once, no matter who got to it first
+                assert_eq!(0, timer.storage().used_count());
+            });
+        }
+
+        #[test]
+        fn add_concurrent_tick_never_early() {
+            loom::model(|| {
+                let timer = Arc::new(TimerWheel::<LevelOneWheel, Scale1Ms>::new());
+                let (waker, count) = futures_test::task::new_count_waker();
+
+                let add_timer = timer.clone();
+                let adder = loom::thread::spawn(move || {
+                    let handle = add_timer.add_ms(2, waker).unwrap();
+                    core::mem::forget(handle);
+                });
+
+                let tick_timer = timer.clone();
+                let ticker = loom::thread::spawn(move || {
+                    tick_timer.tick();
+                });
+
+                adder.join().unwrap();
+                ticker.join().unwrap();
+
+                // Only a single tick happened so far
+                assert_eq!(0, count.get());
+
+                timer.tick();
+                let fired_after_two = count.get();
+                timer.tick();
+
+                // If the concurrent tick happened before the Timer was added, it does not
+                // count towards the Timer and the Timer only fires on the third tick
+                assert!(fired_after_two <= 1);
+                assert_eq!(1, count.get());
+            });
+        }
+
+        #[test]
+        fn add_reused_slot_concurrent_tick() {
+            loom::model(|| {
+                let timer = Arc::new(TimerWheel::<LevelOneWheel, Scale1Ms>::new());
+                let (old_waker, old_count) = futures_test::task::new_count_waker();
+                let (waker, count) = futures_test::task::new_count_waker();
+
+                // Leaves a stale entry for the Slot in the Wheel, which is found by the next tick
+                drop(timer.add_ms(1, old_waker).unwrap());
+
+                let add_timer = timer.clone();
+                let adder = loom::thread::spawn(move || {
+                    let handle = add_timer.add_ms(2, waker).unwrap();
+                    core::mem::forget(handle);
+                });
+
+                let tick_timer = timer.clone();
+                let ticker = loom::thread::spawn(move || {
+                    tick_timer.tick();
+                });
+
+                adder.join().unwrap();
+                ticker.join().unwrap();
+
+                // The stale entry must not fire the new Timer, that reused the Slot, early
+                assert_eq!(0, count.get());
+
+                timer.tick();
+                timer.tick();
+                assert_eq!(1, count.get());
+                assert_eq!(0, old_count.get());
+            });
+        }
+
+        #[test]
+        fn add_collision_concurrent_tick() {
+            loom::model(|| {
+                let timer = Arc::new(TimerWheel::<LevelOneWheel, Scale1Ms>::new());
+                let (first_waker, first_count) = futures_test::task::new_count_waker();
+                let (waker, count) = futures_test::task::new_count_waker();
+
+                core::mem::forget(timer.add_ms(1, first_waker).unwrap());
+
+                // Collides with the first Timer, so it is moved to the next Slot and its
+                // Deadline changes after it was already marked as Ready
+                let add_timer = timer.clone();
+                let adder = loom::thread::spawn(move || {
+                    let handle = add_timer.add_ms(1, waker).unwrap();
+                    core::mem::forget(handle);
+                });
+
+                let tick_timer = timer.clone();
+                let ticker = loom::thread::spawn(move || {
+                    tick_timer.tick();
+                    tick_timer.tick();
+                });
+
+                adder.join().unwrap();
+                ticker.join().unwrap();
+
+                timer.tick();
+                assert_eq!(1, first_count.get());
+                assert_eq!(1, count.get());
+            });
+        }
+
+        #[test]
+        fn rearm_concurrent_tick_never_lost() {
+            loom::model(|| {
+                let timer: &'static _ =
+                    Box::leak(Box::new(TimerWheel::<LevelOneWheel, Scale1Ms>::new()));
+                let (waker, count) = futures_test::task::new_count_waker();
+
+                let handle = timer.add_ms(1, waker.clone()).unwrap();
+                timer.tick();
+                assert!(handle.take_fired());
+
+                let rearmer = loom::thread::spawn(move || {
+                    timer.rearm_ms(&handle, 1, waker).unwrap();
+                    handle
+                });
+                let ticker = loom::thread::spawn(move || {
+                    timer.tick();
+                });
+
+                let handle = rearmer.join().unwrap();
+                ticker.join().unwrap();
+
+                timer.tick();
+                assert_eq!(2, count.get());
+                assert!(handle.take_fired());
+            });
+        }
+
+        #[test]
+        fn tick_while_dropping_and_reusing() {
+            loom::model(|| {
+                let timer = Arc::new(TimerWheel::<LevelOneWheel, Scale1Ms>::new());
+                let (first_waker, first_count) = futures_test::task::new_count_waker();
+                let (second_waker, second_count) = futures_test::task::new_count_waker();
+
+                let add_timer = timer.clone();
+                let adder = loom::thread::spawn(move || {
+                    let first = add_timer.add_ms(1, first_waker).unwrap();
+                    drop(first);
+
+                    // Likely reuses the Slot of the first Timer
+                    let second = add_timer.add_ms(5, second_waker).unwrap();
+                    second.take_fired()
+                });
+
+                let tick_timer = timer.clone();
+                let ticker = loom::thread::spawn(move || {
+                    tick_timer.tick();
+                });
+
+                let second_fired = adder.join().unwrap();
+                ticker.join().unwrap();
+
+                assert!(first_count.get() <= 1);
+                assert!(!second_fired);
+                assert_eq!(0, second_count.get());
+                assert_eq!(0, timer.storage().used_count());
+            });
+        }
+    }
 }
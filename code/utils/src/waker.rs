@@ -0,0 +1,262 @@
+//! A lock-free Cell for a single Waker, that is registered by a single Task and woken from any
+//! other context, like other Tasks or interrupt handlers.
+//!
+//! # States
+//! ```text
+//! Empty -> Registering -> Waiting -> Waking -> Empty
+//! ```
+//! Waking while a Waker is being registered only marks the Cell as `WAKING` and leaves the
+//! actual waking up to the registering side, so neither side ever waits on the other one, which
+//! means that it can also be used from interrupt handlers, without disabling the interrupts.
+
+use core::task::Waker;
+
+use crate::{
+    atomic::{self, AtomicU8},
+    UnsafeCell,
+};
+
+/// A Waker is currently being registered
+const REGISTERING: u8 = 0b001;
+/// The registered Waker is currently being woken
+const WAKING: u8 = 0b010;
+/// A Waker is registered and waits to be woken
+const WAITING: u8 = 0b100;
+
+/// Stores the Waker of a single Task, which can then be woken from any context, similar to the
+/// `AtomicWaker` of the futures crate.
+///
+/// The registered Waker is only woken once, so the Task needs to register itself again every
+/// time it is polled and still waits on something.
+pub struct AtomicWakerCell {
+    state: AtomicU8,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// Safety:
+// The Waker is only accessed by whoever moved the State into `REGISTERING` or set the `WAKING`
+// bit on a `WAITING` State, which can only ever be a single context at a time.
+unsafe impl Send for AtomicWakerCell {}
+unsafe impl Sync for AtomicWakerCell {}
+
+impl AtomicWakerCell {
+    /// Creates a new empty Cell, which can be used in a `static`
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(0),
+            waker: UnsafeCell::new(None),
+        }
+    }
+    /// Creates a new empty Cell
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        Self {
+            state: AtomicU8::new(0),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Registers the Waker, which is woken by the next call to [`wake`](AtomicWakerCell::wake).
+    ///
+    /// This should only be called by a single Task at a time, if it is called while the Cell is
+    /// being woken, the given Waker is woken right away instead.
+    pub fn register(&self, waker: &Waker) {
+        let mut state = self.state.load(atomic::Ordering::SeqCst);
+        loop {
+            if state & WAKING != 0 {
+                // The previous Waker is currently being woken, so we can't replace it and
+                // instead make sure that we are polled again
+                waker.wake_by_ref();
+                return;
+            }
+
+            match self.state.compare_exchange(
+                state,
+                REGISTERING,
+                atomic::Ordering::SeqCst,
+                atomic::Ordering::SeqCst,
+            ) {
+                Ok(_) => break,
+                Err(current) => state = current,
+            };
+        }
+
+        self.waker.with_mut(|w| {
+            // Safety: We hold the `REGISTERING` State, so no one else accesses the Waker
+            let w_ref = unsafe { &mut *w };
+            match w_ref {
+                Some(previous) if previous.will_wake(waker) => {}
+                _ => *w_ref = Some(waker.clone()),
+            };
+        });
+
+        if self
+            .state
+            .compare_exchange(
+                REGISTERING,
+                WAITING,
+                atomic::Ordering::SeqCst,
+                atomic::Ordering::SeqCst,
+            )
+            .is_err()
+        {
+            // Someone tried to wake us while we were registering and left the waking up to us
+            let waker = self.waker.with_mut(|w| unsafe { &mut *w }.take());
+            self.state.store(0, atomic::Ordering::SeqCst);
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+
+        // Pairs with the fence in `wake`, see there
+        atomic::fence(atomic::Ordering::SeqCst);
+    }
+
+    /// Wakes the registered Waker, if there is one. This never waits on the registering side, so
+    /// it can be used from any context
+    pub fn wake(&self) {
+        // Nothing is registered, which is the common case, so we don't need to modify the State.
+        // The fence pairs with the one at the end of `register`, so that either we see the
+        // registered Waker or the registering side sees the Event we are waking for
+        atomic::fence(atomic::Ordering::SeqCst);
+        if self.state.load(atomic::Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        let previous = self.state.fetch_or(WAKING, atomic::Ordering::SeqCst);
+        if previous == 0 {
+            // The Waker was woken in the meantime, so we only undo our change
+            self.state.fetch_and(!WAKING, atomic::Ordering::SeqCst);
+            return;
+        }
+        if previous != WAITING {
+            // Either the Waker is being registered, which then wakes itself, or someone else is
+            // already waking it
+            return;
+        }
+
+        let waker = self.waker.with_mut(|w| {
+            // Safety: We set the `WAKING` bit, so no one else accesses the Waker
+            let w_ref = unsafe { &mut *w };
+            w_ref.take()
+        });
+        self.state.store(0, atomic::Ordering::SeqCst);
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for AtomicWakerCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wake_registered() {
+        static CELL: AtomicWakerCell = AtomicWakerCell::new();
+
+        let (waker, count) = futures_test::task::new_count_waker();
+        CELL.register(&waker);
+        assert_eq!(0, count.get());
+
+        CELL.wake();
+        assert_eq!(1, count.get());
+
+        // The Waker is only woken once
+        CELL.wake();
+        assert_eq!(1, count.get());
+    }
+
+    #[test]
+    fn wake_empty() {
+        let cell = AtomicWakerCell::new();
+        cell.wake();
+
+        // Waking an empty Cell is not remembered for the next registered Waker
+        let (waker, count) = futures_test::task::new_count_waker();
+        cell.register(&waker);
+        assert_eq!(0, count.get());
+    }
+
+    #[test]
+    fn register_replaces() {
+        let cell = AtomicWakerCell::new();
+
+        let (first, first_count) = futures_test::task::new_count_waker();
+        let (second, second_count) = futures_test::task::new_count_waker();
+        cell.register(&first);
+        cell.register(&second);
+
+        cell.wake();
+        assert_eq!(0, first_count.get());
+        assert_eq!(1, second_count.get());
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::*;
+
+    use loom::sync::{atomic::AtomicBool, Arc};
+
+    /// Registers the given Waker and checks for the Event, like a Future would, while the other
+    /// thread signals the Event and wakes the Cell. Returns if the Event was seen by the
+    /// registering side
+    fn register_concurrent_signal(cell: Arc<AtomicWakerCell>, waker: &Waker) -> bool {
+        let event = Arc::new(AtomicBool::new(false));
+
+        let signaling = loom::thread::spawn({
+            let cell = cell.clone();
+            let event = event.clone();
+            move || {
+                event.store(true, atomic::Ordering::SeqCst);
+                cell.wake();
+            }
+        });
+
+        cell.register(waker);
+        let seen = event.load(atomic::Ordering::SeqCst);
+
+        signaling.join().unwrap();
+
+        seen
+    }
+
+    #[test]
+    fn register_then_wake() {
+        loom::model(|| {
+            let cell = Arc::new(AtomicWakerCell::new());
+            let (waker, count) = futures_test::task::new_count_waker();
+
+            let seen = register_concurrent_signal(cell, &waker);
+
+            // Either the Event was already there or the registered Waker must have been woken
+            assert!(seen || count.get() == 1);
+        });
+    }
+
+    #[test]
+    fn wake_then_register() {
+        loom::model(|| {
+            let cell = Arc::new(AtomicWakerCell::new());
+            let (previous, _) = futures_test::task::new_count_waker();
+            let (waker, count) = futures_test::task::new_count_waker();
+
+            // The wake can take the previous Waker out, before the new one is registered
+            cell.register(&previous);
+
+            let seen = register_concurrent_signal(cell, &waker);
+
+            assert!(seen || count.get() == 1);
+        });
+    }
+}